@@ -0,0 +1,46 @@
+//! Compute the coefficients by optimizing the distributions instead of converting a published file.
+
+use std::{fs::File, io::BufWriter, ops::RangeInclusive, path::Path};
+
+use rayon::prelude::*;
+
+/// Optimize the distribution of each order and write them as JSON in the same format as `iltcme.json`.
+///
+/// The orders are optimized in parallel and reported as soon as they're finished.
+pub fn compute(orders: RangeInclusive<usize>, output: &Path) {
+    let params = orders
+        .into_par_iter()
+        .map(|n| {
+            let params = iltcme::coefficients::optimize(n);
+            println!("{n:>6} {:>12.4e}", params.cv2);
+
+            params
+        })
+        .collect::<Vec<_>>();
+
+    let out = BufWriter::new(File::create(output).unwrap());
+    serde_json::to_writer(out, &params).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use iltcme::coefficients::CmeTable;
+
+    use super::*;
+
+    #[test]
+    fn orders() {
+        let path = std::env::temp_dir().join(format!("compute-{}.json", std::process::id()));
+        compute(1..=3, &path);
+
+        // Readable as coefficients, in order and steeper with each order
+        let table = CmeTable::from_reader(File::open(&path).unwrap()).unwrap();
+        assert_eq!(table.len(), 3);
+        let cv2 = (2..=4)
+            .map(|max| table.order(max).unwrap().cv2())
+            .collect::<Vec<_>>();
+        assert!(cv2.windows(2).all(|cv2| cv2[1] < cv2[0]), "{cv2:?}");
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
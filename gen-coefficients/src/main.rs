@@ -1,33 +1,107 @@
-//! Generate a Rust source file with all parameters as proper structs.
+//! Generate a compressed binary blob, a C header or tables with the parameters used for each maximum of function evaluations.
+
+mod compute;
+mod validate;
 
 use std::{
+    collections::BTreeMap,
+    fmt::Debug,
     fs::File,
     io::{BufWriter, Write},
     path::PathBuf,
+    process::ExitCode,
+    sync::mpsc,
 };
 
-use clap::Parser;
+use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand, ValueEnum};
+use rayon::prelude::*;
 use serde::Deserialize;
 use serde_json::value::RawValue;
 
-/// Convert coefficients from JSON to Rust files.
+/// Convert coefficients from JSON to a compressed binary blob, a C header, CSV, NumPy arrays or Rust files.
 #[derive(Parser)]
-#[command(author, version, about)]
+#[command(
+    author,
+    version,
+    about,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub generate: Args,
+}
+
+/// Other tasks than converting the coefficients.
+#[derive(Subcommand)]
+enum Command {
+    /// Invert test functions with every distribution and report the maximum errors.
+    Validate {
+        /// JSON coefficients file path.
+        #[arg(short, long)]
+        input: PathBuf,
+    },
+    /// Compute the steepest distributions by optimization and write them as JSON.
+    ///
+    /// The output has the same format as `iltcme.json` and can be used as input to convert.
+    Compute {
+        /// Lowest order to compute, at least one.
+        #[arg(long, default_value_t = 1)]
+        min_order: usize,
+        /// Highest order to compute.
+        #[arg(long)]
+        max_order: usize,
+        /// JSON coefficients file path.
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+}
+
+/// Arguments for converting the coefficients.
+#[derive(clap::Args)]
 struct Args {
     /// JSON coefficients file path.
-    #[arg(short, long)]
-    pub input: PathBuf,
-    /// Output Rust source code file path.
-    #[arg(short, long)]
-    pub output: PathBuf,
+    #[arg(short, long, required = true)]
+    pub input: Option<PathBuf>,
+    /// Output coefficient file path, or Rust source code file path when exporting raw coefficients.
+    #[arg(short, long, required = true)]
+    pub output: Option<PathBuf>,
     /// Max evaluations to calculate.
     #[arg(short, long, default_value_t = 500)]
     pub max_evaluations: usize,
-    /// Export the raw coefficients instead of precalculated values.
-    #[arg(short, long)]
+    /// Format of the output file.
+    #[arg(short, long, value_enum, default_value_t = Format::Blob)]
+    pub format: Format,
+    /// Only keep the distributions used for these maximums of function evaluations, separated by commas.
+    ///
+    /// Other maximums use the steepest of the kept distributions, which gives a small table when only a few orders are needed.
+    #[arg(long, value_delimiter = ',', num_args = 1..)]
+    pub orders: Vec<usize>,
+    /// Write single precision values, each correctly rounded from the double precision value.
+    ///
+    /// Not available for the blob, which the crate only reads in double precision.
+    #[arg(long)]
+    pub f32: bool,
+    /// Export the raw coefficients as Rust source code instead of a blob.
+    #[arg(short, long, conflicts_with_all = ["format", "f32", "orders"])]
     pub raw: bool,
 }
 
+/// Output format of the distributions used for each maximum of function evaluations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Compressed binary blob embedded in the crate.
+    Blob,
+    /// C header with the weights and nodes of each distribution.
+    CHeader,
+    /// CSV with a row for each weight and node of each distribution.
+    Csv,
+    /// NumPy array with the same rows and columns as the CSV.
+    Npy,
+}
+
 #[derive(Debug, Deserialize)]
 struct ParsedParam {
     pub n: usize,
@@ -56,80 +130,331 @@ struct RawParam<'a> {
     pub cv2: &'a RawValue,
 }
 
-/// Convert all ILTCME values to eta and beta complex pairs.
-fn generate_precomputed<W>(json: &str, out: &mut BufWriter<W>, max_evaluations: usize)
+/// Indices of the steepest distribution satisfying N for each maximum of function evaluations up to and including `max_evaluations`.
+///
+/// When `orders` isn't empty only the distributions used for those maximums of function evaluations are candidates.
+fn steepest(params: &[ParsedParam], max_evaluations: usize, orders: &[usize]) -> Vec<usize> {
+    let all = (0..=max_evaluations)
+        .into_par_iter()
+        .map(|index| {
+            let mut steepest = 0;
+            for (i, param) in params.iter().enumerate().skip(1) {
+                if param.n < index && param.cv2 < params[steepest].cv2 {
+                    steepest = i;
+                }
+            }
+
+            steepest
+        })
+        .collect::<Vec<_>>();
+    if orders.is_empty() {
+        return all;
+    }
+
+    // The lowest selected order is used when none satisfies N
+    let mut selected = used(&orders.iter().map(|order| all[*order]).collect::<Vec<_>>());
+    selected.sort_by_key(|i| params[*i].n);
+    (0..=max_evaluations)
+        .into_par_iter()
+        .map(|index| {
+            let mut steepest = selected[0];
+            for i in selected.iter().skip(1) {
+                if params[*i].n < index && params[*i].cv2 < params[steepest].cv2 {
+                    steepest = *i;
+                }
+            }
+
+            steepest
+        })
+        .collect()
+}
+
+/// Indices of the distributions that are used at all, each only once.
+fn used(steepest: &[usize]) -> Vec<usize> {
+    let mut used = steepest.to_vec();
+    used.sort_unstable();
+    used.dedup();
+
+    used
+}
+
+/// Format each of `count` distributions on the thread pool and write them in order, each as soon as it and the ones before it are formatted.
+///
+/// Only the distributions that finished ahead of an earlier one are kept in memory.
+fn write_ordered<W>(out: &mut W, count: usize, format: impl Fn(usize) -> Vec<u8> + Sync)
 where
     W: Write,
 {
-    // Read the json file
-    let params: Vec<ParsedParam> = serde_json::from_str(json).unwrap();
+    let (sender, receiver) = mpsc::channel();
+    std::thread::scope(|scope| {
+        // Formatting on a separate thread, so the writer doesn't block a worker of the pool
+        scope.spawn(|| {
+            (0..count)
+                .into_par_iter()
+                .for_each_with(sender, |sender, i| sender.send((i, format(i))).unwrap());
+        });
+
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        for (i, bytes) in receiver {
+            pending.insert(i, bytes);
+            while let Some(bytes) = pending.remove(&next) {
+                out.write_all(&bytes).unwrap();
+                next += 1;
+            }
+        }
+    });
+}
+
+/// Write the distributions used for each maximum of function evaluations as a compressed binary blob.
+///
+/// Each distribution is written once even when it's the steepest for many maximums, the crate selects the distribution for each maximum when decoding.
+/// The distributions are encoded as they're finished, but the blob is a single zstd frame which is compressed and written at the end.
+///
+/// The blob is a zstd frame containing the little-endian number of distributions as a `u32`, followed by each distribution as its order `n` as a `u32`, `c`, `omega`, `mu1` and `cv2` as `f64`, then `n` values of `a` and `n` values of `b` as `f64`.
+fn generate_blob<W>(params: &[ParsedParam], steepest: &[usize], out: &mut W)
+where
+    W: Write,
+{
+    let used = used(steepest);
+
+    let mut blob = Vec::new();
+    blob.extend((used.len() as u32).to_le_bytes());
+    write_ordered(&mut blob, used.len(), |j| {
+        let param = &params[used[j]];
+        let mut bytes = Vec::new();
+        bytes.extend((param.n as u32).to_le_bytes());
+        for value in [param.c, param.omega, param.mu1, param.cv2]
+            .iter()
+            .chain(&param.a)
+            .chain(&param.b)
+        {
+            bytes.extend(value.to_le_bytes());
+        }
+
+        bytes
+    });
+
+    out.write_all(&ruzstd::encoding::compress_to_vec(
+        blob.as_slice(),
+        ruzstd::encoding::CompressionLevel::Fastest,
+    ))
+    .unwrap();
+}
+
+/// Write the weights and nodes of the distributions used for each maximum of function evaluations as a C header.
+///
+/// The inverse is $f(t) \approx \frac{1}{t} \sum_k \operatorname{Re}(\eta_k F(\beta_k / t))$ where all nodes have the real part `mu1`.
+/// With `single` the values are `float` instead of `double`, rounded after computing the weights and nodes.
+fn generate_c_header<W>(params: &[ParsedParam], steepest: &[usize], out: &mut W, single: bool)
+where
+    W: Write,
+{
+    let max_evaluations = steepest.len() - 1;
+    let used = used(steepest);
+    let real = if single { "float" } else { "double" };
 
-    // Re-export the maximum function evaluations
     writeln!(
         out,
-        "pub(crate) const MAX_EVALUATIONS: usize = {max_evaluations};"
+        "/* Auto-generated coefficient file, don't edit. */
+
+#ifndef ILTCME_COEFFICIENTS_H
+#define ILTCME_COEFFICIENTS_H
+
+#include <stddef.h>
+
+/* Floating point type of the coefficients. */
+typedef {real} iltcme_real;
+
+/* Largest maximum number of function evaluations. */
+#define ILTCME_MAX_EVALUATIONS {max_evaluations}
+
+/*
+ * Weights and nodes of a concentrated matrix-exponential distribution.
+ *
+ * The inverse is f(t) = sum_k Re(eta_k F(beta_k / t)) / t over the `evaluations` nodes,
+ * where the real part of every node beta_k is `mu1`.
+ */
+typedef struct {{
+    size_t n;
+    size_t evaluations;
+    iltcme_real mu1;
+    iltcme_real cv2;
+    const iltcme_real *eta_re;
+    const iltcme_real *eta_im;
+    const iltcme_real *beta_im;
+}} iltcme_order;
+"
     )
     .unwrap();
-    // Create a lookup list for each iteration
+
+    // Format the arrays of each distribution in parallel, and write them in order
+    write_ordered(out, used.len(), |j| {
+        let param = &params[used[j]];
+        let n = param.n;
+        let [eta_re, eta_im, beta_im] = nodes(param);
+
+        let mut source = Vec::new();
+        for (name, values) in [("ETA_RE", eta_re), ("ETA_IM", eta_im), ("BETA_IM", beta_im)] {
+            write!(
+                source,
+                "static const iltcme_real ILTCME_{name}_{n}[{}] = {{",
+                n + 1
+            )
+            .unwrap();
+            write_c_reals(&mut source, &values, single);
+            writeln!(source, "}};").unwrap();
+        }
+
+        source
+    });
+
+    writeln!(
+        out,
+        "\n/* Distributions sorted by order. */\nstatic const iltcme_order ILTCME_ORDERS[{}] = {{",
+        used.len()
+    )
+    .unwrap();
+    for param in used.iter().map(|i| &params[*i]) {
+        let n = param.n;
+        write!(out, "    {{{n}, {}, ", n + 1).unwrap();
+        write_c_reals(out, &[param.mu1, param.cv2], single);
+        writeln!(
+            out,
+            ", ILTCME_ETA_RE_{n}, ILTCME_ETA_IM_{n}, ILTCME_BETA_IM_{n}}},"
+        )
+        .unwrap();
+    }
+    writeln!(out, "}};").unwrap();
+
+    // Position in the used distributions for each maximum of function evaluations
+    let index = steepest
+        .iter()
+        .map(|i| used.binary_search(i).unwrap())
+        .collect::<Vec<_>>();
     write!(
         out,
-        "pub(crate) const ETA_BETA_PAIRS: [(f64, &[(f64, f64, f64)], f64); {max_evaluations}] = ["
+        "\n/* Index in ILTCME_ORDERS of the steepest distribution for each maximum number of function evaluations. */\nstatic const size_t ILTCME_ORDER_INDEX[{}] = {{",
+        index.len()
     )
     .unwrap();
+    write_c_array(out, &index, "");
+    writeln!(out, "}};\n\n#endif /* ILTCME_COEFFICIENTS_H */").unwrap();
+}
 
-    // Calculate the etas and betas for each maximum of function evaluations
-    let mut consts = String::new();
-    (0..max_evaluations).for_each(|index| {
-        // Find the steepest CME satisfying N
-        let mut steepest = &params[0];
-        for param in params.iter().skip(1).filter(|param| param.n < index) {
-            if param.cv2 < steepest.cv2 {
-                steepest = param;
+/// Real and imaginary parts of the weights and imaginary parts of the nodes, the real part of all nodes is `mu1`.
+fn nodes(param: &ParsedParam) -> [Vec<f64>; 3] {
+    let eta_re = std::iter::once(param.c * param.mu1)
+        .chain(param.a.iter().map(|a| a * param.mu1))
+        .collect();
+    let eta_im = std::iter::once(0.0)
+        .chain(param.b.iter().map(|b| b * param.mu1))
+        .collect();
+    let beta_im = (0..=param.n)
+        .map(|k| k as f64 * param.omega * param.mu1)
+        .collect();
+
+    [eta_re, eta_im, beta_im]
+}
+
+/// Rows of the order, node index, weight and node of a distribution, for the tabular formats.
+fn rows(param: &ParsedParam) -> impl Iterator<Item = [f64; 6]> + '_ {
+    let [eta_re, eta_im, beta_im] = nodes(param);
+
+    (0..=param.n).map(move |k| {
+        [
+            param.n as f64,
+            k as f64,
+            eta_re[k],
+            eta_im[k],
+            param.mu1,
+            beta_im[k],
+        ]
+    })
+}
+
+/// Column names of [`rows`].
+const COLUMNS: [&str; 6] = ["n", "k", "eta_re", "eta_im", "beta_re", "beta_im"];
+
+/// Write the weights and nodes of the distributions used as CSV, with a header and a row for each node.
+fn generate_csv<W>(params: &[ParsedParam], steepest: &[usize], out: &mut W, single: bool)
+where
+    W: Write,
+{
+    let used = used(steepest);
+
+    writeln!(out, "{}", COLUMNS.join(",")).unwrap();
+    write_ordered(out, used.len(), |j| {
+        let mut lines = Vec::new();
+        for row in rows(&params[used[j]]) {
+            // The order and node index are integers
+            write!(lines, "{},{}", row[0], row[1]).unwrap();
+            for value in &row[2..] {
+                if single {
+                    write!(lines, ",{:?}", *value as f32).unwrap();
+                } else {
+                    write!(lines, ",{value:?}").unwrap();
+                }
             }
+            writeln!(lines).unwrap();
         }
 
-        let eta = steepest
-            .a
-            .iter()
-            .zip(steepest.b.iter())
-            .map(|(a, b)| (steepest.mu1 * a, steepest.mu1 * b));
-        let beta = (0..steepest.n).map(|i| ((i + 1) as f64) * steepest.omega * steepest.mu1);
-
-        let eta_betas = eta.zip(beta).collect::<Vec<_>>();
-        consts += &format!(
-            "const E{index:X}:[(f64,f64,f64);{}]={};\n",
-            eta_betas.len(),
-            fmt_vec(&eta_betas)
-        );
-        write!(
-            out,
-            "({},&E{index:X},{}),",
-            fmt_f64(steepest.mu1),
-            fmt_f64(steepest.c * steepest.mu1),
-        )
-        .unwrap();
+        lines
     });
-
-    writeln!(out, "];\n{consts}").unwrap();
 }
 
-fn fmt_vec(v: &[((f64, f64), f64)]) -> String {
-    format!(
-        "[{}]",
-        v.iter()
-            .map(|((v1, v2), v3)| format!("({},{},{})", fmt_f64(*v1), fmt_f64(*v2), fmt_f64(*v3)))
-            .collect::<Vec<String>>()
-            .join(",")
-    )
+/// Write the weights and nodes of the distributions used as a NumPy `.npy` array with the columns of the CSV.
+fn generate_npy<W>(params: &[ParsedParam], steepest: &[usize], out: &mut W, single: bool)
+where
+    W: Write,
+{
+    let used = used(steepest);
+    // A row for each node of each distribution
+    let len = used.iter().map(|i| params[*i].n + 1).sum::<usize>();
+    let descr = if single { "<f4" } else { "<f8" };
+    let mut header = format!(
+        "{{'descr': '{descr}', 'fortran_order': False, 'shape': ({len}, {}), }}",
+        COLUMNS.len()
+    );
+    // Pad so the data is aligned to 64 bytes, after the magic string, version and header length
+    let unpadded = 10 + header.len() + 1;
+    header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+    header.push('\n');
+
+    out.write_all(b"\x93NUMPY\x01\x00").unwrap();
+    out.write_all(&(header.len() as u16).to_le_bytes()).unwrap();
+    out.write_all(header.as_bytes()).unwrap();
+    write_ordered(out, used.len(), |j| {
+        let mut bytes = Vec::new();
+        for value in rows(&params[used[j]]).flatten() {
+            if single {
+                bytes.extend((value as f32).to_le_bytes());
+            } else {
+                bytes.extend(value.to_le_bytes());
+            }
+        }
+
+        bytes
+    });
 }
 
-/// Properly print floats so Rust source code can parse them.
-fn fmt_f64(v: f64) -> String {
-    if v.fract() == 0.0 {
-        // Always print as a float
-        format!("{v}.")
+/// Write comma separated floating point literals that round-trip exactly, as `float` literals when `single`.
+fn write_c_reals(out: &mut impl Write, values: &[f64], single: bool) {
+    if single {
+        let values = values.iter().map(|v| *v as f32).collect::<Vec<_>>();
+        write_c_array(out, &values, "f");
     } else {
-        format!("{v}")
+        write_c_array(out, values, "");
+    }
+}
+
+/// Write comma separated values with a suffix, floats are written so they round-trip exactly.
+fn write_c_array(out: &mut impl Write, values: &[impl Debug], suffix: &str) {
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            write!(out, ", ").unwrap();
+        }
+        write!(out, "{value:?}{suffix}").unwrap();
     }
 }
 
@@ -172,20 +497,186 @@ fn write_raw_vec(s: &mut impl Write, v: &[&RawValue]) {
     write!(s, "]").unwrap();
 }
 
-fn main() {
-    let args = Args::parse();
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let args = match cli.command {
+        Some(Command::Validate { input }) => {
+            return if validate::validate(&input) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            };
+        }
+        Some(Command::Compute {
+            min_order,
+            max_order,
+            output,
+        }) => {
+            compute::compute(min_order.max(1)..=max_order, &output);
 
-    let file = File::create(args.output).unwrap();
-    let mut out = BufWriter::new(file);
+            return ExitCode::SUCCESS;
+        }
+        None => cli.generate,
+    };
+    if args.f32 && args.format == Format::Blob {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "`--f32` can't be used with the blob format, the crate only reads double precision blobs",
+            )
+            .exit();
+    }
 
-    writeln!(out, "//! Auto-generated coefficient file, don't edit.\n").unwrap();
-    writeln!(out, "#![cfg_attr(rustfmt, rustfmt_skip)]").unwrap();
-    writeln!(out, "#[allow(clippy::all)]").unwrap();
+    // Required unless a subcommand is used
+    let file = File::create(args.output.unwrap()).unwrap();
+    let mut out = BufWriter::new(file);
 
-    let json = std::fs::read_to_string(args.input).unwrap();
+    let json = std::fs::read_to_string(args.input.unwrap()).unwrap();
     if args.raw {
+        writeln!(out, "//! Auto-generated coefficient file, don't edit.\n").unwrap();
+        writeln!(out, "#![cfg_attr(rustfmt, rustfmt_skip)]").unwrap();
+        writeln!(out, "#![allow(clippy::all)]").unwrap();
+
         generate_raw(&json, &mut out);
-    } else {
-        generate_precomputed(&json, &mut out, args.max_evaluations);
+        return ExitCode::SUCCESS;
+    }
+
+    // Read the json file
+    let params: Vec<ParsedParam> = serde_json::from_str(&json).unwrap();
+    assert!(
+        args.orders
+            .iter()
+            .all(|order| *order <= args.max_evaluations),
+        "Selected orders must be less or equal to the max evaluations"
+    );
+    let steepest = steepest(&params, args.max_evaluations, &args.orders);
+    match args.format {
+        Format::Blob => generate_blob(&params, &steepest, &mut out),
+        Format::CHeader => generate_c_header(&params, &steepest, &mut out, args.f32),
+        Format::Csv => generate_csv(&params, &steepest, &mut out, args.f32),
+        Format::Npy => generate_npy(&params, &steepest, &mut out, args.f32),
+    }
+
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    /// Distributions of the published file and the steepest ones for up to 20 evaluations.
+    fn published() -> (Vec<ParsedParam>, Vec<usize>) {
+        let params: Vec<ParsedParam> =
+            serde_json::from_str(include_str!("../../iltcme.json")).unwrap();
+        let steepest = steepest(&params, 20, &[]);
+
+        (params, steepest)
+    }
+
+    #[test]
+    fn blob() {
+        let (params, steepest) = published();
+        let mut compressed = Vec::new();
+        generate_blob(&params, &steepest, &mut compressed);
+
+        let mut bytes = Vec::new();
+        ruzstd::decoding::StreamingDecoder::new(compressed.as_slice())
+            .unwrap()
+            .read_to_end(&mut bytes)
+            .unwrap();
+        let used = used(&steepest);
+        assert_eq!(bytes[..4], (used.len() as u32).to_le_bytes());
+        // Order, four parameters and both coefficients of each distribution
+        let len = used
+            .iter()
+            .map(|i| 4 + 8 * (4 + 2 * params[*i].n))
+            .sum::<usize>();
+        assert_eq!(bytes.len(), 4 + len);
+    }
+
+    #[test]
+    fn c_header() {
+        let (params, steepest) = published();
+        let mut header = Vec::new();
+        generate_c_header(&params, &steepest, &mut header, false);
+        let header = String::from_utf8(header).unwrap();
+
+        assert!(header.contains("#define ILTCME_MAX_EVALUATIONS 20"));
+        assert!(header.contains("static const size_t ILTCME_ORDER_INDEX[21]"));
+        for i in used(&steepest) {
+            let param = &params[i];
+            let [eta_re, _, _] = nodes(param);
+            // Values are written so they round-trip exactly
+            let prefix = format!(
+                "static const iltcme_real ILTCME_ETA_RE_{}[{}] = {{",
+                param.n,
+                param.n + 1
+            );
+            let line = header.lines().find(|line| line.starts_with(&prefix));
+            let values = line.unwrap()[prefix.len()..]
+                .trim_end_matches("};")
+                .split(", ")
+                .map(|value| value.parse::<f64>().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(values, eta_re);
+        }
+
+        let mut single = Vec::new();
+        generate_c_header(&params, &steepest, &mut single, true);
+        assert!(String::from_utf8(single)
+            .unwrap()
+            .contains("typedef float iltcme_real;"));
+    }
+
+    #[test]
+    fn csv() {
+        let (params, steepest) = published();
+        let mut csv = Vec::new();
+        generate_csv(&params, &steepest, &mut csv, false);
+        let csv = String::from_utf8(csv).unwrap();
+
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("n,k,eta_re,eta_im,beta_re,beta_im"));
+        let parsed = lines
+            .map(|line| {
+                line.split(',')
+                    .map(|value| value.parse::<f64>().unwrap())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let expected = used(&steepest)
+            .into_iter()
+            .flat_map(|i| rows(&params[i]))
+            .collect::<Vec<_>>();
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn npy() {
+        let (params, steepest) = published();
+        let mut npy = Vec::new();
+        generate_npy(&params, &steepest, &mut npy, false);
+
+        let expected = used(&steepest)
+            .into_iter()
+            .flat_map(|i| rows(&params[i]))
+            .collect::<Vec<_>>();
+        assert_eq!(npy[..8], *b"\x93NUMPY\x01\x00");
+        let header_len = u16::from_le_bytes([npy[8], npy[9]]) as usize;
+        // The data is aligned to 64 bytes
+        assert_eq!((10 + header_len) % 64, 0);
+        let header = std::str::from_utf8(&npy[10..10 + header_len]).unwrap();
+        assert!(header.contains(&format!("'shape': ({}, 6)", expected.len())));
+        let values = npy[10 + header_len..]
+            .chunks_exact(8)
+            .map(|bytes| f64::from_le_bytes(bytes.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(values, expected.concat());
+
+        let mut single = Vec::new();
+        generate_npy(&params, &steepest, &mut single, true);
+        assert_eq!(single.len(), 10 + header_len + 4 * values.len());
     }
 }
@@ -0,0 +1,118 @@
+//! Vet coefficient files by inverting test functions with known inverses.
+
+use std::path::Path;
+
+use iltcme::coefficients::{CmeParams, CmeTable};
+use rayon::prelude::*;
+
+/// Times at which the test functions are inverted, halfway between the jumps of the staircase.
+const TIMES: [f64; 20] = [
+    0.25, 0.75, 1.25, 1.75, 2.25, 2.75, 3.25, 3.75, 4.25, 4.75, 5.25, 5.75, 6.25, 6.75, 7.25, 7.75,
+    8.25, 8.75, 9.25, 9.75,
+];
+
+/// Names of the test functions in the order of [`max_errors`].
+const NAMES: [&str; 3] = ["exponential", "sine", "staircase"];
+
+/// Invert the test functions with every distribution in the file and print the maximum absolute errors.
+///
+/// Returns whether all distributions are valid and all errors are finite.
+pub fn validate(input: &Path) -> bool {
+    let json = std::fs::read_to_string(input).unwrap();
+    let mut params: Vec<CmeParams> = serde_json::from_str(&json).unwrap();
+    params.sort_by_key(|param| param.n);
+
+    println!(
+        "{:>6} {:>12} {:>12} {:>12} {:>12}",
+        "n", "cv2", NAMES[0], NAMES[1], NAMES[2]
+    );
+
+    // Invert with every distribution in parallel, reporting them in order
+    let results = params
+        .into_par_iter()
+        .map(|param| {
+            let (n, cv2) = (param.n, param.cv2);
+            let errors = (param.a.len() == n && param.b.len() == n)
+                .then(|| max_errors(&CmeTable::new(vec![param])));
+
+            (n, cv2, errors)
+        })
+        .collect::<Vec<_>>();
+
+    let mut valid = true;
+    let mut worst = [0.0_f64; 3];
+    for (n, cv2, errors) in results {
+        let Some(errors) = errors else {
+            println!("{n:>6} {cv2:>12.4e} must have {n} cosine and sine coefficients");
+            valid = false;
+            continue;
+        };
+
+        println!(
+            "{n:>6} {cv2:>12.4e} {:>12.4e} {:>12.4e} {:>12.4e}",
+            errors[0], errors[1], errors[2]
+        );
+
+        for (worst, error) in worst.iter_mut().zip(errors) {
+            if !error.is_finite() {
+                valid = false;
+            }
+            *worst = worst.max(error);
+        }
+    }
+
+    println!(
+        "{:>6} {:>12} {:>12.4e} {:>12.4e} {:>12.4e}",
+        "max", "", worst[0], worst[1], worst[2]
+    );
+
+    valid
+}
+
+/// Maximum absolute errors of inverting the exponential, sine and staircase functions with the single distribution in the table.
+fn max_errors(table: &CmeTable) -> [f64; 3] {
+    let order = table
+        .order(table.max_evaluations())
+        .expect("Table contains a distribution");
+
+    let mut errors = [0.0_f64; 3];
+    for t in TIMES {
+        let exponential = order.invert(|s| 1.0 / (s + 1.0), t) - (-t).exp();
+        let sine = order.invert(|s| 1.0 / (s * s + 1.0), t) - t.sin();
+        // Written with decaying exponentials to avoid overflow for large nodes
+        let staircase = order.invert(|s| (-s).exp() / (s * (1.0 - (-s).exp())), t) - t.floor();
+
+        for (error, difference) in errors.iter_mut().zip([exponential, sine, staircase]) {
+            // Keep NaN once it occurs
+            *error = if difference.is_nan() || error.is_nan() {
+                f64::NAN
+            } else {
+                error.max(difference.abs())
+            };
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn published() {
+        let path = std::env::temp_dir().join(format!("validate-{}.json", std::process::id()));
+        let mut params: Vec<serde_json::Value> =
+            serde_json::from_str(include_str!("../../iltcme.json")).unwrap();
+        params.truncate(10);
+        std::fs::write(&path, serde_json::to_string(&params).unwrap()).unwrap();
+        assert!(validate(&path));
+
+        // A distribution without a coefficient for each order
+        params[3]["a"].as_array_mut().unwrap().pop();
+        std::fs::write(&path, serde_json::to_string(&params).unwrap()).unwrap();
+        assert!(!validate(&path));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
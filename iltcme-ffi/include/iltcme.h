@@ -0,0 +1,95 @@
+/* Inverse Laplace transform with concentrated matrix-exponential functions. */
+
+#ifndef ILTCME_H
+#define ILTCME_H
+
+/* Generated with cbindgen from src/lib.rs, don't edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+
+
+
+
+/**
+ * The inversion succeeded.
+ */
+#define ILTCME_OK 0
+
+/**
+ * More function evaluations were requested than there are coefficients for.
+ */
+#define ILTCME_TOO_MANY_EVALUATIONS 1
+
+/**
+ * The time is zero, negative or NaN.
+ */
+#define ILTCME_NON_POSITIVE_TIME 2
+
+/**
+ * The transform function returned NaN or infinity.
+ */
+#define ILTCME_NON_FINITE_TRANSFORM 3
+
+/**
+ * A required pointer is null.
+ */
+#define ILTCME_NULL_POINTER 4
+
+/**
+ * The width of the smoothing window is negative or NaN.
+ */
+#define ILTCME_INVALID_SMOOTHING 5
+
+/**
+ * Complex number passed to and returned from the transform function.
+ */
+typedef struct IltcmeComplex {
+  /**
+   * Real part.
+   */
+  double re;
+  /**
+   * Imaginary part.
+   */
+  double im;
+} IltcmeComplex;
+
+/**
+ * Laplace transform function, called with the point `s` and the user data passed to [`iltcme_invert`].
+ */
+typedef struct IltcmeComplex (*IltcmeTransform)(struct IltcmeComplex s, void *user_data);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Calculate the Laplace inversion of `func` at time `t` with at most `max_evals` evaluations and write it to `out`.
+ *
+ * Returns [`ILTCME_OK`] on success, or one of the other status codes in which case `out` isn't written.
+ *
+ * # Safety
+ *
+ * `func` must be safe to call with `user_data`, and `out` must be null or point to a writable `double`.
+ */
+int iltcme_invert(IltcmeTransform func,
+                  void *user_data,
+                  double t,
+                  size_t max_evals,
+                  double *out);
+
+/**
+ * Largest maximum number of function evaluations accepted by [`iltcme_invert`].
+ */
+size_t iltcme_max_evaluations(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ILTCME_H */
@@ -0,0 +1,155 @@
+//! C interface of `iltcme` for calling the inversion from C, C++ or Fortran.
+//!
+//! Build the shared or static library with `cargo build --release -p iltcme-ffi` and include `include/iltcme.h`.
+//!
+//! ```c
+//! #include "iltcme.h"
+//!
+//! static IltcmeComplex sine(IltcmeComplex s, void *user_data) {
+//!     // 1 / (s^2 + 1)
+//!     double re = s.re * s.re - s.im * s.im + 1.0, im = 2.0 * s.re * s.im;
+//!     double norm = re * re + im * im;
+//!     return (IltcmeComplex){re / norm, -im / norm};
+//! }
+//!
+//! double result;
+//! if (iltcme_invert(sine, NULL, 1.0, 50, &result) != ILTCME_OK) { /* handle the error */ }
+//! ```
+
+use std::ffi::{c_int, c_void};
+
+use nalgebra::Complex;
+
+use iltcme::{coefficients, CmeInverter, Error};
+
+/// The inversion succeeded.
+pub const ILTCME_OK: c_int = 0;
+/// More function evaluations were requested than there are coefficients for.
+pub const ILTCME_TOO_MANY_EVALUATIONS: c_int = 1;
+/// The time is zero, negative or NaN.
+pub const ILTCME_NON_POSITIVE_TIME: c_int = 2;
+/// The transform function returned NaN or infinity.
+pub const ILTCME_NON_FINITE_TRANSFORM: c_int = 3;
+/// A required pointer is null.
+pub const ILTCME_NULL_POINTER: c_int = 4;
+/// The width of the smoothing window is negative or NaN.
+pub const ILTCME_INVALID_SMOOTHING: c_int = 5;
+
+/// Complex number passed to and returned from the transform function.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IltcmeComplex {
+    /// Real part.
+    pub re: f64,
+    /// Imaginary part.
+    pub im: f64,
+}
+
+/// Laplace transform function, called with the point `s` and the user data passed to [`iltcme_invert`].
+pub type IltcmeTransform =
+    Option<unsafe extern "C" fn(s: IltcmeComplex, user_data: *mut c_void) -> IltcmeComplex>;
+
+/// Calculate the Laplace inversion of `func` at time `t` with at most `max_evals` evaluations and write it to `out`.
+///
+/// Returns [`ILTCME_OK`] on success, or one of the other status codes in which case `out` isn't written.
+///
+/// # Safety
+///
+/// `func` must be safe to call with `user_data`, and `out` must be null or point to a writable `double`.
+#[no_mangle]
+pub unsafe extern "C" fn iltcme_invert(
+    func: IltcmeTransform,
+    user_data: *mut c_void,
+    t: f64,
+    max_evals: usize,
+    out: *mut f64,
+) -> c_int {
+    let Some(func) = func else {
+        return ILTCME_NULL_POINTER;
+    };
+    if out.is_null() {
+        return ILTCME_NULL_POINTER;
+    }
+
+    let result = CmeInverter::new(max_evals).and_then(|inverter| {
+        inverter.invert(
+            |s| {
+                // SAFETY: the caller guarantees the function can be called with the user data
+                let value = unsafe { func(IltcmeComplex { re: s.re, im: s.im }, user_data) };
+                Complex::new(value.re, value.im)
+            },
+            t,
+        )
+    });
+
+    match result {
+        Ok(value) => {
+            // SAFETY: checked for null, the caller guarantees it's writable
+            unsafe { out.write(value) };
+            ILTCME_OK
+        }
+        Err(Error::TooManyEvaluations { .. }) => ILTCME_TOO_MANY_EVALUATIONS,
+        Err(Error::NonPositiveTime(_)) => ILTCME_NON_POSITIVE_TIME,
+        Err(Error::NonFiniteTransform { .. }) => ILTCME_NON_FINITE_TRANSFORM,
+        Err(Error::InvalidSmoothing(_)) => ILTCME_INVALID_SMOOTHING,
+    }
+}
+
+/// Largest maximum number of function evaluations accepted by [`iltcme_invert`].
+#[no_mangle]
+pub extern "C" fn iltcme_max_evaluations() -> usize {
+    coefficients::max_evaluations()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use super::*;
+
+    unsafe extern "C" fn exponential(s: IltcmeComplex, user_data: *mut c_void) -> IltcmeComplex {
+        // SAFETY: the tests pass a pointer to the rate
+        let rate = unsafe { *(user_data as *const f64) };
+        let value = 1.0 / (Complex::new(s.re, s.im) + rate);
+
+        IltcmeComplex {
+            re: value.re,
+            im: value.im,
+        }
+    }
+
+    #[test]
+    fn invert() {
+        let mut rate = 2.0;
+        let user_data = &mut rate as *mut f64 as *mut c_void;
+        let mut out = f64::NAN;
+
+        let status = unsafe { iltcme_invert(Some(exponential), user_data, 1.0, 50, &mut out) };
+        assert_eq!(status, ILTCME_OK);
+        assert!(approx::relative_eq!(out, (-2.0_f64).exp(), epsilon = 1e-3));
+
+        let statuses = unsafe {
+            [
+                iltcme_invert(Some(exponential), user_data, -1.0, 50, &mut out),
+                iltcme_invert(
+                    Some(exponential),
+                    user_data,
+                    1.0,
+                    iltcme_max_evaluations() + 1,
+                    &mut out,
+                ),
+                iltcme_invert(None, user_data, 1.0, 50, &mut out),
+                iltcme_invert(Some(exponential), user_data, 1.0, 50, ptr::null_mut()),
+            ]
+        };
+        assert_eq!(
+            statuses,
+            [
+                ILTCME_NON_POSITIVE_TIME,
+                ILTCME_TOO_MANY_EVALUATIONS,
+                ILTCME_NULL_POINTER,
+                ILTCME_NULL_POINTER
+            ]
+        );
+    }
+}
@@ -0,0 +1,275 @@
+//! Macro writing Laplace transform expressions as closures, re-exported by `iltcme` with the `macros` feature.
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// Complex type used in the expansion, re-exported by `iltcme`.
+const COMPLEX: &str = "::iltcme::__private::Complex::<f64>";
+
+/// Laplace transform expression in the variable `s` as a `Fn(Complex<f64>) -> Complex<f64>` closure.
+///
+/// Supports numbers, imaginary numbers like `2i` or `0.5i`, the imaginary unit `i`, the constants `pi` and `e`, the operators `+`, `-`, `*`, `/` and `^`, parentheses and the functions `exp`, `ln`, `sqrt`, `sin`, `cos`, `tan`, `sinh`, `cosh`, `tanh` and `atan`.
+/// Like in mathematical notation `^` is a right associative power that binds stronger than a sign, so `-s^2` is $-(s^2)$, and integer powers are exact.
+/// Other names are captured from the surrounding scope, by value, and must be an `f64` or a `Complex<f64>`.
+///
+/// See the re-export in `iltcme` for an example.
+#[proc_macro]
+pub fn laplace(input: TokenStream) -> TokenStream {
+    match Parser::new(input, Span::call_site()).parse() {
+        Ok(expr) => format!("move |s: {COMPLEX}| -> {COMPLEX} {{ {} }}", expr.code())
+            .parse()
+            .expect("Expansion is a valid closure"),
+        Err(error) => error.compile_error(),
+    }
+}
+
+/// Parsed transform expression.
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    /// Complex constant.
+    Constant { re: f64, im: f64 },
+    /// Real constant of `core::f64::consts`, named so lints don't flag its approximate value.
+    Named(&'static str),
+    /// The transform variable `s`.
+    Variable,
+    /// Value captured from the surrounding scope.
+    Captured(String),
+    /// Negation.
+    Neg(Box<Expr>),
+    /// Complex method applied to an argument.
+    Call(&'static str, Box<Expr>),
+    /// Binary operator applied to two operands.
+    Binary(char, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Rust code evaluating the expression for the complex `s`.
+    fn code(&self) -> String {
+        match self {
+            Self::Constant { re, im } => format!("{COMPLEX}::new({re:?}, {im:?})"),
+            Self::Named(name) => format!("{COMPLEX}::from(::core::f64::consts::{name})"),
+            Self::Variable => "s".to_string(),
+            Self::Captured(name) => format!("{COMPLEX}::from({name})"),
+            Self::Neg(expr) => format!("(-{})", expr.code()),
+            Self::Call(method, argument) => format!("{}.{method}()", argument.code()),
+            // Integer powers are exact and much faster
+            Self::Binary('^', base, exponent) => match exponent.integer() {
+                Some(exponent) => format!("{}.powi({exponent})", base.code()),
+                None => format!("{}.powc({})", base.code(), exponent.code()),
+            },
+            Self::Binary(operator, left, right) => {
+                format!("({} {operator} {})", left.code(), right.code())
+            }
+        }
+    }
+
+    /// Value of a real integer constant that fits in an `i32`.
+    fn integer(&self) -> Option<i32> {
+        match self {
+            Self::Constant { re, im } if *im == 0.0 && re.fract() == 0.0 => {
+                i32::try_from(*re as i64).ok()
+            }
+            Self::Neg(expr) => expr.integer().and_then(i32::checked_neg),
+            _ => None,
+        }
+    }
+}
+
+/// Error while parsing, reported at the span of the offending token.
+struct Error {
+    /// Span of the offending token.
+    span: Span,
+    /// What went wrong.
+    message: String,
+}
+
+impl Error {
+    /// Invocation of `compile_error!` with the message, at the span of the offending token.
+    fn compile_error(self) -> TokenStream {
+        let tokens = [
+            TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+            TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+            TokenTree::Ident(Ident::new("core", self.span)),
+            TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+            TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+            TokenTree::Ident(Ident::new("compile_error", self.span)),
+            TokenTree::Punct(Punct::new('!', Spacing::Alone)),
+            TokenTree::Group(Group::new(
+                Delimiter::Parenthesis,
+                TokenTree::Literal(Literal::string(&self.message)).into(),
+            )),
+        ];
+
+        tokens
+            .into_iter()
+            .map(|mut token| {
+                token.set_span(self.span);
+                token
+            })
+            .collect()
+    }
+}
+
+/// Recursive descent parser over the tokens of the macro input, each method parses one precedence level.
+struct Parser {
+    /// Tokens of the expression.
+    tokens: Vec<TokenTree>,
+    /// Index of the next token.
+    position: usize,
+    /// Span reported when the expression ends too early.
+    end: Span,
+}
+
+impl Parser {
+    /// Parse the tokens, `end` is the span of the end of the expression.
+    fn new(input: TokenStream, end: Span) -> Self {
+        Self {
+            tokens: input.into_iter().collect(),
+            position: 0,
+            end,
+        }
+    }
+
+    /// The whole expression.
+    fn parse(mut self) -> Result<Expr, Error> {
+        let expr = self.sum()?;
+
+        match self.tokens.get(self.position) {
+            None => Ok(expr),
+            Some(token) => Err(error(token.span(), format!("unexpected `{token}`"))),
+        }
+    }
+
+    /// Terms separated by `+` and `-`.
+    fn sum(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.product()?;
+        while let Some(operator) = self.next_punct(&['+', '-']) {
+            expr = Expr::Binary(operator, Box::new(expr), Box::new(self.product()?));
+        }
+
+        Ok(expr)
+    }
+
+    /// Factors separated by `*` and `/`.
+    fn product(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.unary()?;
+        while let Some(operator) = self.next_punct(&['*', '/']) {
+            expr = Expr::Binary(operator, Box::new(expr), Box::new(self.unary()?));
+        }
+
+        Ok(expr)
+    }
+
+    /// Signs, binding weaker than powers so `-s^2` is `-(s^2)`.
+    fn unary(&mut self) -> Result<Expr, Error> {
+        match self.next_punct(&['+', '-']) {
+            Some('-') => Ok(Expr::Neg(Box::new(self.unary()?))),
+            Some(_) => self.unary(),
+            None => self.power(),
+        }
+    }
+
+    /// Right associative powers.
+    fn power(&mut self) -> Result<Expr, Error> {
+        let base = self.atom()?;
+        if self.next_punct(&['^']).is_some() {
+            Ok(Expr::Binary('^', Box::new(base), Box::new(self.unary()?)))
+        } else {
+            Ok(base)
+        }
+    }
+
+    /// Numbers, names, function calls and parenthesized expressions.
+    fn atom(&mut self) -> Result<Expr, Error> {
+        let Some(token) = self.tokens.get(self.position).cloned() else {
+            return Err(error(self.end, "expected an expression".to_string()));
+        };
+        self.position += 1;
+
+        match token {
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis => {
+                parenthesized(&group)
+            }
+            TokenTree::Literal(literal) => number(&literal),
+            TokenTree::Ident(ident) => {
+                let name = ident.to_string();
+                let argument = match self.tokens.get(self.position) {
+                    Some(TokenTree::Group(group))
+                        if group.delimiter() == Delimiter::Parenthesis =>
+                    {
+                        self.position += 1;
+                        parenthesized(group)?
+                    }
+                    _ => {
+                        return Ok(match name.as_str() {
+                            "s" => Expr::Variable,
+                            "i" => Expr::Constant { re: 0.0, im: 1.0 },
+                            "pi" => Expr::Named("PI"),
+                            "e" => Expr::Named("E"),
+                            _ => Expr::Captured(name),
+                        })
+                    }
+                };
+
+                let method = match name.as_str() {
+                    "exp" => "exp",
+                    "ln" | "log" => "ln",
+                    "sqrt" => "sqrt",
+                    "sin" => "sin",
+                    "cos" => "cos",
+                    "tan" => "tan",
+                    "sinh" => "sinh",
+                    "cosh" => "cosh",
+                    "tanh" => "tanh",
+                    "atan" => "atan",
+                    _ => return Err(error(ident.span(), format!("unknown function `{name}`"))),
+                };
+
+                Ok(Expr::Call(method, Box::new(argument)))
+            }
+            token => Err(error(
+                token.span(),
+                format!("expected a number, a name or parentheses, found `{token}`"),
+            )),
+        }
+    }
+
+    /// Take the next token when it's one of the operators.
+    fn next_punct(&mut self, operators: &[char]) -> Option<char> {
+        match self.tokens.get(self.position) {
+            Some(TokenTree::Punct(punct)) if operators.contains(&punct.as_char()) => {
+                self.position += 1;
+
+                Some(punct.as_char())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Expression between parentheses.
+fn parenthesized(group: &Group) -> Result<Expr, Error> {
+    Parser::new(group.stream(), group.span_close()).parse()
+}
+
+/// Real number, or an imaginary one with an `i` suffix like `2i` or `0.5i`.
+fn number(literal: &Literal) -> Result<Expr, Error> {
+    let source = literal.to_string();
+    let (digits, imaginary) = match source.strip_suffix('i') {
+        Some(digits) => (digits, true),
+        None => (source.trim_end_matches("f64"), false),
+    };
+
+    match digits.replace('_', "").parse::<f64>() {
+        Ok(value) if value.is_finite() => Ok(if imaginary {
+            Expr::Constant { re: 0.0, im: value }
+        } else {
+            Expr::Constant { re: value, im: 0.0 }
+        }),
+        _ => Err(error(literal.span(), format!("invalid number `{source}`"))),
+    }
+}
+
+/// Error at the span.
+fn error(span: Span, message: String) -> Error {
+    Error { span, message }
+}
@@ -0,0 +1,206 @@
+//! Python extension module of `iltcme`, built with `maturin` from the root of the repository.
+//!
+//! ```python
+//! import numpy as np
+//! import iltcme
+//!
+//! times = np.linspace(0.1, 10.0, 100)
+//! # Called once with an array of all points
+//! result = iltcme.ilt(lambda s: 1 / (s**2 + 1), times, 50, vectorized=True)
+//! ```
+
+use nalgebra::Complex;
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyComplex, PyList},
+};
+
+// The module function below shadows the crate name
+use ::iltcme::{coefficients, CmeInverter, Error};
+
+/// Invert the Laplace transform `func` at each of the `times` with at most `max_evals` evaluations per time, returning a NumPy array.
+///
+/// With `vectorized` the function is called once with a complex NumPy array of all points and must return an array of the same shape, which avoids the overhead of calling Python for each point.
+#[pyfunction]
+#[pyo3(signature = (func, times, max_evals = 50, vectorized = false))]
+fn ilt<'py>(
+    py: Python<'py>,
+    func: &'py PyAny,
+    times: Vec<f64>,
+    max_evals: usize,
+    vectorized: bool,
+) -> PyResult<&'py PyAny> {
+    let inverter = CmeInverter::new(max_evals).map_err(to_py_err)?;
+
+    let result = if vectorized {
+        invert_vectorized(py, func, &times, max_evals)?
+    } else {
+        // Python exceptions can't pass through the inversion, the first one is raised afterwards
+        let mut exception = None;
+        let result = times
+            .iter()
+            .map(|t| {
+                inverter
+                    .invert(
+                        |s| {
+                            let value = func
+                                .call1((PyComplex::from_doubles(py, s.re, s.im),))
+                                .and_then(to_complex);
+
+                            value.unwrap_or_else(|err| {
+                                exception.get_or_insert(err);
+                                Complex::new(f64::NAN, f64::NAN)
+                            })
+                        },
+                        *t,
+                    )
+                    .map_err(to_py_err)
+            })
+            .collect::<PyResult<Vec<_>>>();
+
+        if let Some(err) = exception {
+            return Err(err);
+        }
+        result?
+    };
+
+    numpy(py)?.getattr("asarray")?.call1((result,))
+}
+
+/// Evaluate the function once for the nodes of all times.
+fn invert_vectorized(
+    py: Python<'_>,
+    func: &PyAny,
+    times: &[f64],
+    max_evals: usize,
+) -> PyResult<Vec<f64>> {
+    if let Some(t) = times.iter().find(|t| t.is_nan() || **t <= 0.0) {
+        return Err(to_py_err(Error::NonPositiveTime(*t)));
+    }
+
+    let order = coefficients::order(max_evals);
+    let points = times
+        .iter()
+        .flat_map(|t| {
+            order.nodes().map(move |(_, beta)| {
+                let s = beta / *t;
+                PyComplex::from_doubles(py, s.re, s.im)
+            })
+        })
+        .collect::<Vec<_>>();
+    let asarray = numpy(py)?.getattr("asarray")?;
+    let values = func.call1((asarray.call1((PyList::new(py, points),))?,))?;
+    let values = asarray
+        .call1((values, "complex128"))?
+        .call_method0("ravel")?
+        .call_method0("tolist")?
+        .extract::<Vec<&PyAny>>()?
+        .into_iter()
+        .map(to_complex)
+        .collect::<PyResult<Vec<_>>>()?;
+    if values.len() != times.len() * order.evaluations() {
+        return Err(PyValueError::new_err(
+            "function must return an array with a value for each point",
+        ));
+    }
+
+    times
+        .iter()
+        .zip(values.chunks_exact(order.evaluations()))
+        .map(|(t, values)| {
+            let mut sum = 0.0;
+            for (node, ((eta, beta), value)) in order.nodes().zip(values).enumerate() {
+                // Fail like the scalar path instead of returning NaN
+                if !value.is_finite() {
+                    return Err(to_py_err(Error::NonFiniteTransform {
+                        node,
+                        beta,
+                        s: beta / *t,
+                        value: *value,
+                    }));
+                }
+                sum += (eta * value).re;
+            }
+
+            Ok(sum / t)
+        })
+        .collect()
+}
+
+/// Convert a Python number to a complex number.
+fn to_complex(value: &PyAny) -> PyResult<Complex<f64>> {
+    match value.downcast::<PyComplex>() {
+        Ok(value) => Ok(Complex::new(value.real(), value.imag())),
+        Err(_) => value.extract::<f64>().map(Complex::from),
+    }
+}
+
+/// The NumPy module.
+fn numpy(py: Python<'_>) -> PyResult<&PyModule> {
+    py.import("numpy")
+}
+
+/// Raise errors of the inversion as `ValueError`.
+fn to_py_err(err: Error) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+/// Inverse Laplace transform with concentrated matrix-exponential functions.
+#[pymodule]
+fn iltcme(_py: Python<'_>, module: &PyModule) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(ilt, module)?)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pyo3::types::IntoPyDict;
+
+    use super::*;
+
+    #[test]
+    fn extension_module() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "iltcme").unwrap();
+            iltcme(py, module).unwrap();
+            let globals = [("iltcme", module)].into_py_dict(py);
+
+            for vectorized in ["False", "True"] {
+                let result = py
+                    .eval(
+                        &format!("iltcme.ilt(lambda s: 1 / (s + 1), [0.5, 1.0], 50, vectorized={vectorized}).tolist()"),
+                        Some(globals),
+                        None,
+                    )
+                    .unwrap()
+                    .extract::<Vec<f64>>()
+                    .unwrap();
+                assert!((result[1] - (-1.0_f64).exp()).abs() < 1e-3);
+            }
+
+            // Exceptions of the function are raised
+            assert!(py
+                .eval("iltcme.ilt(lambda s: 1 / 0, [1.0])", Some(globals), None)
+                .is_err());
+            assert!(py
+                .eval("iltcme.ilt(lambda s: 1 / s, [0.0])", Some(globals), None)
+                .is_err());
+
+            // Non-finite values fail the same with and without vectorization
+            for vectorized in ["False", "True"] {
+                let err = py
+                    .eval(
+                        &format!("iltcme.ilt(lambda s: s * float('nan'), [1.0], vectorized={vectorized})"),
+                        Some(globals),
+                        None,
+                    )
+                    .unwrap_err();
+                assert!(err.is_instance_of::<PyValueError>(py));
+                assert!(err.to_string().contains("at node 0"));
+            }
+        });
+    }
+}
@@ -0,0 +1,230 @@
+//! Sequence transformations estimating the limit of slowly converging sequences, such as partial sums of series or functionals of increasing order.
+//!
+//! Wynn's epsilon algorithm works well for alternating and linearly converging sequences but stalls for logarithmically converging ones, the rho algorithm the other way around.
+//! The Levin u-transform models the remainder with the terms themselves and accelerates both kinds, which makes it the choice when it's unclear where the epsilon or rho algorithm stalls.
+//!
+//! # Example
+//!
+//! ```rust
+//! # fn main() {
+//! use iltcme::acceleration::Acceleration;
+//!
+//! // Partial sums of the alternating series of `ln(2)`
+//! let sums = (1..=12)
+//!     .scan(0.0, |sum, k: i32| {
+//!         *sum += (-1.0_f64).powi(k + 1) / k as f64;
+//!         Some(*sum)
+//!     })
+//!     .collect::<Vec<_>>();
+//! approx::assert_relative_eq!(Acceleration::LevinU.limit(&sums), 2.0_f64.ln(), epsilon = 1e-10);
+//! # }
+//! ```
+
+/// Sequence transformation used to estimate the limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Acceleration {
+    /// Use the last element of the sequence.
+    #[default]
+    None,
+    /// Wynn's epsilon algorithm, see [`epsilon`].
+    Epsilon,
+    /// Wynn's rho algorithm, see [`rho`].
+    Rho,
+    /// Levin's u-transform, see [`levin_u`].
+    LevinU,
+    /// Euler summation, see [`euler`], averaging this many partial sums after the first.
+    Euler(usize),
+}
+
+impl Acceleration {
+    /// Estimate the limit of the sequence, NaN for an empty sequence.
+    pub fn limit(&self, sequence: &[f64]) -> f64 {
+        match self {
+            Self::None => sequence.last().copied().unwrap_or(f64::NAN),
+            Self::Epsilon => epsilon(sequence),
+            Self::Rho => rho(sequence),
+            Self::LevinU => levin_u(sequence),
+            Self::Euler(averaged) => euler(sequence, *averaged),
+        }
+    }
+}
+
+/// Estimate the limit of a sequence with Wynn's epsilon algorithm,
+///
+/// $$
+/// \varepsilon_{-1}^{(k)} = 0, \quad \varepsilon_0^{(k)} = s_k, \quad \varepsilon_r^{(k)} = \varepsilon_{r - 2}^{(k + 1)} + \frac{1}{\varepsilon_{r - 1}^{(k + 1)} - \varepsilon_{r - 1}^{(k)}}
+/// $$
+///
+/// The even columns are the Shanks transformations of the sequence, the top of the last even column is returned.
+/// Stops at the last complete even column when two entries of a column are equal, which happens when the sequence already converged.
+pub fn epsilon(sequence: &[f64]) -> f64 {
+    wynn(sequence, |_| 1.0)
+}
+
+/// Estimate the limit of a sequence with Wynn's rho algorithm,
+///
+/// $$
+/// \rho_{-1}^{(k)} = 0, \quad \rho_0^{(k)} = s_k, \quad \rho_r^{(k)} = \rho_{r - 2}^{(k + 1)} + \frac{r}{\rho_{r - 1}^{(k + 1)} - \rho_{r - 1}^{(k)}}
+/// $$
+///
+/// The even columns are estimates of the limit, the top of the last even column is returned.
+/// Stops at the last complete even column when two entries of a column are equal, which happens when the sequence already converged.
+pub fn rho(sequence: &[f64]) -> f64 {
+    wynn(sequence, |r| r as f64)
+}
+
+/// Estimate the limit of a sequence with Levin's u-transform,
+///
+/// $$
+/// u_k = \frac{\sum_{j = 0}^k (-1)^j \binom{k}{j} \left( \frac{j + 1}{k + 1} \right)^{k - 1} \frac{s_j}{\omega_j}}{\sum_{j = 0}^k (-1)^j \binom{k}{j} \left( \frac{j + 1}{k + 1} \right)^{k - 1} \frac{1}{\omega_j}}, \quad \omega_j = (j + 1) (s_j - s_{j - 1})
+/// $$
+///
+/// with $s_{-1} = 0$, using the whole sequence.
+/// The binomial weights amplify rounding errors, it's meant for sequences of tens of elements.
+/// Returns the last element when the transformation isn't finite, for example when two consecutive elements are equal.
+pub fn levin_u(sequence: &[f64]) -> f64 {
+    let Some(last) = sequence.last().copied() else {
+        return f64::NAN;
+    };
+
+    let k = sequence.len() - 1;
+    let mut binomial = 1.0;
+    let (mut numerator, mut denominator) = (0.0, 0.0);
+    let mut previous = 0.0;
+    for (j, s) in sequence.iter().enumerate() {
+        let omega = (j + 1) as f64 * (s - previous);
+        let sign = if j.is_multiple_of(2) { 1.0 } else { -1.0 };
+        let weight = sign * binomial * ((j + 1) as f64 / (k + 1) as f64).powi(k as i32 - 1) / omega;
+        numerator += weight * s;
+        denominator += weight;
+
+        binomial *= (k - j) as f64 / (j + 1) as f64;
+        previous = *s;
+    }
+
+    let limit = numerator / denominator;
+    if limit.is_finite() {
+        limit
+    } else {
+        last
+    }
+}
+
+/// Estimate the limit of the partial sums of an alternating series with Euler summation, the binomial average of the last `averaged + 1` elements,
+///
+/// $$
+/// \sum_{j = 0}^m \binom{m}{j} 2^{-m} s_{n + j}
+/// $$
+///
+/// Averages all elements when the sequence is shorter.
+/// With the partial sums of the Bromwich integral this is the Euler algorithm of Abate and Whitt.
+pub fn euler(sequence: &[f64], averaged: usize) -> f64 {
+    if sequence.is_empty() {
+        return f64::NAN;
+    }
+
+    let start = sequence.len().saturating_sub(averaged + 1);
+    let m = sequence.len() - start - 1;
+    let mut weight = 0.5_f64.powi(m as i32);
+    let mut sum = 0.0;
+    for (j, s) in sequence[start..].iter().enumerate() {
+        sum += weight * s;
+        weight *= (m - j) as f64 / (j + 1) as f64;
+    }
+
+    sum
+}
+
+/// Wynn's algorithm with the numerator of column `r`.
+fn wynn(sequence: &[f64], numerator: impl Fn(usize) -> f64) -> f64 {
+    let Some(mut best) = sequence.last().copied() else {
+        return f64::NAN;
+    };
+
+    let mut previous = vec![0.0; sequence.len() + 1];
+    let mut current = sequence.to_vec();
+    for r in 1..sequence.len() {
+        let mut next = Vec::with_capacity(current.len() - 1);
+        for k in 0..current.len() - 1 {
+            let difference = current[k + 1] - current[k];
+            if difference == 0.0 {
+                return best;
+            }
+            next.push(previous[k + 1] + numerator(r) / difference);
+        }
+
+        if r.is_multiple_of(2) {
+            best = next[0];
+        }
+        previous = current;
+        current = next;
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits() {
+        // Alternating series of `ln(2)`
+        let alternating = (1..=12)
+            .scan(0.0, |sum, k: i32| {
+                *sum += (-1.0_f64).powi(k + 1) / k as f64;
+                Some(*sum)
+            })
+            .collect::<Vec<_>>();
+        // Logarithmically converging series of `pi^2 / 6`
+        let basel = (1..=20)
+            .scan(0.0, |sum, k: i32| {
+                *sum += 1.0 / f64::from(k * k);
+                Some(*sum)
+            })
+            .collect::<Vec<_>>();
+        let basel_limit = std::f64::consts::PI.powi(2) / 6.0;
+
+        assert_eq!(Acceleration::None.limit(&alternating), alternating[11]);
+        assert!(approx::relative_eq!(
+            epsilon(&alternating),
+            2.0_f64.ln(),
+            epsilon = 1e-8
+        ));
+        assert!((rho(&alternating) - 2.0_f64.ln()).abs() > 1e-3);
+        assert!(approx::relative_eq!(
+            levin_u(&alternating),
+            2.0_f64.ln(),
+            epsilon = 1e-10
+        ));
+        assert!(approx::relative_eq!(
+            rho(&basel),
+            basel_limit,
+            epsilon = 1e-10
+        ));
+        assert!((epsilon(&basel) - basel_limit).abs() > 1e-3);
+        assert!(approx::relative_eq!(
+            levin_u(&basel),
+            basel_limit,
+            epsilon = 1e-5
+        ));
+        assert!(approx::relative_eq!(
+            euler(&alternating, 8),
+            2.0_f64.ln(),
+            epsilon = 1e-4
+        ));
+        assert_eq!(euler(&[1.0, 3.0], 8), 2.0);
+
+        for acceleration in [
+            Acceleration::None,
+            Acceleration::Epsilon,
+            Acceleration::Rho,
+            Acceleration::LevinU,
+            Acceleration::Euler(1),
+        ] {
+            assert!(acceleration.limit(&[]).is_nan());
+            assert_eq!(acceleration.limit(&[2.0, 2.0, 2.0]), 2.0);
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! HTTP service inverting Laplace transform expressions and sampled transforms.
+
+#[path = "../iltcme/samples.rs"]
+// Sample files are only read by the command line tool
+#[allow(dead_code)]
+mod samples;
+
+use std::{collections::HashMap, process::ExitCode};
+
+use axum::{http::StatusCode, routing::post, Json, Router};
+use iltcme::{expression::Transform, CmeInverter, Diagnostics};
+use nalgebra::Complex;
+use serde::{Deserialize, Serialize};
+
+/// Address the service listens on when none is passed as the first argument.
+const DEFAULT_ADDRESS: &str = "127.0.0.1:3000";
+
+/// Longest expression in bytes a request may contain.
+const MAX_EXPRESSION_LEN: usize = 10_000;
+
+/// Most times a request may invert at.
+const MAX_TIMES: usize = 10_000;
+
+/// Body of a request to `/invert`, with either an expression or samples of the transform.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InvertRequest {
+    /// Transform expression, for example `1/(s^2+1)`.
+    expression: Option<String>,
+    /// Values of the parameters of the expression by name.
+    #[serde(default)]
+    parameters: HashMap<String, f64>,
+    /// Samples of the transform as `[Re s, Im s, Re F, Im F]`, fitted with a rational function.
+    samples: Option<Vec<[f64; 4]>>,
+    /// Relative tolerance of the rational fit of the samples.
+    #[serde(default = "default_tolerance")]
+    tolerance: f64,
+    /// Times to invert at.
+    times: Vec<f64>,
+    /// Maximum number of function evaluations.
+    #[serde(default = "default_max_evaluations")]
+    max_evaluations: usize,
+}
+
+/// Default relative tolerance of the rational fit.
+fn default_tolerance() -> f64 {
+    1e-10
+}
+
+/// Default maximum number of function evaluations.
+fn default_max_evaluations() -> usize {
+    50
+}
+
+/// Result at a single time, with the diagnostics or the error when the inversion failed.
+#[derive(Debug, Serialize)]
+struct TimeResult {
+    t: f64,
+    value: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diagnostics: Option<Diagnostics>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Body of a successful response.
+#[derive(Debug, Serialize)]
+struct InvertResponse {
+    results: Vec<TimeResult>,
+}
+
+/// Body of a response to an invalid request.
+#[derive(Debug, Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// Invert the transform of the request at each of its times.
+///
+/// Returns a message when the request is invalid, failures at single times are reported in their result.
+fn invert(request: InvertRequest) -> Result<InvertResponse, String> {
+    if request.times.len() > MAX_TIMES {
+        return Err(format!("at most {MAX_TIMES} times are allowed"));
+    }
+    if request
+        .expression
+        .as_ref()
+        .is_some_and(|expression| expression.len() > MAX_EXPRESSION_LEN)
+    {
+        return Err(format!(
+            "the expression is longer than {MAX_EXPRESSION_LEN} bytes"
+        ));
+    }
+    let inverter = CmeInverter::new(request.max_evaluations).map_err(|err| err.to_string())?;
+    let transform: Box<dyn Fn(Complex<f64>) -> Complex<f64>> =
+        match (request.expression, request.samples) {
+            (Some(expression), None) => {
+                let mut transform = Transform::parse(&expression)
+                    .map_err(|err| format!("invalid expression: {err}"))?;
+                for (name, value) in &request.parameters {
+                    if transform
+                        .parameters()
+                        .all(|(parameter, _)| parameter != name)
+                    {
+                        return Err(format!("the expression has no parameter `{name}`"));
+                    }
+                    transform = transform.with_parameter(name, *value);
+                }
+                if let Some((name, _)) = transform.parameters().find(|(_, value)| value.is_none()) {
+                    return Err(format!("no value for the parameter `{name}`"));
+                }
+                transform.boxed()
+            }
+            (None, Some(samples)) => {
+                let samples = samples
+                    .into_iter()
+                    .map(|[s_re, s_im, re, im]| (Complex::new(s_re, s_im), Complex::new(re, im)))
+                    .collect::<Vec<_>>();
+                let fit = samples::fit(&samples, request.tolerance)?;
+                Box::new(move |s| fit.evaluate(s))
+            }
+            _ => return Err("either `expression` or `samples` is required".to_string()),
+        };
+
+    let results = request
+        .times
+        .into_iter()
+        .map(|t| match inverter.invert_with_diagnostics(&transform, t) {
+            Ok((value, diagnostics)) => TimeResult {
+                t,
+                value: Some(value),
+                diagnostics: Some(diagnostics),
+                error: None,
+            },
+            Err(err) => TimeResult {
+                t,
+                value: None,
+                diagnostics: None,
+                error: Some(err.to_string()),
+            },
+        })
+        .collect();
+
+    Ok(InvertResponse { results })
+}
+
+/// Handle `POST /invert`, inverting on a blocking thread so the runtime keeps serving requests.
+async fn invert_handler(
+    Json(request): Json<InvertRequest>,
+) -> Result<Json<InvertResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let error = |status, error| (status, Json(ErrorResponse { error }));
+
+    match tokio::task::spawn_blocking(move || invert(request)).await {
+        Ok(Ok(response)) => Ok(Json(response)),
+        Ok(Err(message)) => Err(error(StatusCode::UNPROCESSABLE_ENTITY, message)),
+        Err(err) => Err(error(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    }
+}
+
+/// Routes of the service.
+fn router() -> Router {
+    Router::new().route("/invert", post(invert_handler))
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+
+    let listener = match tokio::net::TcpListener::bind(&address).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("Could not listen on {address}: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("Listening on http://{address}/invert");
+
+    match axum::serve(listener, router()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Server stopped: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invert_requests() {
+        let request = |body: &str| invert(serde_json::from_str(body).unwrap());
+
+        let response =
+            request(r#"{"expression": "1/(s^2+1)", "times": [1, 0], "max_evaluations": 100}"#)
+                .unwrap();
+        let value = response.results[0].value.unwrap();
+        assert!((value - 1.0_f64.sin()).abs() < 1e-4);
+        assert_eq!(response.results[0].diagnostics.unwrap().order_used, 100);
+        assert!(response.results[1].value.is_none() && response.results[1].error.is_some());
+
+        // Samples of `1 / (s + 1)` along the imaginary axis
+        let samples = (0..40)
+            .map(|i| {
+                let s = Complex::new(0.0, f64::from(i) * 0.5);
+                let value = 1.0 / (s + 1.0);
+                format!("[{}, {}, {}, {}]", s.re, s.im, value.re, value.im)
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let response = request(&format!(r#"{{"samples": [{samples}], "times": [1]}}"#)).unwrap();
+        assert!((response.results[0].value.unwrap() - (-1.0_f64).exp()).abs() < 1e-3);
+
+        let response = request(
+            r#"{"expression": "1/(s+a)", "parameters": {"a": 2}, "times": [1], "max_evaluations": 100}"#,
+        )
+        .unwrap();
+        assert!((response.results[0].value.unwrap() - (-2.0_f64).exp()).abs() < 1e-4);
+        assert!(request(r#"{"expression": "1/(s+a)", "times": [1]}"#).is_err());
+        assert!(request(r#"{"expression": "1/s", "parameters": {"a": 2}, "times": [1]}"#).is_err());
+
+        assert!(request(r#"{"times": [1]}"#).is_err());
+        assert!(request(r#"{"expression": "1/(s", "times": [1]}"#).is_err());
+        assert!(
+            request(r#"{"expression": "1/s", "times": [1], "max_evaluations": 100000}"#).is_err()
+        );
+
+        let long = format!("s{}", "+s".repeat(MAX_EXPRESSION_LEN));
+        assert!(request(&format!(r#"{{"expression": "{long}", "times": [1]}}"#)).is_err());
+        let times = vec!["1"; MAX_TIMES + 1].join(",");
+        assert!(request(&format!(r#"{{"expression": "1/s", "times": [{times}]}}"#)).is_err());
+    }
+
+    #[tokio::test]
+    async fn nested_expression() {
+        let nested = format!("{}s{}", "(".repeat(1000), ")".repeat(1000));
+        let request =
+            serde_json::from_str(&format!(r#"{{"expression": "{nested}", "times": [1]}}"#))
+                .unwrap();
+
+        let (status, Json(response)) = invert_handler(Json(request)).await.unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(response.error.contains("nested too deeply"));
+    }
+}
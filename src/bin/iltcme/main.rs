@@ -0,0 +1,291 @@
+//! Invert Laplace transform expressions from the command line.
+
+mod plot;
+mod samples;
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    process::ExitCode,
+    str::FromStr,
+};
+
+use clap::{Parser, ValueEnum};
+use iltcme::{expression, rational::RationalFit, CmeInverter};
+use nalgebra::Complex;
+
+/// Invert a Laplace transform expression in `s` or sampled transform values with the CME method and print a table of the results.
+///
+/// Example: iltcme "1/(s^2+1)" --times 0.5,1,2 --max-evaluations 20,50
+#[derive(Parser)]
+#[command(author, version, about)]
+struct Args {
+    /// Transform expression, for example `1/(s^2+1)` or `exp(-sqrt(s))/s`.
+    #[arg(required_unless_present = "samples")]
+    expression: Option<String>,
+    /// Value of a parameter of the expression as `name=value`, can be repeated.
+    #[arg(short, long = "parameter", value_parser = parse_parameter, requires = "expression")]
+    parameters: Vec<(String, f64)>,
+    /// File with samples of the transform to fit and invert instead of an expression.
+    ///
+    /// Each line has the columns `s`, `Re F` and `Im F`, or `Re s`, `Im s`, `Re F` and `Im F`, separated by commas or whitespace.
+    #[arg(short, long, conflicts_with = "expression")]
+    samples: Option<PathBuf>,
+    /// Relative tolerance of the rational fit of the samples.
+    #[arg(long, default_value_t = 1e-10, requires = "samples")]
+    tolerance: f64,
+    /// Times to invert at, separated by commas.
+    #[arg(short, long, value_delimiter = ',', num_args = 1.., required_unless_present = "grid")]
+    times: Vec<f64>,
+    /// Equally spaced times to invert at as `start:end:step`, including the end.
+    #[arg(short, long, conflicts_with = "times")]
+    grid: Option<Grid>,
+    /// Maximum numbers of function evaluations to compare, separated by commas.
+    #[arg(short, long, value_delimiter = ',', num_args = 1.., default_value = "50")]
+    max_evaluations: Vec<usize>,
+    /// Format of the results.
+    #[arg(short, long, value_enum, default_value_t = Output::Table)]
+    output: Output,
+    /// File to write the results to, standard output when omitted except for images.
+    #[arg(short, long, required_if_eq("output", "png"))]
+    file: Option<PathBuf>,
+}
+
+/// Transform to invert.
+enum Transform {
+    /// Parsed expression.
+    Expr(expression::Transform),
+    /// Rational function fitted to samples.
+    Fit(RationalFit),
+}
+
+impl Transform {
+    fn evaluate(&self, s: Complex<f64>) -> Complex<f64> {
+        match self {
+            Self::Expr(expr) => expr.evaluate(s),
+            Self::Fit(fit) => fit.evaluate(s),
+        }
+    }
+}
+
+/// Format of the results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Output {
+    /// Aligned table for reading.
+    Table,
+    /// Comma separated values with a column for each maximum number of function evaluations.
+    Csv,
+    /// Line chart with a line for each maximum number of function evaluations.
+    Png,
+}
+
+/// Equally spaced times.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Grid {
+    start: f64,
+    end: f64,
+    step: f64,
+}
+
+impl Grid {
+    /// Times from the start up to and including the end.
+    fn times(&self) -> Vec<f64> {
+        // Tolerate rounding of the last step
+        let steps = ((self.end - self.start) / self.step + 1e-9).floor() as usize;
+
+        (0..=steps)
+            .map(|i| self.start + i as f64 * self.step)
+            .collect()
+    }
+}
+
+impl FromStr for Grid {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s
+            .split(':')
+            .map(|part| part.trim().parse::<f64>().map_err(|err| err.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match parts[..] {
+            [start, end, step] if step > 0.0 && end >= start => Ok(Self { start, end, step }),
+            [_, _, _] => Err("the step must be positive and the end after the start".to_string()),
+            _ => Err("expected `start:end:step`".to_string()),
+        }
+    }
+}
+
+/// Parse a parameter as `name=value`.
+fn parse_parameter(s: &str) -> Result<(String, f64), String> {
+    let (name, value) = s.split_once('=').ok_or("expected `name=value`")?;
+    let value = value.trim().parse::<f64>().map_err(|err| err.to_string())?;
+
+    Ok((name.trim().to_string(), value))
+}
+
+/// Parse the expression and bind all its parameters.
+fn bind(expression: &str, parameters: &[(String, f64)]) -> Result<expression::Transform, String> {
+    let mut expr = expression::Transform::parse(expression).map_err(|err| err.to_string())?;
+    for (name, value) in parameters {
+        if expr.parameters().all(|(parameter, _)| parameter != name) {
+            return Err(format!("the expression has no parameter '{name}'"));
+        }
+        expr = expr.with_parameter(name, *value);
+    }
+
+    let unbound = expr
+        .parameters()
+        .find(|(_, value)| value.is_none())
+        .map(|(name, _)| name.to_string());
+    match unbound {
+        Some(name) => Err(format!(
+            "no value for '{name}', pass it with `--parameter {name}=<value>`"
+        )),
+        None => Ok(expr),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let (transform, title) = match (&args.expression, &args.samples) {
+        (Some(expression), _) => match bind(expression, &args.parameters) {
+            Ok(expr) => (Transform::Expr(expr), expression.clone()),
+            Err(err) => {
+                eprintln!("Invalid expression: {err}");
+                return ExitCode::FAILURE;
+            }
+        },
+        (None, Some(samples)) => match samples::from_file(samples, args.tolerance) {
+            Ok(fit) => {
+                eprintln!(
+                    "Fitted {} terms with a maximum error of {:e}",
+                    fit.terms(),
+                    fit.max_error()
+                );
+                (Transform::Fit(fit), samples.display().to_string())
+            }
+            Err(err) => {
+                eprintln!("Could not fit {}: {err}", samples.display());
+                return ExitCode::FAILURE;
+            }
+        },
+        // Required by the arguments
+        (None, None) => unreachable!(),
+    };
+    let inverters = match args
+        .max_evaluations
+        .iter()
+        .map(|max| CmeInverter::new(*max))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(inverters) => inverters,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let times = args.grid.map_or(args.times, |grid| grid.times());
+    let rows = times
+        .into_iter()
+        .map(|t| {
+            let values = inverters
+                .iter()
+                .map(|inverter| {
+                    inverter
+                        .invert(|s| transform.evaluate(s), t)
+                        .unwrap_or_else(|err| {
+                            eprintln!("{err}");
+                            f64::NAN
+                        })
+                })
+                .collect::<Vec<_>>();
+
+            (t, values)
+        })
+        .collect::<Vec<_>>();
+
+    if args.output == Output::Png {
+        // Required by the arguments
+        let file = args.file.unwrap();
+        return match plot::plot(&file, &title, &args.max_evaluations, &rows) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("Could not plot to {}: {err}", file.display());
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    let mut out: Box<dyn Write> = match &args.file {
+        Some(file) => match File::create(file) {
+            Ok(file) => Box::new(BufWriter::new(file)),
+            Err(err) => {
+                eprintln!("Could not create {}: {err}", file.display());
+                return ExitCode::FAILURE;
+            }
+        },
+        None => Box::new(std::io::stdout().lock()),
+    };
+    let written = match args.output {
+        Output::Csv => write_csv(&mut out, &args.max_evaluations, &rows),
+        _ => write_table(&mut out, &args.max_evaluations, &rows),
+    };
+
+    match written.and_then(|()| out.flush()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Could not write the results: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Write an aligned table with a column for each maximum number of function evaluations.
+fn write_table(
+    out: &mut impl Write,
+    max_evaluations: &[usize],
+    rows: &[(f64, Vec<f64>)],
+) -> std::io::Result<()> {
+    write!(out, "{:>12}", "t")?;
+    for max in max_evaluations {
+        write!(out, " {:>16}", format!("evals={max}"))?;
+    }
+    writeln!(out)?;
+
+    for (t, values) in rows {
+        write!(out, "{t:>12}")?;
+        for value in values {
+            write!(out, " {value:>16.9e}")?;
+        }
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
+
+/// Write comma separated values with a column for each maximum number of function evaluations.
+fn write_csv(
+    out: &mut impl Write,
+    max_evaluations: &[usize],
+    rows: &[(f64, Vec<f64>)],
+) -> std::io::Result<()> {
+    write!(out, "t")?;
+    for max in max_evaluations {
+        write!(out, ",evals={max}")?;
+    }
+    writeln!(out)?;
+
+    for (t, values) in rows {
+        write!(out, "{t:?}")?;
+        for value in values {
+            write!(out, ",{value:?}")?;
+        }
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+//! Line charts of the inversions.
+
+use std::{error::Error, path::Path};
+
+use plotters::prelude::*;
+
+/// Plot a line for the results of each maximum number of function evaluations as a PNG image.
+pub fn plot(
+    file: &Path,
+    title: &str,
+    max_evaluations: &[usize],
+    rows: &[(f64, Vec<f64>)],
+) -> Result<(), Box<dyn Error>> {
+    let finite = || {
+        rows.iter()
+            .flat_map(|(_, values)| values)
+            .copied()
+            .filter(|value| value.is_finite())
+    };
+    let (t_min, t_max) = rows
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), (t, _)| {
+            (min.min(*t), max.max(*t))
+        });
+    let (y_min, y_max) = finite().fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), y| {
+        (min.min(y), max.max(y))
+    });
+    if !(t_min < t_max && y_min <= y_max) {
+        return Err("at least two times with finite results are needed".into());
+    }
+    // Leave some room above and below the lines
+    let margin = ((y_max - y_min) * 0.05).max(1e-12);
+
+    let root = BitMapBackend::new(file, (1024, 640)).into_drawing_area();
+    root.fill(&WHITE)?;
+    let mut chart = ChartBuilder::on(&root)
+        .caption(format!("Inverse of {title}"), ("sans-serif", 24))
+        .margin(16)
+        .x_label_area_size(40)
+        .y_label_area_size(64)
+        .build_cartesian_2d(t_min..t_max, (y_min - margin)..(y_max + margin))?;
+    chart.configure_mesh().x_desc("t").y_desc("f(t)").draw()?;
+
+    for (i, max) in max_evaluations.iter().enumerate() {
+        let color = Palette99::pick(i).to_rgba();
+        chart
+            .draw_series(LineSeries::new(
+                rows.iter()
+                    .map(|(t, values)| (*t, values[i]))
+                    .filter(|(_, y)| y.is_finite()),
+                color.stroke_width(2),
+            ))?
+            .label(format!("evals={max}"))
+            .legend(move |(x, y)| PathElement::new([(x, y), (x + 20, y)], color.stroke_width(2)));
+    }
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    root.present()?;
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+//! Fitting sampled transform values with a rational function.
+
+use std::path::Path;
+
+use iltcme::rational::RationalFit;
+use nalgebra::Complex;
+
+/// Maximum number of support points of the fit.
+const MAX_SUPPORT_POINTS: usize = 100;
+
+/// Fit samples of the transform until the largest error is below `tolerance` relative to the largest value, see [`RationalFit::fit`].
+pub fn fit(
+    samples: &[(Complex<f64>, Complex<f64>)],
+    tolerance: f64,
+) -> Result<RationalFit, String> {
+    if let Some((s, _)) = samples.iter().find(|(s, value)| {
+        !(s.re.is_finite() && s.im.is_finite() && value.re.is_finite() && value.im.is_finite())
+    }) {
+        return Err(format!("sample at {s} isn't finite"));
+    }
+    // A complex sample is added together with its conjugate
+    if samples.len() < 2 && samples.iter().all(|(s, _)| s.im == 0.0) {
+        return Err("at least two samples are needed".to_string());
+    }
+
+    Ok(RationalFit::fit(samples, tolerance, MAX_SUPPORT_POINTS))
+}
+
+/// Read samples from a file with a line for each sample and fit them, see [`fit`].
+///
+/// Lines have the columns `s`, `Re F` and `Im F`, or `Re s`, `Im s`, `Re F` and `Im F`, separated by commas or whitespace.
+/// Empty lines, lines starting with `#` and a header that isn't numeric are skipped.
+pub fn from_file(path: &Path, tolerance: f64) -> Result<RationalFit, String> {
+    let contents = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+
+    let mut samples = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let columns = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|column| !column.is_empty())
+            .map(str::parse::<f64>)
+            .collect::<Result<Vec<_>, _>>();
+        let sample = match columns.as_deref() {
+            Ok([s, re, im]) => (Complex::from(*s), Complex::new(*re, *im)),
+            Ok([s_re, s_im, re, im]) => (Complex::new(*s_re, *s_im), Complex::new(*re, *im)),
+            // Header
+            Err(_) if samples.is_empty() => continue,
+            _ => {
+                return Err(format!(
+                    "line {} must have 3 or 4 numeric columns",
+                    number + 1
+                ))
+            }
+        };
+        samples.push(sample);
+    }
+
+    fit(&samples, tolerance)
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::ComplexField;
+
+    use super::*;
+
+    #[test]
+    fn fit_samples() {
+        // Frequency response of the transform of the damped sine
+        let transform = |s: Complex<f64>| 1.0 / ((s + 0.5).powi(2) + 1.0);
+        let samples = (0..200)
+            .map(|i| {
+                let s = Complex::new(0.0, 10.0_f64.powf(-2.0 + 4.0 * i as f64 / 199.0));
+                (s, transform(s))
+            })
+            .collect::<Vec<_>>();
+
+        let fit = fit(&samples, 1e-12).unwrap();
+        assert!(fit.terms() < 10);
+
+        // Accurate away from the samples
+        let s = Complex::new(3.0, 2.0);
+        assert!((fit.evaluate(s) - transform(s)).modulus() < 1e-8);
+
+        let inverse = iltcme::laplace_inversion(|s| fit.evaluate(s), 1.0, 50);
+        assert!((inverse - (-0.5_f64).exp() * 1.0_f64.sin()).abs() < 1e-3);
+    }
+}
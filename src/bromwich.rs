@@ -0,0 +1,180 @@
+//! Direct Laplace inversion by trapezoidal integration of the Bromwich integral.
+//!
+//! The inverse is the contour integral along the vertical line $\operatorname{Re}(s) = \sigma$ right of all singularities,
+//!
+//! $$
+//! f(t) = \frac{1}{2 \pi i} \int_{\sigma - i \infty}^{\sigma + i \infty} e^{st} F(s) \, ds
+//!      \approx \frac{e^{\sigma t}}{t} \left[ \frac{F(\sigma)}{2} + \sum_{k = 1}^{N} (-1)^k \operatorname{Re} F\left(\sigma + \frac{i k \pi}{t}\right) \right]
+//! $$
+//!
+//! which is the trapezoidal rule with a step of $\pi / t$, or equivalently a Fourier series with a period of $2t$.
+//! The discretization error is roughly $e^{-2 \sigma t} f(3t)$ and the truncation error depends on how fast $F$ decays, by default there is no acceleration.
+//! It's slow but has no hidden parameters, which makes it a useful reference when faster methods disagree.
+//! With an [`Acceleration`] of the partial sums a few dozen terms are usually enough.
+
+use nalgebra::Complex;
+
+use crate::{acceleration::Acceleration, Error};
+
+/// Laplace inverter integrating the Bromwich integral with the plain trapezoidal rule.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), iltcme::Error> {
+/// use iltcme::bromwich::BromwichInverter;
+///
+/// let inverter = BromwichInverter::new(10.0, 10_000);
+///
+/// let result = inverter.invert(|s| 1.0 / (s.powi(2) + 1.0), 1.0)?;
+/// approx::assert_relative_eq!(result, 1.0_f64.sin(), epsilon = 0.001);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BromwichInverter {
+    /// Product $\sigma t$ of the contour abscissa and the time.
+    abscissa: f64,
+    /// Number of terms after the one on the real axis.
+    terms: usize,
+    /// Transformation of the partial sums.
+    #[cfg_attr(feature = "serde", serde(default))]
+    acceleration: Acceleration,
+}
+
+impl BromwichInverter {
+    /// Create an inverter with the contour at $\sigma = \frac{\text{abscissa}}{t}$ and truncated after `terms` terms.
+    ///
+    /// The contour must lie right of all singularities of the Laplace transform, a larger abscissa reduces the discretization error but amplifies the rounding error by $e^{\sigma t}$.
+    pub fn new(abscissa: f64, terms: usize) -> Self {
+        Self {
+            abscissa,
+            terms,
+            acceleration: Acceleration::None,
+        }
+    }
+
+    /// Set the product of the contour abscissa and the time.
+    pub fn with_abscissa(mut self, abscissa: f64) -> Self {
+        self.abscissa = abscissa;
+
+        self
+    }
+
+    /// Set the number of terms after the one on the real axis.
+    pub fn with_terms(mut self, terms: usize) -> Self {
+        self.terms = terms;
+
+        self
+    }
+
+    /// Set the transformation estimating the limit of the partial sums.
+    ///
+    /// The terms alternate in sign, so [`Acceleration::Epsilon`] and [`Acceleration::LevinU`] work well.
+    pub fn with_acceleration(mut self, acceleration: Acceleration) -> Self {
+        self.acceleration = acceleration;
+
+        self
+    }
+
+    /// Product $\sigma t$ of the contour abscissa and the time.
+    pub fn abscissa(&self) -> f64 {
+        self.abscissa
+    }
+
+    /// Number of terms after the one on the real axis.
+    pub fn terms(&self) -> usize {
+        self.terms
+    }
+
+    /// Transformation of the partial sums.
+    pub fn acceleration(&self) -> Acceleration {
+        self.acceleration
+    }
+
+    /// Calculate the Laplace inversion for a function at time `t`.
+    ///
+    /// Returns [`Error::NonFiniteTransform`] for the first node where the Laplace transform function returned NaN or infinity.
+    pub fn invert(
+        &self,
+        laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+        t: f64,
+    ) -> Result<f64, Error> {
+        self.partial_sums(laplace_func, t)
+            .map(|sums| self.acceleration.limit(&sums))
+    }
+
+    /// Approximations of the inverse at time `t` truncated after each term.
+    pub(crate) fn partial_sums(
+        &self,
+        mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+        t: f64,
+    ) -> Result<Vec<f64>, Error> {
+        if t <= 0.0 || t.is_nan() {
+            return Err(Error::NonPositiveTime(t));
+        }
+
+        let scale = self.abscissa.exp() / t;
+        let mut sum = 0.0;
+        let mut sums = Vec::with_capacity(self.terms + 1);
+        for k in 0..=self.terms {
+            let beta = Complex::new(self.abscissa, k as f64 * std::f64::consts::PI);
+            let s = beta / t;
+            let value = laplace_func(s);
+            if !(value.re.is_finite() && value.im.is_finite()) {
+                return Err(Error::NonFiniteTransform {
+                    node: k,
+                    beta,
+                    s,
+                    value,
+                });
+            }
+
+            sum += match k {
+                0 => value.re / 2.0,
+                k if k % 2 == 0 => value.re,
+                _ => -value.re,
+            };
+            sums.push(scale * sum);
+        }
+
+        Ok(sums)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::ComplexField;
+
+    use super::*;
+
+    #[test]
+    fn bromwich_inversions() {
+        let inverter = BromwichInverter::new(12.0, 20_000);
+
+        for t in [0.5, 1.0, 3.0] {
+            let exponential = inverter.invert(|s| (s + 1.0).recip(), t).unwrap();
+            let cosine = inverter.invert(|s| s / (s.powi(2) + 1.0), t).unwrap();
+
+            assert!(approx::relative_eq!(
+                exponential,
+                (-t).exp(),
+                epsilon = 1e-3
+            ));
+            assert!(approx::relative_eq!(cosine, t.cos(), epsilon = 1e-3));
+        }
+
+        // Few terms with acceleration
+        let inverter = BromwichInverter::new(12.0, 20).with_acceleration(Acceleration::LevinU);
+        for t in [0.5, 1.0, 3.0] {
+            let cosine = inverter.invert(|s| s / (s.powi(2) + 1.0), t).unwrap();
+            assert!(approx::relative_eq!(cosine, t.cos(), epsilon = 1e-9));
+        }
+
+        assert_eq!(
+            inverter.invert(|s| s.recip(), 0.0),
+            Err(Error::NonPositiveTime(0.0))
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! Coefficients compiled into the crate as a compressed blob, decoded the first time they're used.
+//!
+//! Regenerate the blob with `gen-coefficients`, see the readme for the binary layout.
+
+use std::{io::Read, sync::OnceLock};
+
+use super::{CmeParams, CmeTable};
+
+/// Largest maximum number of function evaluations the blob was generated for.
+pub(super) const MAX_EVALUATIONS: usize = EMBEDDED.0;
+
+/// Largest maximum number of function evaluations the high order blob was generated for, the highest order in `iltcme.json` is 1000.
+#[cfg(feature = "high-order")]
+pub(super) const HIGH_ORDER_MAX_EVALUATIONS: usize = 1001;
+
+/// Compressed distributions used for each maximum of function evaluations.
+const BLOB: &[u8] = EMBEDDED.1;
+
+/// Largest maximum number of function evaluations and blob of the largest `orders-*` feature, all orders when there's none.
+#[cfg(any(
+    feature = "orders-500",
+    not(any(feature = "orders-50", feature = "orders-100", feature = "orders-250"))
+))]
+const EMBEDDED: (usize, &[u8]) = (500, include_bytes!("table.zst"));
+
+/// Largest maximum number of function evaluations and blob of the largest `orders-*` feature.
+#[cfg(all(feature = "orders-250", not(feature = "orders-500")))]
+const EMBEDDED: (usize, &[u8]) = (250, include_bytes!("table_250.zst"));
+
+/// Largest maximum number of function evaluations and blob of the largest `orders-*` feature.
+#[cfg(all(
+    feature = "orders-100",
+    not(any(feature = "orders-250", feature = "orders-500"))
+))]
+const EMBEDDED: (usize, &[u8]) = (100, include_bytes!("table_100.zst"));
+
+/// Largest maximum number of function evaluations and blob of the largest `orders-*` feature.
+#[cfg(all(
+    feature = "orders-50",
+    not(any(feature = "orders-100", feature = "orders-250", feature = "orders-500"))
+))]
+const EMBEDDED: (usize, &[u8]) = (50, include_bytes!("table_50.zst"));
+
+/// Compressed distributions including the high orders.
+#[cfg(feature = "high-order")]
+const HIGH_ORDER_BLOB: &[u8] = include_bytes!("table_high_order.zst");
+
+/// Embedded table with the distributions for `max_function_evals` evaluations.
+#[cfg_attr(not(feature = "high-order"), allow(unused_variables))]
+pub(super) fn table_for(max_function_evals: usize) -> &'static CmeTable {
+    #[cfg(feature = "high-order")]
+    if max_function_evals > MAX_EVALUATIONS {
+        return high_order_table();
+    }
+
+    table()
+}
+
+/// Embedded table, decoded on first use.
+pub(super) fn table() -> &'static CmeTable {
+    static TABLE: OnceLock<CmeTable> = OnceLock::new();
+
+    TABLE.get_or_init(|| decode(BLOB))
+}
+
+/// Embedded table including the high orders, decoded the first time an order beyond [`MAX_EVALUATIONS`] is requested.
+#[cfg(feature = "high-order")]
+pub(super) fn high_order_table() -> &'static CmeTable {
+    static TABLE: OnceLock<CmeTable> = OnceLock::new();
+
+    TABLE.get_or_init(|| decode(HIGH_ORDER_BLOB))
+}
+
+/// Decompress and parse the distributions from the blob.
+fn decode(blob: &[u8]) -> CmeTable {
+    let mut bytes = Vec::new();
+    ruzstd::decoding::StreamingDecoder::new(blob)
+        .expect("Embedded coefficients are a zstd frame")
+        .read_to_end(&mut bytes)
+        .expect("Embedded coefficients can be decompressed");
+
+    let mut reader = Reader(&bytes);
+    let count = reader.u32();
+    let params = (0..count)
+        .map(|_| {
+            let n = reader.u32();
+            let [c, omega, mu1, cv2] = reader.f64s(4).try_into().unwrap();
+
+            CmeParams {
+                n,
+                c,
+                omega,
+                mu1,
+                cv2,
+                a: reader.f64s(n),
+                b: reader.f64s(n),
+            }
+        })
+        .collect();
+
+    CmeTable::new(params)
+}
+
+/// Little-endian values read from the start of the remaining bytes.
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    /// Take the next bytes.
+    fn take(&mut self, size: usize) -> &[u8] {
+        let (head, tail) = self.0.split_at(size);
+        self.0 = tail;
+
+        head
+    }
+
+    /// Read a length.
+    fn u32(&mut self) -> usize {
+        u32::from_le_bytes(self.take(4).try_into().unwrap()) as usize
+    }
+
+    /// Read `len` floats.
+    fn f64s(&mut self, len: usize) -> Vec<f64> {
+        self.take(len * 8)
+            .chunks_exact(8)
+            .map(|chunk| f64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect()
+    }
+}
@@ -0,0 +1,322 @@
+//! Computing concentrated matrix-exponential distributions at runtime.
+//!
+//! The density of a distribution of order $n$ is
+//!
+//! $$
+//! f(t) \propto e^{-t} \prod_{i = 1}^{n} \cos^2\left( \frac{\omega t - \phi_i}{2} \right)
+//! $$
+//!
+//! and the frequency $\omega$ and phases $\phi_i$ are chosen to minimize the squared coefficient of variation with the Nelder-Mead method.
+//! Phases are first restricted to equally spaced values with a single gap, the pattern of the published high order distributions, and for low orders each phase is refined separately afterwards.
+
+use std::{
+    collections::HashMap,
+    f64::consts::TAU,
+    sync::{Mutex, OnceLock},
+};
+
+use nalgebra::{Complex, ComplexField};
+
+use super::{runtime::Entry, CmeOrder, CmeParams};
+
+/// Highest order for which each phase is optimized separately.
+const FULL_OPTIMIZATION_ORDER: usize = 40;
+
+/// Maximum function evaluations of a Nelder-Mead run per optimized variable.
+const EVALUATIONS_PER_VARIABLE: usize = 2000;
+
+/// Compute the steepest distribution of order `n` found by optimizing its frequency and phases.
+///
+/// Takes about a second for orders in the hundreds, use [`optimized_order`] to cache the result.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// let params = iltcme::coefficients::optimize(5);
+/// assert_eq!(params.a.len(), 5);
+/// assert!(params.cv2 < 0.0175);
+/// # }
+/// ```
+pub fn optimize(n: usize) -> CmeParams {
+    assert!(n > 0, "Distribution must have at least order one");
+
+    // Equally spaced phases with a single gap
+    let pattern = |x: &[f64]| pattern_phases(n, x[1], x[2], x[3]);
+    let initial = [
+        1.05 * (n as f64).powf(-0.16),
+        0.7 * TAU / n as f64,
+        3.6,
+        0.38,
+    ];
+    let mut best = minimize(|x| cv2(x[0], &pattern(x)), &initial, 0.1);
+    best = minimize(|x| cv2(x[0], &pattern(x)), &best, 0.01);
+
+    let mut omega = best[0];
+    let mut phases = pattern(&best);
+
+    if n <= FULL_OPTIMIZATION_ORDER {
+        let initial = std::iter::once(omega).chain(phases).collect::<Vec<_>>();
+        let mut refined = minimize(|x| cv2(x[0], &x[1..]), &initial, 0.05);
+        refined = minimize(|x| cv2(x[0], &x[1..]), &refined, 0.005);
+
+        omega = refined[0];
+        phases = refined[1..].to_vec();
+    }
+
+    from_phases(omega, &phases)
+}
+
+/// Coefficients of the optimized distribution of order `n`, computed once and cached for the lifetime of the program.
+pub fn optimized_order(n: usize) -> CmeOrder<'static> {
+    static CACHE: OnceLock<Mutex<HashMap<usize, &'static Entry>>> = OnceLock::new();
+
+    let mut cache = CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let entry = cache
+        .entry(n)
+        // Every order is only leaked once
+        .or_insert_with(|| Box::leak(Box::new(Entry::from(&optimize(n)))));
+
+    entry.order()
+}
+
+/// Compute the coefficients of the normalized distribution with frequency `omega` and `phases`.
+fn from_phases(omega: f64, phases: &[f64]) -> CmeParams {
+    let coefficients = fourier_coefficients(phases);
+    let [m0, m1, m2] = moments(omega, &coefficients);
+
+    CmeParams {
+        n: phases.len(),
+        a: coefficients[1..].iter().map(|d| 2.0 * d.re / m0).collect(),
+        b: coefficients[1..].iter().map(|d| -2.0 * d.im / m0).collect(),
+        c: coefficients[0].re / m0,
+        omega,
+        mu1: m1 / m0,
+        cv2: m2 * m0 / m1.powi(2) - 1.0,
+    }
+}
+
+/// Phases with an offset, equally spaced on the circle except for a gap of `gap` spacings at fraction `split` of the phases.
+fn pattern_phases(n: usize, offset: f64, gap: f64, split: f64) -> Vec<f64> {
+    let spacing = TAU / (n as f64 + gap.max(0.0));
+    let split = (split.clamp(0.0, 1.0) * n as f64).round() as usize;
+
+    (0..n)
+        .map(|i| {
+            let slot = if i < split { i as f64 } else { i as f64 + gap };
+
+            offset + spacing * slot
+        })
+        .collect()
+}
+
+/// Squared coefficient of variation of the distribution, infinite for invalid parameters.
+fn cv2(omega: f64, phases: &[f64]) -> f64 {
+    if omega <= 0.0 || phases.iter().any(|phase| !phase.is_finite()) {
+        return f64::INFINITY;
+    }
+
+    let [m0, m1, m2] = moments(omega, &fourier_coefficients(phases));
+    let cv2 = m2 * m0 / m1.powi(2) - 1.0;
+
+    if cv2.is_finite() && cv2 > 0.0 {
+        cv2
+    } else {
+        f64::INFINITY
+    }
+}
+
+/// Fourier coefficients $d_0, \ldots, d_n$ of $\prod_i (1 + \cos(\theta - \phi_i)) = \sum_k d_k e^{ik\theta}$ up to a positive constant.
+///
+/// The negative coefficients are the conjugates. Multiplying the factors in an interleaved order keeps each partial product spread over the circle, which avoids the cancellation that otherwise destroys the precision for high orders.
+fn fourier_coefficients(phases: &[f64]) -> Vec<Complex<f64>> {
+    let mut sorted = phases.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let bits = usize::BITS - sorted.len().leading_zeros();
+    let mut order = (0..sorted.len()).collect::<Vec<_>>();
+    order.sort_by_key(|i| i.reverse_bits() >> (usize::BITS - bits));
+
+    // Coefficients of `e^(ik theta)` for `k` from `-n` to `n`
+    let n = sorted.len();
+    let mut coefficients = vec![Complex::new(0.0, 0.0); 2 * n + 1];
+    coefficients[n] = Complex::new(1.0, 0.0);
+    for (multiplied, phase) in order.into_iter().map(|i| sorted[i]).enumerate() {
+        // Factor `1 + e^(i(theta - phi)) / 2 + e^(-i(theta - phi)) / 2`
+        let rotation = Complex::new(0.0, -phase).exp() / 2.0;
+        let previous = coefficients.clone();
+        for k in n - multiplied..=n + multiplied {
+            coefficients[k + 1] += previous[k] * rotation;
+            coefficients[k - 1] += previous[k] * rotation.conj();
+        }
+
+        // Rescale to avoid underflow, the constant cancels in the normalization
+        let scale = coefficients.iter().map(|d| d.modulus()).fold(0.0, f64::max);
+        coefficients.iter_mut().for_each(|d| *d /= scale);
+    }
+
+    coefficients.split_off(n)
+}
+
+/// Moments $\int_0^\infty t^m e^{-t} \sum_k d_k e^{ik\omega t} \, dt = \sum_k \frac{m! \, d_k}{(1 - ik\omega)^{m + 1}}$ for $m = 0, 1, 2$.
+fn moments(omega: f64, coefficients: &[Complex<f64>]) -> [f64; 3] {
+    let mut moments = [
+        coefficients[0].re,
+        coefficients[0].re,
+        2.0 * coefficients[0].re,
+    ];
+    for (k, d) in coefficients.iter().enumerate().skip(1) {
+        let z = Complex::new(1.0, -(k as f64) * omega).recip();
+        // Both the positive and negative frequency
+        moments[0] += 2.0 * (d * z).re;
+        moments[1] += 2.0 * (d * z.powi(2)).re;
+        moments[2] += 4.0 * (d * z.powi(3)).re;
+    }
+
+    moments
+}
+
+/// Minimize a function with the Nelder-Mead simplex method starting from `initial` with a simplex of size `step`.
+fn minimize(mut func: impl FnMut(&[f64]) -> f64, initial: &[f64], step: f64) -> Vec<f64> {
+    let dimensions = initial.len();
+    let max_evaluations = EVALUATIONS_PER_VARIABLE * dimensions;
+
+    let mut simplex = (0..=dimensions)
+        .map(|i| {
+            let mut point = initial.to_vec();
+            if i > 0 {
+                point[i - 1] += step;
+            }
+            let value = func(&point);
+
+            (point, value)
+        })
+        .collect::<Vec<_>>();
+    let mut evaluations = simplex.len();
+
+    // Move the worst point through the centroid of the others
+    let towards = |centroid: &[f64], worst: &[f64], factor: f64| {
+        centroid
+            .iter()
+            .zip(worst)
+            .map(|(c, w)| c + factor * (c - w))
+            .collect::<Vec<_>>()
+    };
+
+    while evaluations < max_evaluations {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let (best, worst) = (simplex[0].1, simplex[dimensions].1);
+        if worst - best <= 1e-15 * best.abs() {
+            break;
+        }
+
+        let centroid = (0..dimensions)
+            .map(|j| {
+                simplex[..dimensions].iter().map(|(p, _)| p[j]).sum::<f64>() / dimensions as f64
+            })
+            .collect::<Vec<_>>();
+        let worst_point = simplex[dimensions].0.clone();
+
+        let reflected = towards(&centroid, &worst_point, 1.0);
+        let reflected_value = func(&reflected);
+        evaluations += 1;
+
+        if reflected_value < best {
+            let expanded = towards(&centroid, &worst_point, 2.0);
+            let expanded_value = func(&expanded);
+            evaluations += 1;
+
+            simplex[dimensions] = if expanded_value < reflected_value {
+                (expanded, expanded_value)
+            } else {
+                (reflected, reflected_value)
+            };
+        } else if reflected_value < simplex[dimensions - 1].1 {
+            simplex[dimensions] = (reflected, reflected_value);
+        } else {
+            let contracted = towards(&centroid, &worst_point, -0.5);
+            let contracted_value = func(&contracted);
+            evaluations += 1;
+
+            if contracted_value < worst {
+                simplex[dimensions] = (contracted, contracted_value);
+            } else {
+                // Shrink everything towards the best point
+                let best_point = simplex[0].0.clone();
+                for (point, value) in simplex.iter_mut().skip(1) {
+                    for (p, b) in point.iter_mut().zip(&best_point) {
+                        *p = b + (*p - b) / 2.0;
+                    }
+                    *value = func(point);
+                }
+                evaluations += dimensions;
+            }
+        }
+    }
+
+    simplex
+        .into_iter()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(point, _)| point)
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn published_distribution() {
+        // Parameters of the published distribution of order 3
+        let params = from_phases(0.884919, &[3.29263, 3.90442, 4.86219]);
+
+        assert!(approx::relative_eq!(
+            params.mu1,
+            3.937623583463675,
+            max_relative = 1e-5
+        ));
+        assert!(approx::relative_eq!(
+            params.cv2,
+            0.04288000357027757,
+            max_relative = 1e-4
+        ));
+        assert!(approx::relative_eq!(
+            params.c,
+            9.77825673092299,
+            max_relative = 1e-4
+        ));
+        // Phase order doesn't matter
+        assert_eq!(params, from_phases(0.884919, &[4.86219, 3.29263, 3.90442]));
+    }
+
+    #[test]
+    fn optimized_distributions() {
+        // Steepest published squared coefficients of variation
+        for (n, published) in [
+            (1, 0.20090156350183885),
+            (4, 0.026156884691722396),
+            (100, 6.412223292092247e-5),
+        ] {
+            let order = optimized_order(n);
+
+            assert_eq!(order.n(), n);
+            assert!(
+                order.cv2() < published * 1.01,
+                "Order {n} has {}",
+                order.cv2()
+            );
+        }
+
+        let result = optimized_order(100).invert(|s| 1.0 / (s + 1.0), 1.0);
+        assert!(approx::relative_eq!(
+            result,
+            (-1.0_f64).exp(),
+            epsilon = 1e-4
+        ));
+
+        // Cached
+        assert_eq!(optimized_order(4), optimized_order(4));
+    }
+}
@@ -0,0 +1,356 @@
+//! Coefficient tables constructed at runtime.
+
+use std::sync::OnceLock;
+
+use super::CmeOrder;
+
+/// Parameters of a concentrated matrix-exponential distribution as published in `iltcme.json`.
+///
+/// The density of the distribution is $c + \sum_k a_k \cos(k \omega t) + b_k \sin(k \omega t)$ modulated by an exponential, normalized with the mean $\mu_1$.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CmeParams {
+    /// Order of the distribution.
+    pub n: usize,
+    /// Cosine coefficients, one for each order.
+    pub a: Vec<f64>,
+    /// Sine coefficients, one for each order.
+    pub b: Vec<f64>,
+    /// Constant coefficient.
+    pub c: f64,
+    /// Base frequency.
+    pub omega: f64,
+    /// Mean of the distribution.
+    pub mu1: f64,
+    /// Squared coefficient of variation.
+    pub cv2: f64,
+}
+
+/// Precomputed weights and nodes of a single distribution.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(super) struct Entry {
+    /// Order of the distribution.
+    n: usize,
+    /// Mean of the distribution.
+    mu1: f64,
+    /// Squared coefficient of variation.
+    cv2: f64,
+    /// Real part of the weights, starting with the node on the real axis.
+    eta_re: Vec<f64>,
+    /// Imaginary part of the weights.
+    eta_im: Vec<f64>,
+    /// Imaginary part of the nodes.
+    beta_im: Vec<f64>,
+}
+
+impl From<&CmeParams> for Entry {
+    fn from(params: &CmeParams) -> Self {
+        let mu1 = params.mu1;
+
+        Self {
+            n: params.n,
+            mu1,
+            cv2: params.cv2,
+            eta_re: std::iter::once(params.c * mu1)
+                .chain(params.a.iter().map(|a| mu1 * a))
+                .collect(),
+            eta_im: std::iter::once(0.0)
+                .chain(params.b.iter().map(|b| mu1 * b))
+                .collect(),
+            beta_im: (0..=params.n)
+                .map(|k| k as f64 * params.omega * mu1)
+                .collect(),
+        }
+    }
+}
+
+impl Entry {
+    /// Borrow as the public coefficients.
+    pub(super) fn order(&self) -> CmeOrder<'_> {
+        CmeOrder {
+            n: self.n,
+            mu1: self.mu1,
+            cv2: self.cv2,
+            eta_re: &self.eta_re,
+            eta_im: &self.eta_im,
+            beta_im: &self.beta_im,
+        }
+    }
+}
+
+/// Table of concentrated matrix-exponential distributions loaded at runtime.
+///
+/// Allows newer or custom coefficient sets and orders beyond the embedded maximum of 500 evaluations.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::coefficients::{CmeParams, CmeTable};
+///
+/// // Exponential distribution, the lowest order
+/// let table = CmeTable::new(vec![CmeParams {
+///     n: 1,
+///     a: vec![-2.4865],
+///     b: vec![-0.8713],
+///     c: 2.6348,
+///     omega: 1.0359,
+///     mu1: 2.2571,
+///     cv2: 0.2009,
+/// }]);
+/// let order = table.order(10).unwrap();
+/// assert_eq!(order.evaluations(), 2);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedCmeTable"))]
+pub struct CmeTable {
+    /// Distributions sorted by order.
+    entries: Vec<Entry>,
+    /// Index in the entries of the steepest distribution for each maximum number of function evaluations, computed on first use.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    steepest: OnceLock<Vec<Option<usize>>>,
+}
+
+impl PartialEq for CmeTable {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl CmeTable {
+    /// Construct from the parameters of each distribution.
+    ///
+    /// Each distribution must have `n` cosine and sine coefficients.
+    pub fn new(mut params: Vec<CmeParams>) -> Self {
+        assert!(
+            params.iter().all(|p| p.a.len() == p.n && p.b.len() == p.n),
+            "Each distribution must have a cosine and sine coefficient for each order"
+        );
+
+        params.sort_by_key(|p| p.n);
+
+        Self::from_entries(params.iter().map(Entry::from).collect())
+    }
+
+    /// Construct from entries sorted by order.
+    fn from_entries(entries: Vec<Entry>) -> Self {
+        Self {
+            entries,
+            steepest: OnceLock::new(),
+        }
+    }
+
+    /// Parse the JSON format of `iltcme.json`, a list of objects with the fields of [`CmeParams`].
+    ///
+    /// Other fields are ignored.
+    #[cfg(feature = "json")]
+    pub fn from_json_str(json: &str) -> Result<Self, serde_json::Error> {
+        Self::from_params(serde_json::from_str(json)?)
+    }
+
+    /// Read and parse the JSON format of `iltcme.json`, see [`CmeTable::from_json_str`].
+    #[cfg(feature = "json")]
+    pub fn from_reader(reader: impl std::io::Read) -> Result<Self, serde_json::Error> {
+        Self::from_params(serde_json::from_reader(reader)?)
+    }
+
+    /// Validate the parsed parameters.
+    #[cfg(feature = "json")]
+    fn from_params(params: Vec<CmeParams>) -> Result<Self, serde_json::Error> {
+        match params.iter().find(|p| p.a.len() != p.n || p.b.len() != p.n) {
+            Some(p) => Err(serde::de::Error::custom(format!(
+                "distribution of order {} must have {} cosine and sine coefficients",
+                p.n, p.n
+            ))),
+            None => Ok(Self::new(params)),
+        }
+    }
+
+    /// Number of distributions in the table.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the table has no distributions.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Largest number of function evaluations of any distribution in the table.
+    pub fn max_evaluations(&self) -> usize {
+        self.entries.last().map_or(0, |entry| entry.n + 1)
+    }
+
+    /// Coefficients like [`CmeTable::order`], with the lowest order when even that has too many evaluations.
+    ///
+    /// Returns [`None`] only when the table is empty.
+    pub(super) fn order_or_lowest(&self, max_function_evals: usize) -> Option<CmeOrder<'_>> {
+        self.order(max_function_evals)
+            .or_else(|| self.entries.first().map(Entry::order))
+    }
+
+    /// Coefficients of the steepest distribution that uses at most `max_function_evals` evaluations.
+    ///
+    /// Returns [`None`] when no distribution in the table is small enough.
+    ///
+    /// The steepest distribution for every maximum is looked up once, on the first call.
+    pub fn order(&self, max_function_evals: usize) -> Option<CmeOrder<'_>> {
+        let steepest = self.steepest.get_or_init(|| self.steepest_indices());
+
+        // All distributions are small enough beyond the largest maximum
+        steepest[max_function_evals.min(steepest.len() - 1)]
+            .map(|index| self.entries[index].order())
+    }
+
+    /// Index of the entry with the lowest squared coefficient of variation that uses at most each maximum number of function evaluations, up to [`CmeTable::max_evaluations`].
+    fn steepest_indices(&self) -> Vec<Option<usize>> {
+        let mut steepest: Option<usize> = None;
+        let mut next = 0;
+
+        (0..=self.max_evaluations())
+            .map(|max_function_evals| {
+                // The entries are sorted by order, so each maximum adds the next ones
+                while let Some(entry) = self
+                    .entries
+                    .get(next)
+                    .filter(|entry| entry.n < max_function_evals)
+                {
+                    if steepest.is_none_or(|index| entry.cv2 < self.entries[index].cv2) {
+                        steepest = Some(next);
+                    }
+                    next += 1;
+                }
+
+                steepest
+            })
+            .collect()
+    }
+}
+
+/// Deserialized distributions of [`CmeTable`] before they're validated and sorted by order.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedCmeTable {
+    entries: Vec<Entry>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedCmeTable> for CmeTable {
+    type Error = String;
+
+    fn try_from(unchecked: UncheckedCmeTable) -> Result<Self, Self::Error> {
+        let mut entries = unchecked.entries;
+        if let Some(entry) = entries.iter().find(|entry| {
+            [&entry.eta_re, &entry.eta_im, &entry.beta_im]
+                .iter()
+                .any(|values| values.len() != entry.n + 1)
+        }) {
+            return Err(format!(
+                "distribution of order {} must have {} weights and nodes",
+                entry.n,
+                entry.n + 1
+            ));
+        }
+
+        entries.sort_by_key(|entry| entry.n);
+
+        Ok(Self::from_entries(entries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steepest_lookup() {
+        let params = |n: usize, cv2: f64| CmeParams {
+            n,
+            a: vec![0.0; n],
+            b: vec![0.0; n],
+            c: 1.0,
+            omega: 1.0,
+            mu1: 1.0,
+            cv2,
+        };
+        // Unsorted, with a steeper low order and equally steep orders
+        let table = CmeTable::new(vec![
+            params(4, 0.1),
+            params(1, 0.5),
+            params(2, 0.05),
+            params(6, 0.05),
+            params(8, 0.01),
+        ]);
+
+        let orders = (0..12)
+            .map(|max| table.order(max).map(|order| order.n()))
+            .collect::<Vec<_>>();
+        // Same as scanning all distributions that are small enough
+        let scanned = (0..12)
+            .map(|max| {
+                table
+                    .entries
+                    .iter()
+                    .take_while(|entry| entry.n < max)
+                    .min_by(|a, b| a.cv2.total_cmp(&b.cv2))
+                    .map(|entry| entry.n)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(orders, scanned);
+        assert_eq!(
+            orders,
+            [
+                None,
+                None,
+                Some(1),
+                Some(2),
+                Some(2),
+                Some(2),
+                Some(2),
+                Some(2),
+                Some(2),
+                Some(8),
+                Some(8),
+                Some(8)
+            ]
+        );
+        assert_eq!(table.order_or_lowest(1).map(|order| order.n()), Some(1));
+        assert_eq!(table.order_or_lowest(10), table.order(10));
+        assert_eq!(table.clone(), table);
+        assert_eq!(CmeTable::new(Vec::new()).order(10), None);
+        assert_eq!(CmeTable::new(Vec::new()).order_or_lowest(10), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let params = |n: usize, cv2: f64| CmeParams {
+            n,
+            a: vec![-1.0; n],
+            b: vec![0.5; n],
+            c: 1.0,
+            omega: 1.0,
+            mu1: 1.0,
+            cv2,
+        };
+        let table = CmeTable::new(vec![params(1, 0.5), params(2, 0.1)]);
+        let json = serde_json::to_string(&table).unwrap();
+        let deserialized = serde_json::from_str::<CmeTable>(&json).unwrap();
+        assert_eq!(deserialized, table);
+        assert_eq!(deserialized.order(3), table.order(3));
+
+        // Sorted by order like the constructor
+        let mut value = serde_json::to_value(&table).unwrap();
+        value["entries"].as_array_mut().unwrap().reverse();
+        let unsorted = serde_json::from_value::<CmeTable>(value.clone()).unwrap();
+        assert_eq!(unsorted, table);
+        assert_eq!(unsorted.order(3).map(|order| order.n()), Some(2));
+
+        // Validated like the constructor
+        value["entries"][0]["beta_im"] = serde_json::json!([0.0]);
+        assert!(serde_json::from_value::<CmeTable>(value).is_err());
+    }
+}
@@ -0,0 +1,616 @@
+//! Combine and transform Laplace transform functions using the properties of the Laplace transform.
+//!
+//! Each combinator returns a new Laplace transform function that can be passed to [`crate::laplace_inversion`].
+//! Composite models are easier to build as a [`TransformExpr`], an expression tree of the same properties that's only evaluated when it's inverted.
+//!
+//! # Example
+//!
+//! ```rust
+//! # fn main() {
+//! use iltcme::combinators::{add, scale_time};
+//!
+//! // sin(2t) + sin(t)
+//! let sine = |s: nalgebra::Complex<f64>| 1.0 / (s.powi(2) + 1.0);
+//! let result = iltcme::laplace_inversion(add(scale_time(sine, 2.0), sine), 1.0, 50);
+//! approx::assert_relative_eq!(result, 2.0_f64.sin() + 1.0_f64.sin(), epsilon = 0.001);
+//! # }
+//! ```
+
+use std::{
+    ops::{Add, Mul, Neg, Sub},
+    rc::Rc,
+};
+
+use nalgebra::Complex;
+
+use crate::{delay::DelayedTransform, BoxedLaplaceFunc};
+
+/// Shared Laplace transform function of a term of an expanded [`TransformExpr`].
+type SharedLaplaceFunc<'a> = Rc<dyn Fn(Complex<f64>) -> Complex<f64> + 'a>;
+
+/// Term $e^{-s T} G(s - a)$ of an expanded [`TransformExpr`], with the inverse $e^{a (t - T)} g(t - T)$ from time $T$ on.
+#[derive(Clone)]
+struct Term<'a> {
+    /// Delay $T$.
+    delay: f64,
+    /// Rate $a$ of the exponential factor.
+    rate: f64,
+    /// Delay-free and unshifted Laplace transform function $G$.
+    laplace_func: SharedLaplaceFunc<'a>,
+}
+
+/// Scaling theorem, the transform of $f(a t)$ is $\frac{1}{a} F(\frac{s}{a})$.
+///
+/// `a` must be positive.
+pub fn scale_time(
+    laplace_func: impl Fn(Complex<f64>) -> Complex<f64>,
+    a: f64,
+) -> impl Fn(Complex<f64>) -> Complex<f64> {
+    move |s| laplace_func(s / a) / a
+}
+
+/// Frequency shift theorem, the transform of $e^{a t} f(t)$ is $F(s - a)$.
+pub fn shift_frequency(
+    laplace_func: impl Fn(Complex<f64>) -> Complex<f64>,
+    a: f64,
+) -> impl Fn(Complex<f64>) -> Complex<f64> {
+    move |s| laplace_func(s - a)
+}
+
+/// Integration theorem, the transform of $\int_0^t f(\tau) d\tau$ is $\frac{F(s)}{s}$.
+pub fn integrate(
+    laplace_func: impl Fn(Complex<f64>) -> Complex<f64>,
+) -> impl Fn(Complex<f64>) -> Complex<f64> {
+    move |s| laplace_func(s) / s
+}
+
+/// Differentiation theorem, the transform of $f'(t)$ is $s F(s) - f(0)$.
+pub fn differentiate(
+    laplace_func: impl Fn(Complex<f64>) -> Complex<f64>,
+    initial_value: f64,
+) -> impl Fn(Complex<f64>) -> Complex<f64> {
+    move |s| s * laplace_func(s) - initial_value
+}
+
+/// Linearity, the transform of $c f(t)$ is $c F(s)$.
+pub fn scale(
+    laplace_func: impl Fn(Complex<f64>) -> Complex<f64>,
+    c: f64,
+) -> impl Fn(Complex<f64>) -> Complex<f64> {
+    move |s| laplace_func(s) * c
+}
+
+/// Linearity, the transform of $f(t) + g(t)$ is $F(s) + G(s)$.
+pub fn add(
+    laplace_func: impl Fn(Complex<f64>) -> Complex<f64>,
+    other_laplace_func: impl Fn(Complex<f64>) -> Complex<f64>,
+) -> impl Fn(Complex<f64>) -> Complex<f64> {
+    move |s| laplace_func(s) + other_laplace_func(s)
+}
+
+/// Time shift theorem, the transform of $f(t - a)$ starting at $t = a$ is $e^{-a s} F(s)$.
+///
+/// Unlike the other combinators this returns an expression, so [`TransformExpr::invert`] shifts the inverse exactly instead of inverting the delay factor numerically.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::combinators::delayed;
+///
+/// // Sine that only starts at `t = 2`
+/// let expr = delayed(|s| 1.0 / (s * s + 1.0), 2.0);
+/// assert_eq!(expr.invert(1.5, 50), 0.0);
+/// approx::assert_relative_eq!(expr.invert(3.0, 50), 1.0_f64.sin(), epsilon = 0.001);
+/// # }
+/// ```
+pub fn delayed<'a>(
+    laplace_func: impl Fn(Complex<f64>) -> Complex<f64> + 'a,
+    a: f64,
+) -> TransformExpr<'a> {
+    TransformExpr::new(laplace_func).shift_time(a)
+}
+
+/// Frequency shift theorem, the transform of the damped $e^{-a t} f(t)$ is $F(s + a)$.
+///
+/// Like [`delayed`] this returns an expression, so [`TransformExpr::invert`] multiplies the exact factor $e^{-a t}$ after inverting $F$.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::combinators::damped;
+///
+/// // Strongly damped sine, the relative accuracy is kept in the tail
+/// let expr = damped(|s| 1.0 / (s * s + 1.0), 5.0);
+/// let exact = (-5.0 * 10.0_f64).exp() * 10.0_f64.sin();
+/// approx::assert_relative_eq!(expr.invert(10.0, 100), exact, max_relative = 0.01);
+/// # }
+/// ```
+pub fn damped<'a>(
+    laplace_func: impl Fn(Complex<f64>) -> Complex<f64> + 'a,
+    a: f64,
+) -> TransformExpr<'a> {
+    TransformExpr::new(laplace_func).shift_frequency(-a)
+}
+
+/// Expression tree of Laplace transforms combined with the properties of the Laplace transform.
+///
+/// Sums, differences and scalar multiples use the operators, the product of two expressions is the transform of the convolution of their inverses.
+/// Nothing is evaluated until [`TransformExpr::evaluate`] is called, [`TransformExpr::into_fn`] turns the whole tree into a single Laplace transform function.
+/// [`TransformExpr::invert`] recognizes the time and frequency shifts in the tree and applies them exactly.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::combinators::TransformExpr;
+///
+/// // Convolution of `e^(-t)` with a unit step delayed by one, plus twice `t`
+/// let decay = TransformExpr::new(|s| 1.0 / (s + 1.0));
+/// let step = TransformExpr::new(|s| 1.0 / s).shift_time(1.0);
+/// let ramp = TransformExpr::new(|s| 1.0 / (s * s));
+/// let expr = decay * step + 2.0 * ramp;
+///
+/// let result = iltcme::laplace_inversion(expr.into_fn(), 3.0, 100);
+/// approx::assert_relative_eq!(result, 1.0 - (-2.0_f64).exp() + 6.0, epsilon = 0.01);
+/// # }
+/// ```
+pub struct TransformExpr<'a> {
+    /// Root of the expression tree.
+    node: Node<'a>,
+}
+
+/// Operation in the expression tree of a [`TransformExpr`].
+enum Node<'a> {
+    /// Laplace transform function $F(s)$.
+    Transform(BoxedLaplaceFunc<'a>),
+    /// Transform of $f(t) + g(t)$.
+    Sum(Box<Node<'a>>, Box<Node<'a>>),
+    /// Transform of $c f(t)$.
+    Scale(Box<Node<'a>>, f64),
+    /// Transform of the convolution $\int_0^t f(t - \tau) g(\tau) d\tau$.
+    Product(Box<Node<'a>>, Box<Node<'a>>),
+    /// Transform of $f(t - a)$ for $t \geq a$ and zero before.
+    ShiftTime(Box<Node<'a>>, f64),
+    /// Transform of $e^{a t} f(t)$.
+    ShiftFrequency(Box<Node<'a>>, f64),
+    /// Transform of $f(a t)$.
+    ScaleTime(Box<Node<'a>>, f64),
+    /// Transform of $\int_0^t f(\tau) d\tau$.
+    Integrate(Box<Node<'a>>),
+    /// Transform of $f'(t)$ with the initial value $f(0)$.
+    Differentiate(Box<Node<'a>>, f64),
+}
+
+impl Node<'_> {
+    /// Evaluate the subtree at `s`.
+    fn evaluate(&self, s: Complex<f64>) -> Complex<f64> {
+        match self {
+            Self::Transform(laplace_func) => laplace_func(s),
+            Self::Sum(left, right) => left.evaluate(s) + right.evaluate(s),
+            Self::Scale(node, c) => node.evaluate(s) * *c,
+            Self::Product(left, right) => left.evaluate(s) * right.evaluate(s),
+            Self::ShiftTime(node, a) => (-s * *a).exp() * node.evaluate(s),
+            Self::ShiftFrequency(node, a) => node.evaluate(s - *a),
+            Self::ScaleTime(node, a) => node.evaluate(s / *a) / *a,
+            Self::Integrate(node) => node.evaluate(s) / s,
+            Self::Differentiate(node, initial_value) => s * node.evaluate(s) - *initial_value,
+        }
+    }
+
+    /// Expand the subtree into delayed and shifted terms $\sum_i e^{-s T_i} G_i(s - a_i)$.
+    fn terms(&self) -> Vec<Term<'_>> {
+        match self {
+            Self::Transform(laplace_func) => vec![Term {
+                delay: 0.0,
+                rate: 0.0,
+                laplace_func: Rc::new(laplace_func),
+            }],
+            Self::Sum(left, right) => {
+                let mut terms = left.terms();
+                terms.extend(right.terms());
+                terms
+            }
+            Self::Scale(node, c) => map_terms(node, |g| Term {
+                laplace_func: Rc::new(move |u| (g.laplace_func)(u) * *c),
+                ..g
+            }),
+            // Shifting one function to the rate of the other would move its singularities, so different rates are evaluated numerically
+            Self::Product(left, right) => {
+                let right = right.terms();
+                left.terms()
+                    .into_iter()
+                    .flat_map(|left| {
+                        right.iter().map(move |right| {
+                            let (left, right) = if left.rate == right.rate {
+                                (left.clone(), right.clone())
+                            } else {
+                                (left.clone().unshifted(), right.clone().unshifted())
+                            };
+                            Term {
+                                delay: left.delay + right.delay,
+                                rate: left.rate,
+                                laplace_func: Rc::new(move |u| {
+                                    (left.laplace_func)(u) * (right.laplace_func)(u)
+                                }),
+                            }
+                        })
+                    })
+                    .collect()
+            }
+            Self::ShiftTime(node, a) => map_terms(node, |g| Term {
+                delay: g.delay + a,
+                ..g
+            }),
+            // `e^(-(s - a) T) G(s - a - b) = e^(-s T) e^(a T) G(s - (a + b))`
+            Self::ShiftFrequency(node, a) => map_terms(node, |g| {
+                let factor = (a * g.delay).exp();
+                Term {
+                    delay: g.delay,
+                    rate: g.rate + a,
+                    laplace_func: Rc::new(move |u| (g.laplace_func)(u) * factor),
+                }
+            }),
+            // `e^(-s T / a) G((s - a b) / a) / a`
+            Self::ScaleTime(node, a) => map_terms(node, |g| Term {
+                delay: g.delay / a,
+                rate: g.rate * a,
+                laplace_func: Rc::new(move |u| (g.laplace_func)(u / *a) / *a),
+            }),
+            // Dividing by `u + b` would add a pole at `-b`, so shifted terms are evaluated numerically
+            Self::Integrate(node) => map_terms(node, |g| {
+                let g = g.unshifted();
+                Term {
+                    laplace_func: Rc::new(move |s| (g.laplace_func)(s) / s),
+                    ..g
+                }
+            }),
+            Self::Differentiate(node, initial_value) => {
+                let mut terms = map_terms(node, |g| Term {
+                    laplace_func: Rc::new(move |u| (u + g.rate) * (g.laplace_func)(u)),
+                    ..g
+                });
+                terms.push(Term {
+                    delay: 0.0,
+                    rate: 0.0,
+                    laplace_func: Rc::new(move |_| Complex::from(-*initial_value)),
+                });
+                terms
+            }
+        }
+    }
+}
+
+impl Term<'_> {
+    /// Move the exponential factor back into the Laplace transform function.
+    fn unshifted(self) -> Self {
+        if self.rate == 0.0 {
+            return self;
+        }
+
+        let (rate, laplace_func) = (self.rate, self.laplace_func);
+        Self {
+            delay: self.delay,
+            rate: 0.0,
+            laplace_func: Rc::new(move |s| laplace_func(s - rate)),
+        }
+    }
+}
+
+/// Expand the subtree and transform each term.
+fn map_terms<'a>(node: &'a Node<'_>, map: impl FnMut(Term<'a>) -> Term<'a>) -> Vec<Term<'a>> {
+    node.terms().into_iter().map(map).collect()
+}
+
+impl<'a> TransformExpr<'a> {
+    /// Construct from a Laplace transform function.
+    pub fn new(laplace_func: impl Fn(Complex<f64>) -> Complex<f64> + 'a) -> Self {
+        Self {
+            node: Node::Transform(Box::new(laplace_func)),
+        }
+    }
+
+    /// Time shift theorem, the transform of $f(t - a)$ starting at $t = a$ is $e^{-a s} F(s)$.
+    ///
+    /// The delay factor is only evaluated by [`TransformExpr::evaluate`], [`TransformExpr::invert`] shifts the inverse exactly.
+    pub fn shift_time(self, a: f64) -> Self {
+        assert!(a >= 0.0, "Delay can't be negative");
+
+        self.wrap(|node| Node::ShiftTime(node, a))
+    }
+
+    /// Frequency shift theorem, the transform of $e^{a t} f(t)$ is $F(s - a)$.
+    ///
+    /// The shift is only evaluated by [`TransformExpr::evaluate`], [`TransformExpr::invert`] multiplies the exponential factor exactly.
+    pub fn shift_frequency(self, a: f64) -> Self {
+        self.wrap(|node| Node::ShiftFrequency(node, a))
+    }
+
+    /// Scaling theorem, the transform of $f(a t)$ is $\frac{1}{a} F(\frac{s}{a})$.
+    pub fn scale_time(self, a: f64) -> Self {
+        assert!(a > 0.0, "Time scale must be positive");
+
+        self.wrap(|node| Node::ScaleTime(node, a))
+    }
+
+    /// Integration theorem, the transform of $\int_0^t f(\tau) d\tau$ is $\frac{F(s)}{s}$.
+    pub fn integrate(self) -> Self {
+        self.wrap(Node::Integrate)
+    }
+
+    /// Differentiation theorem, the transform of $f'(t)$ is $s F(s) - f(0)$.
+    pub fn differentiate(self, initial_value: f64) -> Self {
+        self.wrap(|node| Node::Differentiate(node, initial_value))
+    }
+
+    /// Evaluate the expression at `s`.
+    pub fn evaluate(&self, s: Complex<f64>) -> Complex<f64> {
+        self.node.evaluate(s)
+    }
+
+    /// Expand the expression into a sum of delayed terms, with the delay factors of the time shifts taken out.
+    ///
+    /// Products of sums are multiplied out, so every combination of delays becomes a separate term.
+    pub fn to_delayed_transform(&self) -> DelayedTransform<'_> {
+        self.node
+            .terms()
+            .into_iter()
+            .fold(DelayedTransform::new(), |transform, term| {
+                transform.with_term(term.delay, move |s| (term.laplace_func)(s - term.rate))
+            })
+    }
+
+    /// Calculate the Laplace inversion at time `t`, applying the time and frequency shifts exactly.
+    ///
+    /// Terms delayed by `t` or more don't contribute, so the inverse is zero before the smallest delay.
+    /// The exponential factors of the frequency shifts are multiplied after the inversion, so a damped inverse keeps its relative accuracy where it's small.
+    /// Only shifts below an integration, or in a product with a different shift, are inverted numerically, since taking them out would move a singularity to the right half-plane.
+    ///
+    /// Maximum number of evaluations per term is given by [`crate::coefficients::max_evaluations`].
+    pub fn invert(&self, t: f64, max_function_evals: usize) -> f64 {
+        self.node
+            .terms()
+            .iter()
+            .filter(|term| t > term.delay)
+            .map(|term| {
+                let t = t - term.delay;
+                (term.rate * t).exp()
+                    * crate::laplace_inversion(&*term.laplace_func, t, max_function_evals)
+            })
+            .sum()
+    }
+
+    /// Turn the expression into a single Laplace transform function.
+    pub fn into_fn(self) -> impl Fn(Complex<f64>) -> Complex<f64> + 'a {
+        move |s| self.evaluate(s)
+    }
+
+    /// Put the expression below a new root.
+    fn wrap(self, root: impl FnOnce(Box<Node<'a>>) -> Node<'a>) -> Self {
+        Self {
+            node: root(Box::new(self.node)),
+        }
+    }
+}
+
+impl<'a> Add for TransformExpr<'a> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.wrap(|node| Node::Sum(node, Box::new(rhs.node)))
+    }
+}
+
+impl<'a> Sub for TransformExpr<'a> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Neg for TransformExpr<'_> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self * -1.0
+    }
+}
+
+impl Mul<f64> for TransformExpr<'_> {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        self.wrap(|node| Node::Scale(node, rhs))
+    }
+}
+
+impl<'a> Mul<TransformExpr<'a>> for f64 {
+    type Output = TransformExpr<'a>;
+
+    fn mul(self, rhs: TransformExpr<'a>) -> Self::Output {
+        rhs * self
+    }
+}
+
+/// Product in the Laplace domain, the transform of the convolution of the inverses.
+impl<'a> Mul for TransformExpr<'a> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.wrap(|node| Node::Product(node, Box::new(rhs.node)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::ComplexField;
+
+    use super::*;
+
+    fn sine(s: Complex<f64>) -> Complex<f64> {
+        (1.0 + s.powi(2)).recip()
+    }
+
+    fn assert_inversion(laplace_func: impl Fn(Complex<f64>) -> Complex<f64>, expected: f64) {
+        let result = crate::laplace_inversion(laplace_func, 1.5, 100);
+        assert!(
+            approx::relative_eq!(result, expected, epsilon = 0.01),
+            "Result {result} differs from {expected}"
+        );
+    }
+
+    #[test]
+    fn combinators() {
+        assert_inversion(scale_time(sine, 3.0), (3.0 * 1.5_f64).sin());
+        assert_inversion(
+            shift_frequency(sine, -0.5),
+            (-0.5 * 1.5_f64).exp() * 1.5_f64.sin(),
+        );
+        assert_inversion(integrate(sine), 1.0 - 1.5_f64.cos());
+        assert_inversion(
+            differentiate(|s| s / (1.0 + s.powi(2)), 1.0),
+            -1.5_f64.sin(),
+        );
+        assert_inversion(scale(sine, -2.0), -2.0 * 1.5_f64.sin());
+        assert_inversion(
+            add(sine, integrate(sine)),
+            1.5_f64.sin() + 1.0 - 1.5_f64.cos(),
+        );
+    }
+
+    #[test]
+    fn transform_expr() {
+        let expr =
+            |laplace_func: fn(Complex<f64>) -> Complex<f64>| TransformExpr::new(laplace_func);
+
+        // Same results as the combinators
+        assert_inversion(expr(sine).scale_time(3.0).into_fn(), (3.0 * 1.5_f64).sin());
+        assert_inversion(
+            expr(sine).shift_frequency(-0.5).into_fn(),
+            (-0.5 * 1.5_f64).exp() * 1.5_f64.sin(),
+        );
+        assert_inversion(expr(sine).integrate().into_fn(), 1.0 - 1.5_f64.cos());
+        assert_inversion(
+            expr(|s| s / (1.0 + s.powi(2))).differentiate(1.0).into_fn(),
+            -1.5_f64.sin(),
+        );
+        assert_inversion(
+            (-2.0 * expr(sine) - expr(sine).integrate()).into_fn(),
+            -2.0 * 1.5_f64.sin() - 1.0 + 1.5_f64.cos(),
+        );
+
+        // Convolution of `sin(t)` with itself, and a delayed ramp
+        assert_inversion(
+            (expr(sine) * expr(sine)).into_fn(),
+            (1.5_f64.sin() - 1.5 * 1.5_f64.cos()) / 2.0,
+        );
+        assert_inversion(expr(|s| s.powi(-2)).shift_time(0.5).into_fn(), 1.0);
+
+        // Nested operations are applied from the inside out
+        let s = Complex::new(0.3, 2.0);
+        let nested = expr(sine).shift_frequency(1.0).scale_time(2.0).integrate();
+        assert_eq!(nested.evaluate(s), sine(s / 2.0 - 1.0) / 2.0 / s);
+    }
+
+    #[test]
+    fn delayed_terms() {
+        let decay = TransformExpr::new(|s| (s + 1.0).recip());
+        // Unit pulse between one and two filtered by a first-order system
+        let pulse = delayed(|s| s.recip(), 1.0) - delayed(|s| s.recip(), 2.0);
+        let response = pulse * decay;
+        // `e^(-2t)` starting at `t = 0.5`
+        let shifted = delayed(|s| s.recip(), 1.0)
+            .shift_frequency(-1.0)
+            .scale_time(2.0);
+        // Derivative of a ramp starting at one is a step
+        let step = delayed(|s| s.powi(-2), 1.0).differentiate(0.0);
+
+        for t in [0.4, 0.6, 1.5, 2.5, 4.0] {
+            let expected = if t < 1.0 {
+                0.0
+            } else if t < 2.0 {
+                1.0 - (1.0 - t).exp()
+            } else {
+                (2.0 - t).exp() - (1.0 - t).exp()
+            };
+            let result = response.invert(t, 100);
+            assert!(
+                approx::relative_eq!(result, expected, epsilon = 1e-4),
+                "Result {result} at time {t} differs from {expected}"
+            );
+
+            let expected = if t < 0.5 { 0.0 } else { (-2.0 * t).exp() };
+            assert!(approx::relative_eq!(
+                shifted.invert(t, 100),
+                expected,
+                epsilon = 1e-5
+            ));
+
+            let expected = f64::from(u8::from(t > 1.0));
+            assert!(approx::relative_eq!(
+                step.invert(t, 100),
+                expected,
+                epsilon = 1e-5
+            ));
+        }
+
+        // Products of sums are multiplied out, the evaluation is unchanged
+        let delayed = response.to_delayed_transform();
+        assert_eq!(delayed.delays().collect::<Vec<_>>(), vec![1.0, 2.0]);
+        let s = Complex::new(0.5, 3.0);
+        assert!(approx::relative_eq!(
+            delayed.evaluate(s).norm(),
+            response.evaluate(s).norm(),
+            epsilon = 1e-14
+        ));
+    }
+
+    #[test]
+    fn damped_terms() {
+        // Relative accuracy is kept in the tail, unlike when the shift is inverted numerically
+        let expr = damped(sine, 5.0);
+        let t = 10.0;
+        let exact = (-5.0 * t).exp() * t.sin();
+        assert!(approx::relative_eq!(
+            expr.invert(t, 100),
+            exact,
+            max_relative = 1e-3
+        ));
+        let naive = crate::laplace_inversion(|s| expr.evaluate(s), t, 100);
+        assert!((naive - exact).abs() > 1e-3 * exact.abs());
+
+        // Integral `(1 - e^(-2t)) / 2` and the convolution of `e^(-2t)` with `e^(-t)`
+        let integral = damped(|s| s.recip(), 2.0).integrate();
+        let convolution = damped(|s| s.recip(), 2.0) * damped(|s| s.recip(), 1.0);
+        // Damping and delay in either order
+        let delayed_decay = damped(|s| s.recip(), 1.0).shift_time(1.0);
+        let decay_delayed = delayed(|s| s.recip(), 1.0).shift_frequency(-1.0);
+        for t in [0.5, 2.0, 8.0] {
+            assert!(approx::relative_eq!(
+                integral.invert(t, 100),
+                (1.0 - (-2.0 * t).exp()) / 2.0,
+                epsilon = 1e-4
+            ));
+            assert!(approx::relative_eq!(
+                convolution.invert(t, 100),
+                (-t).exp() - (-2.0 * t).exp(),
+                epsilon = 1e-4
+            ));
+
+            let expected = if t < 1.0 { 0.0 } else { (1.0 - t).exp() };
+            assert!(approx::relative_eq!(
+                delayed_decay.invert(t, 100),
+                expected,
+                epsilon = 1e-4
+            ));
+            let expected = if t < 1.0 { 0.0 } else { (-t).exp() };
+            assert!(approx::relative_eq!(
+                decay_delayed.invert(t, 100),
+                expected,
+                epsilon = 1e-4
+            ));
+        }
+    }
+}
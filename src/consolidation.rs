@@ -0,0 +1,311 @@
+//! Consolidation of saturated soil layers under a sudden uniform load.
+//!
+//! The excess pore pressure $u$ dissipates by diffusion, starting from the uniform initial excess pressure $u_0$ carried by the water when the load is applied.
+//! One-dimensional vertical flow is Terzaghi's theory, radial flow towards a vertical drain is Barron's free strain theory.
+//! In the Laplace domain both have closed-form solutions, with $q = \sqrt{s / c}$ for the coefficient of consolidation $c$, so pore pressures and the degree of consolidation follow from a single inversion instead of a series over eigenvalues.
+//!
+//! Pressures are relative to $u_0$, and the degree of consolidation is the dissipated fraction of the average excess pressure, $U = 1 - \bar{u} / u_0$.
+
+use nalgebra::Complex;
+
+use crate::special::{bessel_ie, bessel_ke};
+
+/// One-dimensional consolidation of a layer draining at the top, Terzaghi's theory
+///
+/// $$
+/// \frac{\partial u}{\partial t} = c_v \frac{\partial^2 u}{\partial z^2}, \quad u(0, t) = 0, \quad \frac{\partial u}{\partial z}(H, t) = 0
+/// $$
+///
+/// with $z$ measured down from the drained boundary and the drainage length $H$.
+/// A layer draining at the top and bottom behaves like two layers with half its thickness as drainage length.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::consolidation::Terzaghi;
+///
+/// // Half consolidated at a time factor of about 0.197
+/// let layer = Terzaghi::new(1e-7, 2.0);
+/// let t = 0.197 * 4.0 / 1e-7;
+/// let degree = layer.degree_of_consolidation(&[t], 100);
+/// approx::assert_relative_eq!(degree[0], 0.5, epsilon = 1e-3);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Terzaghi {
+    /// Coefficient of consolidation $c_v$.
+    consolidation_coefficient: f64,
+    /// Length $H$ of the longest drainage path.
+    drainage_length: f64,
+}
+
+impl Terzaghi {
+    /// Construct from the coefficient of consolidation $c_v$ and the length $H$ of the longest drainage path.
+    pub fn new(consolidation_coefficient: f64, drainage_length: f64) -> Self {
+        assert!(
+            consolidation_coefficient > 0.0,
+            "Coefficient of consolidation must be positive"
+        );
+        assert!(drainage_length > 0.0, "Drainage length must be positive");
+
+        Self {
+            consolidation_coefficient,
+            drainage_length,
+        }
+    }
+
+    /// Coefficient of consolidation $c_v$.
+    pub fn consolidation_coefficient(&self) -> f64 {
+        self.consolidation_coefficient
+    }
+
+    /// Length $H$ of the longest drainage path.
+    pub fn drainage_length(&self) -> f64 {
+        self.drainage_length
+    }
+
+    /// Dimensionless time factor $T_v = c_v t / H^2$.
+    pub fn time_factor(&self, t: f64) -> f64 {
+        self.consolidation_coefficient * t / self.drainage_length.powi(2)
+    }
+
+    /// Evaluate the transform $\frac{\tanh(qH)}{s q H}$ of the degree of consolidation.
+    pub fn degree_transform(&self, s: Complex<f64>) -> Complex<f64> {
+        let x = (s / self.consolidation_coefficient).sqrt() * self.drainage_length;
+        // `tanh` without overflow, the real part of `x` is positive
+        let decay = (-2.0 * x).exp();
+
+        (1.0 - decay) / (1.0 + decay) / (s * x)
+    }
+
+    /// Evaluate the transform $\frac{1}{s} \left( 1 - \frac{\cosh(q (H - z))}{\cosh(q H)} \right)$ of the relative excess pore pressure at `depth`.
+    pub fn pore_pressure_transform(&self, depth: f64, s: Complex<f64>) -> Complex<f64> {
+        let q = (s / self.consolidation_coefficient).sqrt();
+        let ratio = ((-q * depth).exp() + (-q * (2.0 * self.drainage_length - depth)).exp())
+            / (1.0 + (-2.0 * q * self.drainage_length).exp());
+
+        (1.0 - ratio) / s
+    }
+
+    /// Calculate the degree of consolidation at each of the times.
+    pub fn degree_of_consolidation(&self, times: &[f64], max_function_evals: usize) -> Vec<f64> {
+        times
+            .iter()
+            .map(|t| crate::laplace_inversion(|s| self.degree_transform(s), *t, max_function_evals))
+            .collect()
+    }
+
+    /// Calculate the excess pore pressure relative to the initial excess pressure at each of the depths at time `t`.
+    pub fn pore_pressure(&self, depths: &[f64], t: f64, max_function_evals: usize) -> Vec<f64> {
+        depths
+            .iter()
+            .map(|depth| {
+                crate::laplace_inversion(
+                    |s| self.pore_pressure_transform(*depth, s),
+                    t,
+                    max_function_evals,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Radial consolidation around a vertical drain, Barron's free strain theory
+///
+/// $$
+/// \frac{\partial u}{\partial t} = c_h \left( \frac{\partial^2 u}{\partial r^2} + \frac{1}{r} \frac{\partial u}{\partial r} \right), \quad u(r_w, t) = 0, \quad \frac{\partial u}{\partial r}(r_e, t) = 0
+/// $$
+///
+/// with the drain radius $r_w$ and the radius $r_e$ of the cylinder of soil drained by it, about half the drain spacing.
+/// The solution of the transform uses the modified Bessel functions, for the degree of consolidation
+///
+/// $$
+/// \bar{U}(s) = \frac{2 r_w}{s q (r_e^2 - r_w^2)} \frac{K_1(q r_w) I_1(q r_e) - I_1(q r_w) K_1(q r_e)}{I_0(q r_w) K_1(q r_e) + K_0(q r_w) I_1(q r_e)}
+/// $$
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::consolidation::Barron;
+///
+/// // Drains with a radius of 5 cm at 1 m from each other
+/// let drain = Barron::new(1e-7, 0.05, 0.5);
+/// let degree = drain.degree_of_consolidation(&[1e6, 1e7], 100);
+/// assert!(degree[0] < degree[1] && degree[1] < 1.0);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Barron {
+    /// Horizontal coefficient of consolidation $c_h$.
+    consolidation_coefficient: f64,
+    /// Radius $r_w$ of the drain.
+    drain_radius: f64,
+    /// Radius $r_e$ of the cylinder drained by a single drain.
+    influence_radius: f64,
+}
+
+impl Barron {
+    /// Construct from the horizontal coefficient of consolidation $c_h$, the radius $r_w$ of the drain and the radius $r_e$ of the cylinder drained by it.
+    pub fn new(consolidation_coefficient: f64, drain_radius: f64, influence_radius: f64) -> Self {
+        assert!(
+            consolidation_coefficient > 0.0,
+            "Coefficient of consolidation must be positive"
+        );
+        assert!(
+            0.0 < drain_radius && drain_radius < influence_radius,
+            "Drain radius must be positive and smaller than the influence radius"
+        );
+
+        Self {
+            consolidation_coefficient,
+            drain_radius,
+            influence_radius,
+        }
+    }
+
+    /// Horizontal coefficient of consolidation $c_h$.
+    pub fn consolidation_coefficient(&self) -> f64 {
+        self.consolidation_coefficient
+    }
+
+    /// Radius $r_w$ of the drain.
+    pub fn drain_radius(&self) -> f64 {
+        self.drain_radius
+    }
+
+    /// Radius $r_e$ of the cylinder drained by a single drain.
+    pub fn influence_radius(&self) -> f64 {
+        self.influence_radius
+    }
+
+    /// Dimensionless time factor $T_h = c_h t / (2 r_e)^2$.
+    pub fn time_factor(&self, t: f64) -> f64 {
+        self.consolidation_coefficient * t / (2.0 * self.influence_radius).powi(2)
+    }
+
+    /// Evaluate the transform of the degree of consolidation.
+    pub fn degree_transform(&self, s: Complex<f64>) -> Complex<f64> {
+        let q = (s / self.consolidation_coefficient).sqrt();
+        let (inner, outer) = (q * self.drain_radius, q * self.influence_radius);
+        // Both the numerator and denominator are scaled by `e^(q (r_w - r_e))`
+        let decay = (2.0 * (inner - outer)).exp();
+        let numerator = bessel_ke(1, inner) * bessel_ie(1, outer)
+            - decay * bessel_ie(1, inner) * bessel_ke(1, outer);
+
+        2.0 * self.drain_radius * numerator
+            / (s * q
+                * (self.influence_radius.powi(2) - self.drain_radius.powi(2))
+                * self.scaled_solution(inner, outer))
+    }
+
+    /// Evaluate the transform of the relative excess pore pressure at `radius`.
+    pub fn pore_pressure_transform(&self, radius: f64, s: Complex<f64>) -> Complex<f64> {
+        let q = (s / self.consolidation_coefficient).sqrt();
+        let outer = q * self.influence_radius;
+        let ratio = (q * (self.drain_radius - radius)).exp()
+            * self.scaled_solution(q * radius, outer)
+            / self.scaled_solution(q * self.drain_radius, outer);
+
+        (1.0 - ratio) / s
+    }
+
+    /// Calculate the degree of consolidation at each of the times.
+    pub fn degree_of_consolidation(&self, times: &[f64], max_function_evals: usize) -> Vec<f64> {
+        times
+            .iter()
+            .map(|t| crate::laplace_inversion(|s| self.degree_transform(s), *t, max_function_evals))
+            .collect()
+    }
+
+    /// Calculate the excess pore pressure relative to the initial excess pressure at each of the radii at time `t`.
+    pub fn pore_pressure(&self, radii: &[f64], t: f64, max_function_evals: usize) -> Vec<f64> {
+        radii
+            .iter()
+            .map(|radius| {
+                crate::laplace_inversion(
+                    |s| self.pore_pressure_transform(*radius, s),
+                    t,
+                    max_function_evals,
+                )
+            })
+            .collect()
+    }
+
+    /// Solution $I_0(x) K_1(q r_e) + K_0(x) I_1(q r_e)$ with a vanishing flux at the outer radius, divided by $e^{q r_e - x}$.
+    fn scaled_solution(&self, x: Complex<f64>, outer: Complex<f64>) -> Complex<f64> {
+        (2.0 * (x - outer)).exp() * bessel_ie(0, x) * bessel_ke(1, outer)
+            + bessel_ke(0, x) * bessel_ie(1, outer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    #[test]
+    fn consolidation() {
+        // Series solutions over the eigenvalues `M = pi (2m + 1) / 2`
+        let layer = Terzaghi::new(2e-8, 3.0);
+        let eigenvalues = (0..200).map(|m| PI * (2 * m + 1) as f64 / 2.0);
+        let degree = |time_factor: f64| {
+            1.0 - eigenvalues
+                .clone()
+                .map(|m| 2.0 / (m * m) * (-m * m * time_factor).exp())
+                .sum::<f64>()
+        };
+        let pressure = |depth: f64, time_factor: f64| {
+            eigenvalues
+                .clone()
+                .map(|m| 2.0 / m * (m * depth / 3.0).sin() * (-m * m * time_factor).exp())
+                .sum::<f64>()
+        };
+        for time_factor in [0.01, 0.2, 1.0] {
+            let t = time_factor * 9.0 / 2e-8;
+            assert!(approx::relative_eq!(layer.time_factor(t), time_factor));
+            assert!(approx::relative_eq!(
+                layer.degree_of_consolidation(&[t], 100)[0],
+                degree(time_factor),
+                epsilon = 1e-3
+            ));
+            let depths = [0.0, 1.0, 2.0, 3.0];
+            for (depth, result) in depths.iter().zip(layer.pore_pressure(&depths, t, 100)) {
+                assert!(approx::relative_eq!(
+                    result,
+                    pressure(*depth, time_factor),
+                    epsilon = 1e-3
+                ));
+            }
+        }
+
+        // Barron's equal strain approximation is close for a spacing ratio of 10, free strain dissipates faster at first
+        let drain = Barron::new(2e-8, 0.05, 0.5);
+        let n = 10.0_f64;
+        let mu = n * n / (n * n - 1.0) * n.ln() - (3.0 * n * n - 1.0) / (4.0 * n * n);
+        let radii = (0..=90)
+            .map(|k| 0.05 + k as f64 * 0.005)
+            .collect::<Vec<_>>();
+        for time_factor in [0.05, 0.2, 0.5] {
+            let t = time_factor / drain.time_factor(1.0);
+            let result = drain.degree_of_consolidation(&[t], 100)[0];
+            assert!((result - (1.0 - (-8.0 * time_factor / mu).exp())).abs() < 0.03);
+
+            // Degree is the dissipated part of the average pressure
+            let pressures = drain.pore_pressure(&radii, t, 100);
+            assert!(pressures[0].abs() < 1e-6);
+            let average = radii
+                .windows(2)
+                .zip(pressures.windows(2))
+                .map(|(r, u)| (r[1] - r[0]) * (r[0] * u[0] + r[1] * u[1]) / 2.0)
+                .sum::<f64>()
+                * 2.0
+                / (0.5_f64.powi(2) - 0.05_f64.powi(2));
+            assert!((1.0 - average - result).abs() < 1e-4);
+        }
+    }
+}
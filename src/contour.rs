@@ -0,0 +1,247 @@
+//! Laplace inversion along a hyperbolic contour with parameters chosen from the location of the singularities.
+//!
+//! The Bromwich contour is deformed into the left half-plane along the hyperbola
+//!
+//! $$
+//! s(u) = \sigma_0 + \mu \left( 1 + \sin(iu - \alpha) \right), \quad u \in \mathbb{R}
+//! $$
+//!
+//! and integrated with the trapezoidal rule, following Weideman and Trefethen (2007).
+//! The parameters $\mu$, $\alpha$ and the step size $h$ are found by a grid search that balances the estimated discretization, truncation and rounding errors for the declared [`SingularityRegion`].
+
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use nalgebra::Complex;
+
+use crate::Error;
+
+/// Number of grid points for each of the contour parameters searched.
+const GRID_POINTS: usize = 200;
+
+/// Sector opening to the left that contains all singularities of a Laplace transform.
+///
+/// Singularities $p$ must satisfy $|\arg(\sigma_0 - p)| \leq \delta$, where $\delta$ is the angle and $\sigma_0$ the abscissa.
+/// An angle of zero means all singularities lie on the real axis left of the abscissa.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SingularityRegion {
+    /// Half opening angle $\delta$ of the sector around the negative real axis.
+    angle: f64,
+    /// Vertex $\sigma_0$ of the sector, right of or at the rightmost singularity.
+    abscissa: f64,
+}
+
+impl SingularityRegion {
+    /// Construct from the half opening angle in radians and the vertex on the real axis.
+    ///
+    /// The angle must be in $[0, \frac{\pi}{2})$, a transform with singularities arbitrarily close to a vertical line can't be inverted along a contour bending left.
+    pub fn new(angle: f64, abscissa: f64) -> Self {
+        assert!(
+            (0.0..FRAC_PI_2).contains(&angle),
+            "Singularity sector angle must be in [0, pi / 2)"
+        );
+
+        Self { angle, abscissa }
+    }
+
+    /// Half opening angle of the sector around the negative real axis.
+    pub fn angle(&self) -> f64 {
+        self.angle
+    }
+
+    /// Vertex of the sector on the real axis.
+    pub fn abscissa(&self) -> f64 {
+        self.abscissa
+    }
+}
+
+impl Default for SingularityRegion {
+    /// Singularities on the negative real axis, such as poles of a diffusion problem or a branch cut.
+    fn default() -> Self {
+        Self::new(0.0, 0.0)
+    }
+}
+
+/// Laplace inverter integrating along a hyperbolic contour tuned to a [`SingularityRegion`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), iltcme::Error> {
+/// use iltcme::contour::{HyperbolicInverter, SingularityRegion};
+///
+/// // Poles at `+-i` lie in the sector with a vertex at one and an angle of 60 degrees
+/// let region = SingularityRegion::new(60_f64.to_radians(), 1.0);
+/// let inverter = HyperbolicInverter::new(region, 40);
+///
+/// let result = inverter.invert(|s| 1.0 / (s.powi(2) + 1.0), 1.0)?;
+/// approx::assert_relative_eq!(result, 1.0_f64.sin(), epsilon = 1e-8);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HyperbolicInverter {
+    /// Where the singularities are.
+    region: SingularityRegion,
+    /// Number of points on the upper half of the contour, the function is evaluated one more time.
+    points: usize,
+    /// Product $\mu t$ of the contour scale and the time.
+    scale: f64,
+    /// Angle $\alpha$ of the hyperbola.
+    alpha: f64,
+    /// Step size $h$ of the trapezoidal rule.
+    step: f64,
+    /// Natural logarithm of the estimated relative error.
+    error_exponent: f64,
+}
+
+impl HyperbolicInverter {
+    /// Create an inverter for transforms with singularities in `region`, evaluating the function `points + 1` times.
+    pub fn new(region: SingularityRegion, points: usize) -> Self {
+        assert!(points > 0, "Hyperbolic contour needs at least one point");
+
+        let (error_exponent, (scale, alpha, step)) = optimal_parameters(region.angle, points);
+
+        Self {
+            region,
+            points,
+            scale,
+            alpha,
+            step,
+            error_exponent,
+        }
+    }
+
+    /// Where the singularities are.
+    pub fn region(&self) -> SingularityRegion {
+        self.region
+    }
+
+    /// Number of points on the upper half of the contour.
+    pub fn points(&self) -> usize {
+        self.points
+    }
+
+    /// Chosen contour parameters as the product $\mu t$, the angle $\alpha$ and the step size $h$.
+    pub fn parameters(&self) -> (f64, f64, f64) {
+        (self.scale, self.alpha, self.step)
+    }
+
+    /// Estimated relative error of the chosen contour parameters.
+    pub fn estimated_error(&self) -> f64 {
+        self.error_exponent.exp()
+    }
+
+    /// Calculate the Laplace inversion for a function at time `t`.
+    ///
+    /// Returns [`Error::NonFiniteTransform`] for the first node where the Laplace transform function returned NaN or infinity.
+    pub fn invert(
+        &self,
+        mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+        t: f64,
+    ) -> Result<f64, Error> {
+        if t <= 0.0 || t.is_nan() {
+            return Err(Error::NonPositiveTime(t));
+        }
+
+        // Conjugate symmetry of the contour only needs the upper half
+        let mut sum = Complex::new(0.0, 0.0);
+        for k in 0..=self.points {
+            let u = Complex::new(0.0, k as f64 * self.step);
+            let beta = ((u - self.alpha).sin() + 1.0) * self.scale;
+            let derivative = Complex::new(0.0, self.scale) * (u - self.alpha).cos();
+            let s = beta / t + self.region.abscissa;
+
+            let value = laplace_func(s);
+            if !(value.re.is_finite() && value.im.is_finite()) {
+                return Err(Error::NonFiniteTransform {
+                    node: k,
+                    beta,
+                    s,
+                    value,
+                });
+            }
+
+            let term = (s * t).exp() * value * derivative / t;
+            sum += if k == 0 { term / 2.0 } else { term };
+        }
+
+        Ok(self.step / PI * sum.im)
+    }
+}
+
+/// Find $\mu t$, $\alpha$ and $h$ minimizing the largest estimated error exponent for `points` points, returned together with that exponent.
+///
+/// The errors are the discretization error towards the singularities $e^{-2\pi (\pi/2 - \alpha - \delta) / h}$, the discretization error away from them $e^{\mu t - 2\pi\alpha/h}$, the truncation error $e^{\mu t (1 - \sin\alpha \cosh(Nh))}$ and the rounding error $\epsilon e^{\mu t}$.
+fn optimal_parameters(angle: f64, points: usize) -> (f64, (f64, f64, f64)) {
+    let points = points as f64;
+    let max_alpha = FRAC_PI_2 - angle;
+
+    let mut best = (f64::INFINITY, (f64::NAN, f64::NAN, f64::NAN));
+    for i in 1..GRID_POINTS {
+        let alpha = max_alpha * i as f64 / GRID_POINTS as f64;
+        for j in 1..=GRID_POINTS {
+            // Truncation point `Nh` between zero and six
+            let step = 6.0 * j as f64 / GRID_POINTS as f64 / points;
+
+            // Balance the discretization error away from the singularities with the truncation error
+            let scale = 2.0 * PI * alpha / (step * alpha.sin() * (points * step).cosh());
+            let exponent = (scale - 2.0 * PI * alpha / step)
+                .max(-2.0 * PI * (max_alpha - alpha) / step)
+                .max(f64::EPSILON.ln() + scale);
+
+            if exponent < best.0 {
+                best = (exponent, (scale, alpha, step));
+            }
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::ComplexField;
+
+    use super::*;
+
+    #[test]
+    fn hyperbolic_inversions() {
+        let inverter = HyperbolicInverter::new(SingularityRegion::default(), 30);
+
+        for t in [0.1, 1.0, 10.0] {
+            let exponential = inverter.invert(|s| (s + 1.0).recip(), t).unwrap();
+            // Branch cut along the negative real axis
+            let branch_cut = inverter.invert(|s| s.sqrt().recip(), t).unwrap();
+
+            assert!(approx::relative_eq!(
+                exponential,
+                (-t).exp(),
+                epsilon = 1e-9
+            ));
+            assert!(approx::relative_eq!(
+                branch_cut,
+                (PI * t).sqrt().recip(),
+                epsilon = 1e-9
+            ));
+        }
+    }
+
+    #[test]
+    fn oscillating_inversion() {
+        let region = SingularityRegion::new(PI / 3.0, 1.0);
+        let inverter = HyperbolicInverter::new(region, 60);
+
+        for t in [0.5, 2.0, 5.0] {
+            let cosine = inverter.invert(|s| s / (s.powi(2) + 1.0), t).unwrap();
+
+            assert!(approx::relative_eq!(cosine, t.cos(), epsilon = 1e-6));
+        }
+
+        // Narrower sectors allow a faster converging contour
+        let narrow = HyperbolicInverter::new(SingularityRegion::new(0.1, 0.0), 20);
+        let wide = HyperbolicInverter::new(SingularityRegion::new(1.4, 0.0), 20);
+        assert!(narrow.estimated_error() < wide.estimated_error());
+    }
+}
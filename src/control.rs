@@ -0,0 +1,154 @@
+//! Time responses of linear time-invariant systems described by a transfer function.
+
+use nalgebra::Complex;
+
+use crate::rational::RationalTransform;
+
+/// Transfer function $G(s) = \frac{N(s)}{D(s)}$ of a linear time-invariant system.
+///
+/// Responses to rational inputs are computed exactly from the partial fractions, responses to other inputs use the CME method.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::control::TransferFunction;
+///
+/// // First order system with a time constant of 2
+/// let system = TransferFunction::new(&[1.0], &[2.0, 1.0]);
+/// let response = system.step_response(&[1.0, 2.0, 4.0]);
+/// approx::assert_relative_eq!(response[1], 1.0 - (-1.0_f64).exp(), epsilon = 1e-12);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferFunction {
+    /// Transform of the impulse response.
+    transform: RationalTransform,
+}
+
+impl TransferFunction {
+    /// Construct from the polynomial coefficients of the numerator and denominator, highest power first.
+    pub fn new(numerator: &[f64], denominator: &[f64]) -> Self {
+        RationalTransform::new(numerator, denominator).into()
+    }
+
+    /// Rational transform of the impulse response.
+    pub fn transform(&self) -> &RationalTransform {
+        &self.transform
+    }
+
+    /// Evaluate the transfer function in the Laplace domain.
+    pub fn evaluate(&self, s: Complex<f64>) -> Complex<f64> {
+        self.transform.evaluate(s)
+    }
+
+    /// Steady-state gain $G(0)$ of the system.
+    pub fn dc_gain(&self) -> f64 {
+        self.evaluate(Complex::new(0.0, 0.0)).re
+    }
+
+    /// Response of the system to a unit impulse at each of the times.
+    ///
+    /// The impulse of a system with a numerator of a degree greater or equal to the denominator is not part of the response.
+    pub fn impulse_response(&self, times: &[f64]) -> Vec<f64> {
+        times.iter().map(|t| self.transform.inverse(*t)).collect()
+    }
+
+    /// Response of the system to a unit step at each of the times.
+    pub fn step_response(&self, times: &[f64]) -> Vec<f64> {
+        self.response_to_rational(&RationalTransform::new(&[1.0], &[1.0, 0.0]), times)
+    }
+
+    /// Response of the system to an input with a rational Laplace transform at each of the times.
+    pub fn response_to_rational(&self, input: &RationalTransform, times: &[f64]) -> Vec<f64> {
+        let output = RationalTransform::new(
+            &multiply(self.transform.numerator(), input.numerator()),
+            &multiply(self.transform.denominator(), input.denominator()),
+        );
+
+        times.iter().map(|t| output.inverse(*t)).collect()
+    }
+
+    /// Response of the system to an input described by its Laplace transform at each of the times.
+    ///
+    /// Uses the CME method with at most `max_function_evals` evaluations per time.
+    pub fn response_to(
+        &self,
+        input_transform: impl Fn(Complex<f64>) -> Complex<f64>,
+        times: &[f64],
+        max_function_evals: usize,
+    ) -> Vec<f64> {
+        times
+            .iter()
+            .map(|t| {
+                crate::laplace_inversion(
+                    |s| self.evaluate(s) * input_transform(s),
+                    *t,
+                    max_function_evals,
+                )
+            })
+            .collect()
+    }
+}
+
+impl From<RationalTransform> for TransferFunction {
+    fn from(transform: RationalTransform) -> Self {
+        Self { transform }
+    }
+}
+
+/// Multiply two polynomials with the highest power first.
+fn multiply(a: &[f64], b: &[f64]) -> Vec<f64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    let mut product = vec![0.0; a.len() + b.len() - 1];
+    for (i, a) in a.iter().enumerate() {
+        for (j, b) in b.iter().enumerate() {
+            product[i + j] += a * b;
+        }
+    }
+
+    product
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::ComplexField;
+
+    use super::*;
+
+    #[test]
+    fn second_order_system() {
+        // Damping ratio of 0.5 and natural frequency of 2
+        let system = TransferFunction::new(&[4.0], &[1.0, 2.0, 4.0]);
+        let times = [0.5, 1.0, 2.0, 5.0];
+        let wd = 3.0_f64.sqrt();
+
+        assert_eq!(system.dc_gain(), 1.0);
+
+        let step = system.step_response(&times);
+        let impulse = system.impulse_response(&times);
+        // Ramp input `1 / s^2` using the CME method
+        let ramp = system.response_to(|s| s.powi(2).recip(), &times, 100);
+        for (i, t) in times.into_iter().enumerate() {
+            let expected_step = 1.0 - (-t).exp() * ((wd * t).cos() + (wd * t).sin() / wd);
+            let expected_impulse = 4.0 / wd * (-t).exp() * (wd * t).sin();
+            assert!(approx::relative_eq!(
+                step[i],
+                expected_step,
+                epsilon = 1e-10
+            ));
+            assert!(approx::relative_eq!(
+                impulse[i],
+                expected_impulse,
+                epsilon = 1e-10
+            ));
+
+            let expected_ramp = system
+                .response_to_rational(&RationalTransform::new(&[1.0], &[1.0, 0.0, 0.0]), &[t])[0];
+            assert!(approx::relative_eq!(ramp[i], expected_ramp, epsilon = 1e-3));
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//! Convolutions $\int_0^t K(t - \tau) g(\tau) d\tau$ when only the Laplace transform of the kernel is known, using Lubich's convolution quadrature.
+//!
+//! The convolution is discretized with a linear multistep or Runge-Kutta method with step $h$, which gives the weights as the coefficients of the power series
+//!
+//! $$
+//! \mathcal{K}\left( \frac{\delta(\zeta)}{h} \right) = \sum_{j = 0}^\infty \omega_j \zeta^j
+//! $$
+//!
+//! where $\delta$ is the generating function of the method.
+//! The coefficients are computed with a discrete Fourier transform of the transform evaluated on a circle with radius $\rho < 1$, chosen such that the aliasing error is about the square root of the machine precision.
+//!
+//! The kernel doesn't need to be integrable, fractional derivatives and integrals with transforms $s^\alpha$ work as well, which is what time-domain boundary elements and fractional viscoelasticity need.
+//!
+//! # Example
+//!
+//! ```rust
+//! # fn main() {
+//! use iltcme::convolution::{ConvolutionQuadrature, Scheme};
+//!
+//! // Convolution of the kernel `e^(-t)` with a unit step
+//! let quadrature = ConvolutionQuadrature::new(|s| 1.0 / (s + 1.0), Scheme::RadauIIA, 0.01, 100);
+//! let result = quadrature.convolve(|_| 1.0);
+//! approx::assert_relative_eq!(result[100], 1.0 - (-1.0_f64).exp(), epsilon = 0.001);
+//! # }
+//! ```
+
+use std::f64::consts::PI;
+
+use nalgebra::{Complex, DMatrix, Matrix2};
+
+/// Time stepping method the convolution is discretized with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Scheme {
+    /// Second order backward differentiation formula, $\delta(\zeta) = (1 - \zeta) + (1 - \zeta)^2 / 2$.
+    #[default]
+    Bdf2,
+    /// Two stage Radau IIA Runge-Kutta method of order three, $\delta(\zeta) = (A + \frac{\zeta}{1 - \zeta} \mathbb{1} b^T)^{-1}$.
+    ///
+    /// The transform is evaluated at the eigenvalues of $\delta(\zeta) / h$, the weights are $2 \times 2$ matrices acting on the stage values.
+    RadauIIA,
+}
+
+impl Scheme {
+    /// Butcher tableau of the Radau IIA method.
+    const RADAU_A: [[f64; 2]; 2] = [[5.0 / 12.0, -1.0 / 12.0], [3.0 / 4.0, 1.0 / 4.0]];
+    /// Stages of the Radau IIA method as fractions of the step.
+    const RADAU_C: [f64; 2] = [1.0 / 3.0, 1.0];
+
+    /// Number of stages, the size of the weight matrices.
+    pub fn stages(&self) -> usize {
+        match self {
+            Self::Bdf2 => 1,
+            Self::RadauIIA => 2,
+        }
+    }
+
+    /// Transform applied to the generating function at `zeta`.
+    fn transform(
+        &self,
+        mut kernel_transform: impl FnMut(Complex<f64>) -> Complex<f64>,
+        zeta: Complex<f64>,
+        step: f64,
+    ) -> DMatrix<Complex<f64>> {
+        let one = Complex::new(1.0, 0.0);
+
+        match self {
+            Self::Bdf2 => {
+                let delta = (one - zeta) + (one - zeta).powi(2) / 2.0;
+
+                DMatrix::from_element(1, 1, kernel_transform(delta / step))
+            }
+            Self::RadauIIA => {
+                // Every row of the rank one part is the last row of `A`
+                let ratio = zeta / (one - zeta);
+                let [b0, b1] = Self::RADAU_A[1];
+                let delta = Matrix2::new(
+                    Self::RADAU_A[0][0] + ratio * b0,
+                    Self::RADAU_A[0][1] + ratio * b1,
+                    Self::RADAU_A[1][0] + ratio * b0,
+                    Self::RADAU_A[1][1] + ratio * b1,
+                )
+                .try_inverse()
+                .expect("Generating function is regular inside the unit circle")
+                    / Complex::from(step);
+
+                // Sylvester's formula with the two distinct eigenvalues
+                let half_trace = (delta[(0, 0)] + delta[(1, 1)]) / 2.0;
+                let root = (half_trace.powi(2) - delta.determinant()).sqrt();
+                let (first, second) = (half_trace + root, half_trace - root);
+                let identity = Matrix2::identity();
+                let result = ((delta - identity * second) * kernel_transform(first)
+                    - (delta - identity * first) * kernel_transform(second))
+                    / (first - second);
+
+                DMatrix::from_column_slice(2, 2, result.as_slice())
+            }
+        }
+    }
+}
+
+/// Convolution quadrature weights for a kernel, reusable for any number of functions it's convolved with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvolutionQuadrature {
+    /// Method the weights were derived from.
+    scheme: Scheme,
+    /// Time step.
+    step: f64,
+    /// Weight matrix for each step back in time.
+    weights: Vec<DMatrix<f64>>,
+}
+
+impl ConvolutionQuadrature {
+    /// Compute the weights for `steps` steps of size `step` from the Laplace transform of the kernel.
+    ///
+    /// The transform is evaluated `2 * (steps + 1)` times for each stage of the scheme, the weights are computed with a direct discrete Fourier transform in $O(N^2)$.
+    pub fn new(
+        mut kernel_transform: impl FnMut(Complex<f64>) -> Complex<f64>,
+        scheme: Scheme,
+        step: f64,
+        steps: usize,
+    ) -> Self {
+        assert!(step > 0.0, "Step must be positive");
+
+        let len = steps + 1;
+        let points = 2 * len;
+        // Aliasing error of `radius^points` is the square root of the machine precision
+        let radius = f64::EPSILON.powf(0.5 / points as f64);
+        let transforms = (0..points)
+            .map(|l| {
+                let zeta = Complex::from_polar(radius, 2.0 * PI * l as f64 / points as f64);
+                scheme.transform(&mut kernel_transform, zeta, step)
+            })
+            .collect::<Vec<_>>();
+
+        let stages = scheme.stages();
+        let weights = (0..len)
+            .map(|j| {
+                let sum = transforms.iter().enumerate().fold(
+                    DMatrix::zeros(stages, stages),
+                    |sum, (l, transform)| {
+                        let angle = -2.0 * PI * ((j * l) % points) as f64 / points as f64;
+                        sum + transform * Complex::from_polar(1.0, angle)
+                    },
+                );
+
+                sum.map(|w| w.re / (points as f64 * radius.powi(j as i32)))
+            })
+            .collect();
+
+        Self {
+            scheme,
+            step,
+            weights,
+        }
+    }
+
+    /// Method the weights were derived from.
+    pub fn scheme(&self) -> Scheme {
+        self.scheme
+    }
+
+    /// Time step.
+    pub fn step(&self) -> f64 {
+        self.step
+    }
+
+    /// Number of steps.
+    pub fn steps(&self) -> usize {
+        self.weights.len() - 1
+    }
+
+    /// Weight matrices $\omega_j$ for every step back in time.
+    pub fn weights(&self) -> &[DMatrix<f64>] {
+        &self.weights
+    }
+
+    /// Calculate the convolution of the kernel with `g` at the times $t_n = n h$ for $n = 0, \dots, N$.
+    ///
+    /// `g` is sampled at the stages of every step, for the best accuracy it should vanish at zero, otherwise the order is reduced close to zero.
+    pub fn convolve(&self, mut g: impl FnMut(f64) -> f64) -> Vec<f64> {
+        match self.scheme {
+            Scheme::Bdf2 => {
+                let samples = (0..self.weights.len())
+                    .map(|n| g(n as f64 * self.step))
+                    .collect::<Vec<_>>();
+
+                (0..samples.len())
+                    .map(|n| {
+                        (0..=n)
+                            .map(|j| self.weights[j][(0, 0)] * samples[n - j])
+                            .sum()
+                    })
+                    .collect()
+            }
+            Scheme::RadauIIA => {
+                let samples = (0..self.steps())
+                    .map(|n| {
+                        Scheme::RADAU_C
+                            .map(|c| g((n as f64 + c) * self.step))
+                            .into()
+                    })
+                    .collect::<Vec<nalgebra::Vector2<f64>>>();
+
+                // The last stage is at the end of the step
+                std::iter::once(0.0)
+                    .chain((0..samples.len()).map(|n| {
+                        (0..=n)
+                            .map(|j| {
+                                let weight = &self.weights[j];
+                                weight[(1, 0)] * samples[n - j][0]
+                                    + weight[(1, 1)] * samples[n - j][1]
+                            })
+                            .sum()
+                    }))
+                    .collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convolutions() {
+        // Exponential kernel with a ramp
+        let exact = |t: f64| t - 1.0 + (-t).exp();
+        let max_error = |scheme, steps| {
+            let step = 1.0 / steps as f64;
+            let quadrature = ConvolutionQuadrature::new(|s| 1.0 / (s + 1.0), scheme, step, steps);
+            assert_eq!(quadrature.steps(), steps);
+
+            quadrature
+                .convolve(|t| t)
+                .iter()
+                .enumerate()
+                .map(|(n, y)| (y - exact(n as f64 * step)).abs())
+                .fold(0.0, f64::max)
+        };
+
+        // Orders of convergence
+        for (scheme, order, epsilon) in [(Scheme::Bdf2, 2.0, 1e-4), (Scheme::RadauIIA, 3.0, 1e-8)] {
+            let (coarse, fine) = (max_error(scheme, 50), max_error(scheme, 100));
+            assert!(fine < epsilon);
+            assert!((coarse / fine).log2() > order - 0.2);
+        }
+
+        // Half integral, a kernel without a regular inverse
+        let quadrature = ConvolutionQuadrature::new(|s| s.powf(-0.5), Scheme::RadauIIA, 0.01, 100);
+        let result = quadrature.convolve(|t| t);
+        assert!(approx::relative_eq!(
+            result[100],
+            4.0 / (3.0 * PI.sqrt()),
+            epsilon = 1e-5
+        ));
+    }
+}
@@ -0,0 +1,498 @@
+//! Density and distribution functions of nonnegative random variables known through their Laplace-Stieltjes transform.
+//!
+//! The density is the inverse of the transform $F^*(s) = E[e^{-sX}]$ and the distribution function the inverse of $F^*(s) / s$.
+//! The CME method averages the function against a nonnegative kernel, so inversions of exact transforms keep these properties up to rounding.
+//! Errors in the evaluation of the transform, from numerical quadrature, truncated series or measured data, are amplified by the inversion however.
+//! Then a density can dip below zero where it's small and a distribution function can decrease or exceed one.
+//! Samplers and other downstream computations need outputs that are valid probabilities.
+//! These can be projected onto the feasible set over the grid with [`enforce_nonnegative`] and [`enforce_monotone`].
+//!
+//! Most mistakes with probability transforms, like a forgotten atom at zero or a grid that misses part of the support, show up as missing mass.
+//! [`check_density`] compares the mass of the inverted density with the total mass $F^*(0^+)$ of the transform.
+//!
+//! Random variates are generated by inverting the distribution function, which a [`Sampler`] caches on an adaptive grid so every variate only needs a search.
+
+use nalgebra::Complex;
+
+use crate::{BoxedLaplaceFunc, CmeInverter, Error, TimePolicy};
+
+/// Argument where the transform is evaluated for its limit at zero.
+const NEAR_ZERO: f64 = 1e-12;
+
+/// Number of intervals of the initial grid of a [`Sampler`].
+const INITIAL_INTERVALS: usize = 32;
+
+/// Number of times an interval of the grid of a [`Sampler`] can be halved, or its end doubled.
+const MAX_REFINEMENTS: usize = 60;
+
+/// Distribution of a nonnegative random variable given by its Laplace-Stieltjes transform.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::distribution::Distribution;
+///
+/// // Deterministic value of one, the distribution function jumps there
+/// let distribution = Distribution::new(|s| (-s).exp(), 100).with_enforce_monotone(true);
+/// let grid = (1..40).map(|k| k as f64 * 0.05).collect::<Vec<_>>();
+/// let cdf = distribution.cdf(&grid);
+/// assert!(cdf.windows(2).all(|pair| pair[0] <= pair[1]));
+/// assert!(cdf.iter().all(|p| (0.0..=1.0).contains(p)));
+/// # }
+/// ```
+pub struct Distribution<'a> {
+    /// Laplace-Stieltjes transform $F^*(s)$.
+    transform: BoxedLaplaceFunc<'a>,
+    /// Maximum number of times the transform is evaluated for each inversion.
+    max_function_evals: usize,
+    /// Whether negative densities and probabilities are set to zero.
+    nonnegative: bool,
+    /// Whether distribution functions are projected onto nondecreasing values in $[0, 1]$.
+    monotone: bool,
+}
+
+impl<'a> Distribution<'a> {
+    /// Construct from the Laplace-Stieltjes transform, inverted with at most `max_function_evals` evaluations.
+    ///
+    /// Outputs are the raw inversions until constraints are enabled.
+    pub fn new(
+        transform: impl Fn(Complex<f64>) -> Complex<f64> + 'a,
+        max_function_evals: usize,
+    ) -> Self {
+        Self {
+            transform: Box::new(transform),
+            max_function_evals,
+            nonnegative: false,
+            monotone: false,
+        }
+    }
+
+    /// Set whether negative densities and probabilities are set to zero.
+    pub fn with_enforce_nonnegative(mut self, nonnegative: bool) -> Self {
+        self.nonnegative = nonnegative;
+
+        self
+    }
+
+    /// Set whether distribution functions are projected onto nondecreasing values in $[0, 1]$.
+    pub fn with_enforce_monotone(mut self, monotone: bool) -> Self {
+        self.monotone = monotone;
+
+        self
+    }
+
+    /// Maximum number of times the transform is evaluated for each inversion.
+    pub fn max_function_evals(&self) -> usize {
+        self.max_function_evals
+    }
+
+    /// Whether negative densities and probabilities are set to zero.
+    pub fn enforces_nonnegative(&self) -> bool {
+        self.nonnegative
+    }
+
+    /// Whether distribution functions are projected onto nondecreasing values in $[0, 1]$.
+    pub fn enforces_monotone(&self) -> bool {
+        self.monotone
+    }
+
+    /// Evaluate the Laplace-Stieltjes transform $F^*(s)$.
+    pub fn transform(&self, s: Complex<f64>) -> Complex<f64> {
+        (self.transform)(s)
+    }
+
+    /// Calculate the density at each of the points of the grid.
+    ///
+    /// A probability mass at zero isn't part of the density and must be removed from the transform first.
+    pub fn pdf(&self, grid: &[f64]) -> Vec<f64> {
+        let mut values = grid
+            .iter()
+            .map(|t| crate::laplace_inversion(|s| self.transform(s), *t, self.max_function_evals))
+            .collect::<Vec<_>>();
+        if self.nonnegative {
+            enforce_nonnegative(&mut values);
+        }
+
+        values
+    }
+
+    /// Calculate the distribution function at each of the points of the increasing grid.
+    pub fn cdf(&self, grid: &[f64]) -> Vec<f64> {
+        let mut values = grid
+            .iter()
+            .map(|t| {
+                crate::laplace_inversion(|s| self.transform(s) / s, *t, self.max_function_evals)
+            })
+            .collect::<Vec<_>>();
+        if self.monotone {
+            assert!(
+                grid.windows(2).all(|pair| pair[0] < pair[1]),
+                "Grid must be increasing to enforce a monotone distribution function"
+            );
+            enforce_monotone(&mut values);
+        } else if self.nonnegative {
+            enforce_nonnegative(&mut values);
+        }
+
+        values
+    }
+
+    /// Cache the distribution function for generating variates, accurate to about `tolerance` in probability.
+    ///
+    /// Returns [`Error::TooManyEvaluations`] for an order the inverter doesn't support.
+    pub fn sampler(&self, tolerance: f64) -> Result<Sampler, Error> {
+        Sampler::new(|s| self.transform(s), self.max_function_evals, tolerance)
+    }
+}
+
+/// Generator of random variates from a distribution function cached on an adaptive grid.
+///
+/// The grid extends until the remaining tail has a probability below the tolerance.
+/// Intervals are halved until the distribution function at their middle differs less than the tolerance from the linear interpolation, so the grid is dense where the density is large or changes quickly.
+/// Variates are the inverse of the linear interpolation at uniform variates, which come from any random number generator.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), iltcme::Error> {
+/// use iltcme::distribution::Sampler;
+///
+/// // Exponential distribution with rate 2
+/// let sampler = Sampler::new(|s| 2.0 / (s + 2.0), 50, 1e-6)?;
+/// let variates = sampler.samples([0.25, 0.5, 0.75]);
+/// approx::assert_relative_eq!(variates[1], 2.0_f64.ln() / 2.0, epsilon = 1e-3);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sampler {
+    /// Increasing points of the grid, starting at zero.
+    grid: Vec<f64>,
+    /// Nondecreasing distribution function at the points of the grid.
+    cdf: Vec<f64>,
+}
+
+impl Sampler {
+    /// Cache the distribution function of the Laplace-Stieltjes transform, inverted with at most `max_function_evals` evaluations.
+    ///
+    /// Returns [`Error::TooManyEvaluations`] for an order the inverter doesn't support.
+    pub fn new(
+        transform: impl Fn(Complex<f64>) -> Complex<f64>,
+        max_function_evals: usize,
+        tolerance: f64,
+    ) -> Result<Self, Error> {
+        assert!(tolerance > 0.0, "Tolerance must be positive");
+
+        // Probability mass at zero is the initial value
+        let inverter =
+            CmeInverter::new(max_function_evals)?.with_time_policy(TimePolicy::InitialValue);
+        let cdf = |t: f64| inverter.invert(|s| transform(s) / s, t);
+
+        let mut end = 1.0;
+        for _ in 0..MAX_REFINEMENTS {
+            if cdf(end)? > 1.0 - tolerance {
+                break;
+            }
+            end *= 2.0;
+        }
+
+        let mut points = Vec::with_capacity(2 * INITIAL_INTERVALS);
+        let mut start = (0.0, cdf(0.0)?);
+        points.push(start);
+        for k in 1..=INITIAL_INTERVALS {
+            let t = end * k as f64 / INITIAL_INTERVALS as f64;
+            let next = (t, cdf(t)?);
+            refine(&cdf, start, next, tolerance, MAX_REFINEMENTS, &mut points)?;
+            start = next;
+        }
+
+        let (grid, mut cdf): (Vec<_>, Vec<_>) = points.into_iter().unzip();
+        enforce_monotone(&mut cdf);
+
+        Ok(Self { grid, cdf })
+    }
+
+    /// Increasing points of the grid, starting at zero.
+    pub fn grid(&self) -> &[f64] {
+        &self.grid
+    }
+
+    /// Nondecreasing distribution function at the points of the grid.
+    pub fn cdf(&self) -> &[f64] {
+        &self.cdf
+    }
+
+    /// Value where the interpolated distribution function reaches `probability`.
+    ///
+    /// Probabilities up to the mass at zero give zero, probabilities in the truncated tail give the end of the grid.
+    pub fn quantile(&self, probability: f64) -> f64 {
+        assert!(
+            (0.0..=1.0).contains(&probability),
+            "Probability must be between zero and one"
+        );
+
+        let index = self.cdf.partition_point(|p| *p < probability);
+        match index {
+            0 => self.grid[0],
+            index if index == self.grid.len() => self.grid[index - 1],
+            index => {
+                let (left, right) = (self.cdf[index - 1], self.cdf[index]);
+                let fraction = (probability - left) / (right - left);
+
+                self.grid[index - 1] + fraction * (self.grid[index] - self.grid[index - 1])
+            }
+        }
+    }
+
+    /// Transform uniform variates on $[0, 1]$ into variates of the distribution.
+    pub fn samples(&self, uniforms: impl IntoIterator<Item = f64>) -> Vec<f64> {
+        uniforms
+            .into_iter()
+            .map(|uniform| self.quantile(uniform))
+            .collect()
+    }
+}
+
+/// Halve the interval between `start` and `end` until the distribution function is linear within `tolerance`, pushing the points after `start`.
+fn refine(
+    cdf: &impl Fn(f64) -> Result<f64, Error>,
+    start: (f64, f64),
+    end: (f64, f64),
+    tolerance: f64,
+    depth: usize,
+    points: &mut Vec<(f64, f64)>,
+) -> Result<(), Error> {
+    let t = (start.0 + end.0) / 2.0;
+    let middle = (t, cdf(t)?);
+    if depth > 0 && (middle.1 - (start.1 + end.1) / 2.0).abs() > tolerance {
+        refine(cdf, start, middle, tolerance, depth - 1, points)?;
+        refine(cdf, middle, end, tolerance, depth - 1, points)?;
+    } else {
+        points.push(middle);
+        points.push(end);
+    }
+
+    Ok(())
+}
+
+/// Set negative values to zero, the closest nonnegative values in the least squares sense.
+pub fn enforce_nonnegative(values: &mut [f64]) {
+    for value in values {
+        *value = value.max(0.0);
+    }
+}
+
+/// Replace values by the closest nondecreasing values in $[0, 1]$ in the least squares sense, for a distribution function on an increasing grid.
+///
+/// Decreasing runs are pooled into their average with the pool adjacent violators algorithm, then the result is clipped to $[0, 1]$.
+pub fn enforce_monotone(values: &mut [f64]) {
+    // Blocks of pooled values as their average and length
+    let mut blocks: Vec<(f64, usize)> = Vec::with_capacity(values.len());
+    for value in values.iter() {
+        let mut block = (*value, 1);
+        while let Some(&(average, length)) = blocks.last() {
+            if average <= block.0 {
+                break;
+            }
+            blocks.pop();
+            let total = length + block.1;
+            block = (
+                (average * length as f64 + block.0 * block.1 as f64) / total as f64,
+                total,
+            );
+        }
+        blocks.push(block);
+    }
+
+    let pooled = blocks
+        .iter()
+        .flat_map(|(average, length)| std::iter::repeat_n(average.clamp(0.0, 1.0), *length));
+    for (value, average) in values.iter_mut().zip(pooled) {
+        *value = average;
+    }
+}
+
+/// Mass of a density inverted on a grid compared with the mass of its transform.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DensityCheck {
+    /// Inverted density at the points of the grid.
+    pub densities: Vec<f64>,
+    /// Integral of the inverted density over the grid with the trapezoidal rule.
+    pub mass: f64,
+    /// Total mass $F^*(0^+)$ of the transform, one for a probability distribution.
+    pub expected: f64,
+}
+
+impl DensityCheck {
+    /// Mass of the transform that's missing from the inverted density, negative when the density has too much mass.
+    ///
+    /// Mass outside the grid, atoms and inversion errors all contribute.
+    pub fn defect(&self) -> f64 {
+        self.expected - self.mass
+    }
+
+    /// Defect relative to the total mass of the transform.
+    pub fn relative_defect(&self) -> f64 {
+        self.defect() / self.expected
+    }
+
+    /// Scale the densities so their mass matches the total mass of the transform.
+    pub fn renormalize(&mut self) {
+        let factor = self.expected / self.mass;
+        for density in &mut self.densities {
+            *density *= factor;
+        }
+        self.mass = self.expected;
+    }
+}
+
+/// Invert the transform of a density on the increasing grid with at most `max_function_evals` evaluations, and compare its mass with the total mass of the transform.
+///
+/// The grid should start at zero and cover the support, the density at zero follows from the initial value theorem.
+/// Returns [`Error::TooManyEvaluations`] for an order the inverter doesn't support.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), iltcme::Error> {
+/// use iltcme::distribution::check_density;
+///
+/// // Grid of an exponential distribution that stops too early misses the tail
+/// let grid = (0..=300).map(|k| k as f64 * 0.01).collect::<Vec<_>>();
+/// let check = check_density(|s| 1.0 / (s + 1.0), &grid, 50)?;
+/// approx::assert_relative_eq!(check.defect(), (-3.0_f64).exp(), epsilon = 1e-3);
+/// # Ok(())
+/// # }
+/// ```
+pub fn check_density(
+    transform: impl Fn(Complex<f64>) -> Complex<f64>,
+    grid: &[f64],
+    max_function_evals: usize,
+) -> Result<DensityCheck, Error> {
+    assert!(grid.len() >= 2, "Grid needs at least two points");
+    assert!(
+        grid[0] >= 0.0 && grid.windows(2).all(|pair| pair[0] < pair[1]),
+        "Grid must be nonnegative and increasing"
+    );
+
+    let inverter = CmeInverter::new(max_function_evals)?.with_time_policy(TimePolicy::InitialValue);
+    let densities = grid
+        .iter()
+        .map(|t| inverter.invert(&transform, *t))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mass = grid
+        .windows(2)
+        .zip(densities.windows(2))
+        .map(|(t, f)| (t[1] - t[0]) * (f[0] + f[1]) / 2.0)
+        .sum();
+
+    Ok(DensityCheck {
+        densities,
+        mass,
+        expected: transform(Complex::from(NEAR_ZERO)).re,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constrained_outputs() {
+        let mut values = [-0.1, 0.3, 0.2, 0.5, 1.1, 1.05];
+        enforce_monotone(&mut values);
+        assert!(approx::relative_eq!(
+            &values[..],
+            &[0.0, 0.25, 0.25, 0.5, 1.0, 1.0][..]
+        ));
+        let mut densities = [0.5, -1e-3, 0.0];
+        enforce_nonnegative(&mut densities);
+        assert_eq!(densities, [0.5, 0.0, 0.0]);
+
+        // Uniform distribution on `[1, 2]` with errors of about `1e-9` in the evaluations
+        let uniform =
+            |s: Complex<f64>| ((-s).exp() - (-2.0 * s).exp()) / s + 1e-9 * (s.im * 12.9898).sin();
+        let grid = (1..60).map(|k| k as f64 * 0.05).collect::<Vec<_>>();
+        let raw = Distribution::new(uniform, 50);
+        let (raw_pdf, raw_cdf) = (raw.pdf(&grid), raw.cdf(&grid));
+        assert!(raw_pdf.iter().any(|p| *p < 0.0));
+        assert!(raw_cdf.windows(2).any(|pair| pair[0] > pair[1]));
+
+        let constrained = Distribution::new(uniform, 50)
+            .with_enforce_nonnegative(true)
+            .with_enforce_monotone(true);
+        let (pdf, cdf) = (constrained.pdf(&grid), constrained.cdf(&grid));
+        assert!(pdf.iter().all(|p| *p >= 0.0));
+        assert!(cdf.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(cdf.iter().all(|p| (0.0..=1.0).contains(p)));
+
+        // Projections only move the values by about the size of the violations
+        let distance = |a: &[f64], b: &[f64]| {
+            a.iter()
+                .zip(b)
+                .map(|(x, y)| (x - y).abs())
+                .fold(0.0, f64::max)
+        };
+        assert!(distance(&pdf, &raw_pdf) < 0.1);
+        assert!(distance(&cdf, &raw_cdf) < 0.02);
+    }
+
+    #[test]
+    fn density_checks() {
+        // Gamma distribution with shape 2 and rate 3
+        let gamma = |s: Complex<f64>| (3.0 / (s + 3.0)).powi(2);
+        let grid = (0..=500).map(|k| k as f64 * 0.02).collect::<Vec<_>>();
+        let check = check_density(gamma, &grid, 50).unwrap();
+        assert!(approx::relative_eq!(check.expected, 1.0, epsilon = 1e-9));
+        assert!(check.defect().abs() < 1e-3);
+
+        // Grid missing the tail and a transform of the distribution function instead of the density
+        let short = check_density(gamma, &grid[..51], 50).unwrap();
+        let tail = (-3.0_f64).exp() * 4.0;
+        assert!(approx::relative_eq!(short.defect(), tail, epsilon = 1e-3));
+        let cdf = check_density(|s| gamma(s) / s, &grid, 50).unwrap();
+        assert!(cdf.relative_defect().abs() > 0.5);
+
+        // Renormalizing spreads the missing mass over the grid
+        let mut renormalized = short.clone();
+        renormalized.renormalize();
+        assert_eq!(renormalized.defect(), 0.0);
+        assert!(approx::relative_eq!(
+            renormalized.densities[25] / short.densities[25],
+            1.0 / (1.0 - tail),
+            epsilon = 1e-3
+        ));
+        assert!(check_density(gamma, &grid, 10_000).is_err());
+    }
+
+    #[test]
+    fn sampling() {
+        // Exponential distribution with rate 2 and an atom of 0.3 at zero
+        let sampler = Sampler::new(|s| 0.3 + 0.7 * 2.0 / (s + 2.0), 50, 1e-6).unwrap();
+        assert_eq!(sampler.grid()[0], 0.0);
+        assert!(approx::relative_eq!(sampler.cdf()[0], 0.3, epsilon = 1e-3));
+        assert!(sampler.cdf().windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(sampler.quantile(0.2), 0.0);
+        for probability in [0.4, 0.7, 0.99] {
+            let exact = -((1.0 - probability) / 0.7_f64).ln() / 2.0;
+            assert!(approx::relative_eq!(
+                sampler.quantile(probability),
+                exact,
+                epsilon = 1e-3
+            ));
+        }
+
+        // Stratified uniforms give the mean, the grid is refined around the jump of a deterministic value
+        let uniforms = (0..1000).map(|k| (k as f64 + 0.5) / 1000.0);
+        let mean = sampler.samples(uniforms.clone()).iter().sum::<f64>() / 1000.0;
+        assert!(approx::relative_eq!(mean, 0.35, epsilon = 1e-3));
+        let deterministic = Distribution::new(|s| (-s).exp(), 100)
+            .sampler(1e-4)
+            .unwrap();
+        let samples = deterministic.samples(uniforms);
+        assert!(samples[50..950].iter().all(|x| (x - 1.0).abs() < 0.05));
+    }
+}
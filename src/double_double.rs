@@ -0,0 +1,128 @@
+//! Double-double arithmetic, about 32 significant digits from an unevaluated sum of two floats.
+//!
+//! Based on the error-free transformations of Dekker and Knuth, without the accurate variants of the operations since only sums of products are needed.
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Number represented by the unevaluated sum `hi + lo` with `|lo|` at most half a unit in the last place of `hi`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct DoubleDouble {
+    /// Leading part, the nearest float to the number.
+    hi: f64,
+    /// Trailing part.
+    lo: f64,
+}
+
+impl DoubleDouble {
+    /// Exact sum of two floats.
+    pub(crate) fn sum(a: f64, b: f64) -> Self {
+        let hi = a + b;
+        let b_virtual = hi - a;
+        let lo = (a - (hi - b_virtual)) + (b - b_virtual);
+
+        Self { hi, lo }
+    }
+
+    /// Exact product of two floats.
+    pub(crate) fn product(a: f64, b: f64) -> Self {
+        let hi = a * b;
+
+        Self {
+            hi,
+            lo: a.mul_add(b, -hi),
+        }
+    }
+
+    /// Renormalize when `|lo|` is known to be smaller than `|hi|`.
+    fn quick_sum(hi: f64, lo: f64) -> Self {
+        let sum = hi + lo;
+
+        Self {
+            hi: sum,
+            lo: lo - (sum - hi),
+        }
+    }
+
+    /// Round to the nearest float.
+    pub(crate) fn to_f64(self) -> f64 {
+        self.hi + self.lo
+    }
+}
+
+impl From<f64> for DoubleDouble {
+    fn from(x: f64) -> Self {
+        Self { hi: x, lo: 0.0 }
+    }
+}
+
+impl Neg for DoubleDouble {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            hi: -self.hi,
+            lo: -self.lo,
+        }
+    }
+}
+
+impl Add for DoubleDouble {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let sum = Self::sum(self.hi, rhs.hi);
+
+        Self::quick_sum(sum.hi, sum.lo + self.lo + rhs.lo)
+    }
+}
+
+impl Sub for DoubleDouble {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Mul for DoubleDouble {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let product = Self::product(self.hi, rhs.hi);
+
+        Self::quick_sum(product.hi, product.lo + self.hi * rhs.lo + self.lo * rhs.hi)
+    }
+}
+
+impl Div for DoubleDouble {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        // Correct the quotient of the leading parts with the remainder
+        let quotient = self.hi / rhs.hi;
+        let remainder = self - rhs * Self::from(quotient);
+
+        Self::quick_sum(quotient, remainder.hi / rhs.hi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        // The error of the float product is kept
+        let product = DoubleDouble::product(0.1, 0.1);
+        assert_eq!(product.to_f64(), 0.1 * 0.1);
+        assert_ne!(product.lo, 0.0);
+
+        // Cancellation that loses everything in floats
+        let big = DoubleDouble::from(1e17);
+        assert_eq!(((big + DoubleDouble::from(1.0)) - big).to_f64(), 1.0);
+
+        let third = DoubleDouble::from(1.0) / DoubleDouble::from(3.0);
+        let one = third * DoubleDouble::from(3.0);
+        assert!((one - DoubleDouble::from(1.0)).to_f64().abs() < 1e-30);
+    }
+}
@@ -0,0 +1,284 @@
+//! Distribution of relaxation times (DRT) of impedance spectra, with the regularized inversion of the [`regularization`](crate::regularization) module.
+//!
+//! An impedance spectrum of a battery or fuel cell is modelled as a continuum of RC elements,
+//!
+//! $$
+//! Z(\omega) = R_\infty + \int_{-\infty}^\infty \frac{\gamma(\ln \tau)}{1 + i \omega \tau} d \ln \tau
+//! $$
+//!
+//! In the rates $1 / \tau$ and with $s = i \omega$ this is a Stieltjes transform, the Laplace transform applied twice, so recovering $\gamma$ is the same ill-posed problem as inverting a Laplace transform from samples.
+//! The integral is discretized on a grid of relaxation times with trapezoidal weights in $\ln \tau$, and the real and imaginary parts of the measured impedances give the rows of the least squares problem.
+//! The series resistance $R_\infty$ is fitted as well but isn't penalized.
+//!
+//! # Example
+//!
+//! ```rust
+//! # fn main() {
+//! use iltcme::drt::Drt;
+//! use nalgebra::Complex;
+//!
+//! // Single RC element with a resistance of 2 ohm and a time constant of 1 ms in series with 0.1 ohm
+//! let omegas = (0..50).map(|k| 10.0_f64.powf(k as f64 / 7.0)).collect::<Vec<_>>();
+//! let impedances = omegas
+//!     .iter()
+//!     .map(|omega| 0.1 + 2.0 / (1.0 + Complex::new(0.0, omega * 1e-3)))
+//!     .collect::<Vec<_>>();
+//!
+//! let spectrum = Drt::log_spaced(1e-6, 1.0, 61).fit(&omegas, &impedances);
+//! approx::assert_relative_eq!(spectrum.r_inf, 0.1, epsilon = 0.01);
+//! approx::assert_relative_eq!(spectrum.polarization_resistance(), 2.0, epsilon = 0.05);
+//! # }
+//! ```
+
+use nalgebra::{Complex, DMatrix, DVector};
+
+use crate::regularization::{self, ParameterChoice, Penalty};
+
+/// Computation of the distribution of relaxation times on a grid.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Drt {
+    /// Increasing relaxation times the distribution is recovered at.
+    taus: Vec<f64>,
+    /// Rule choosing the regularization parameter.
+    choice: ParameterChoice,
+    /// Operator whose norm is penalized.
+    penalty: Penalty,
+    /// Whether the distribution is constrained to be nonnegative.
+    nonnegative: bool,
+}
+
+impl Drt {
+    /// Create a computation on the increasing relaxation times `taus`.
+    ///
+    /// By default the regularization parameter is chosen with [`ParameterChoice::Gcv`], the [`Penalty::Curvature`] is penalized and the distribution is nonnegative.
+    pub fn new(taus: Vec<f64>) -> Self {
+        assert!(taus.len() >= 2, "Grid needs at least two relaxation times");
+        assert!(
+            taus[0] > 0.0 && taus.windows(2).all(|pair| pair[0] < pair[1]),
+            "Relaxation times must be positive and increasing"
+        );
+
+        Self {
+            taus,
+            choice: ParameterChoice::Gcv,
+            penalty: Penalty::default(),
+            nonnegative: true,
+        }
+    }
+
+    /// Create a computation on `points` logarithmically spaced relaxation times from `min_tau` to `max_tau`.
+    ///
+    /// The grid should extend about a decade beyond the reciprocals of the measured angular frequencies.
+    pub fn log_spaced(min_tau: f64, max_tau: f64, points: usize) -> Self {
+        let ratio = (max_tau / min_tau).ln();
+        Self::new(
+            (0..points)
+                .map(|k| min_tau * (ratio * k as f64 / (points - 1) as f64).exp())
+                .collect(),
+        )
+    }
+
+    /// Set the rule choosing the regularization parameter.
+    pub fn with_choice(mut self, choice: ParameterChoice) -> Self {
+        self.choice = choice;
+
+        self
+    }
+
+    /// Set the operator whose norm is penalized.
+    pub fn with_penalty(mut self, penalty: Penalty) -> Self {
+        self.penalty = penalty;
+
+        self
+    }
+
+    /// Set whether the distribution is constrained to be nonnegative, which is the case for impedances of passive RC networks.
+    pub fn with_nonnegative(mut self, nonnegative: bool) -> Self {
+        self.nonnegative = nonnegative;
+
+        self
+    }
+
+    /// Increasing relaxation times the distribution is recovered at.
+    pub fn taus(&self) -> &[f64] {
+        &self.taus
+    }
+
+    /// Rule choosing the regularization parameter.
+    pub fn choice(&self) -> ParameterChoice {
+        self.choice
+    }
+
+    /// Operator whose norm is penalized.
+    pub fn penalty(&self) -> Penalty {
+        self.penalty
+    }
+
+    /// Whether the distribution is constrained to be nonnegative.
+    pub fn nonnegative(&self) -> bool {
+        self.nonnegative
+    }
+
+    /// Recover the distribution of relaxation times from the `impedances` measured at the angular frequencies `omegas`.
+    pub fn fit(&self, omegas: &[f64], impedances: &[Complex<f64>]) -> DrtSpectrum {
+        assert_eq!(
+            omegas.len(),
+            impedances.len(),
+            "Every angular frequency needs an impedance"
+        );
+
+        let weights = log_weights(&self.taus);
+        let (rows, columns) = (2 * omegas.len(), self.taus.len() + 1);
+        // First column is the series resistance, the imaginary rows don't depend on it
+        let kernel = DMatrix::from_fn(rows, columns, |i, j| {
+            let omega = omegas[i / 2];
+            match (j, i % 2) {
+                (0, 0) => 1.0,
+                (0, _) => 0.0,
+                (j, part) => {
+                    let product = omega * self.taus[j - 1];
+                    let element = 1.0 / (1.0 + Complex::new(0.0, product));
+                    weights[j - 1] * if part == 0 { element.re } else { element.im }
+                }
+            }
+        });
+        let data = DVector::from_iterator(
+            rows,
+            impedances
+                .iter()
+                .flat_map(|impedance| [impedance.re, impedance.im]),
+        );
+        let penalty = self.penalty.matrix(self.taus.len()).insert_column(0, 0.0);
+
+        let lambda = regularization::select_lambda(&kernel, &data, &penalty, self.choice);
+        let solution = regularization::solve(&kernel, &data, &penalty, lambda, self.nonnegative);
+
+        DrtSpectrum {
+            taus: self.taus.clone(),
+            gamma: solution.rows(1, self.taus.len()).iter().copied().collect(),
+            r_inf: solution[0],
+            lambda,
+        }
+    }
+}
+
+/// Distribution of relaxation times recovered from an impedance spectrum.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DrtSpectrum {
+    /// Relaxation times of the grid.
+    pub taus: Vec<f64>,
+    /// Distribution $\gamma(\ln \tau)$ at the relaxation times.
+    pub gamma: Vec<f64>,
+    /// Series resistance $R_\infty$.
+    pub r_inf: f64,
+    /// Regularization parameter that was used.
+    pub lambda: f64,
+}
+
+impl DrtSpectrum {
+    /// Polarization resistance $\int \gamma(\ln \tau) d \ln \tau$, the difference between the impedance at zero and infinite frequency.
+    pub fn polarization_resistance(&self) -> f64 {
+        log_weights(&self.taus)
+            .iter()
+            .zip(&self.gamma)
+            .map(|(weight, gamma)| weight * gamma)
+            .sum()
+    }
+
+    /// Impedance of the fitted model at angular frequency `omega`.
+    pub fn impedance(&self, omega: f64) -> Complex<f64> {
+        log_weights(&self.taus)
+            .iter()
+            .zip(&self.taus)
+            .zip(&self.gamma)
+            .fold(Complex::from(self.r_inf), |sum, ((weight, tau), gamma)| {
+                sum + weight * gamma / (1.0 + Complex::new(0.0, omega * tau))
+            })
+    }
+}
+
+/// Trapezoidal weights of the grid in the logarithm of the relaxation times.
+fn log_weights(taus: &[f64]) -> Vec<f64> {
+    let logs = taus.iter().map(|tau| tau.ln()).collect::<Vec<_>>();
+
+    (0..logs.len())
+        .map(|j| {
+            let left = if j > 0 { logs[j] - logs[j - 1] } else { 0.0 };
+            let right = logs.get(j + 1).map_or(0.0, |next| next - logs[j]);
+
+            (left + right) / 2.0
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distribution_of_relaxation_times() {
+        // Gaussian distribution around 1 ms with a polarization resistance of 2 ohm
+        let gaussian = |x: f64| {
+            2.0 / (0.5 * (2.0 * std::f64::consts::PI).sqrt())
+                * (-(x - 1e-3_f64.ln()).powi(2) / (2.0 * 0.5_f64.powi(2))).exp()
+        };
+        let omegas = (0..36)
+            .map(|k| 10.0_f64.powf(k as f64 / 7.0))
+            .collect::<Vec<_>>();
+        let impedances = omegas
+            .iter()
+            .enumerate()
+            .map(|(k, omega)| {
+                let exact = (0..2000)
+                    .map(|j| {
+                        let x = -20.0 + j as f64 * 0.01;
+                        0.01 * gaussian(x) / (1.0 + Complex::new(0.0, omega * x.exp()))
+                    })
+                    .sum::<Complex<f64>>();
+                // Noise of about 1 mohm
+                0.1 + exact
+                    + Complex::new(
+                        1e-3 * (k as f64 * 12.9898).sin(),
+                        1e-3 * (k as f64 * 78.233).sin(),
+                    )
+            })
+            .collect::<Vec<_>>();
+
+        let max_error = |spectrum: &DrtSpectrum| {
+            spectrum
+                .taus
+                .iter()
+                .zip(&spectrum.gamma)
+                .map(|(tau, gamma)| (gamma - gaussian(tau.ln())).abs())
+                .fold(0.0, f64::max)
+        };
+
+        let drt = Drt::log_spaced(1e-6, 10.0, 57);
+        for choice in [ParameterChoice::Gcv, ParameterChoice::LCurve] {
+            let spectrum = drt.clone().with_choice(choice).fit(&omegas, &impedances);
+            assert!(approx::relative_eq!(spectrum.r_inf, 0.1, epsilon = 0.01));
+            assert!(approx::relative_eq!(
+                spectrum.polarization_resistance(),
+                2.0,
+                epsilon = 0.02
+            ));
+            // Peak is about 1.6
+            assert!(max_error(&spectrum) < 0.1);
+            assert!(omegas
+                .iter()
+                .zip(&impedances)
+                .all(|(omega, impedance)| (spectrum.impedance(*omega) - impedance).norm() < 5e-3));
+
+            // Parameters far from the chosen one under- or oversmooth
+            for factor in [1e-4, 1e4] {
+                let fixed = drt
+                    .clone()
+                    .with_choice(ParameterChoice::Fixed(spectrum.lambda * factor))
+                    .fit(&omegas, &impedances);
+                assert!(max_error(&fixed) > 0.5);
+            }
+        }
+    }
+}
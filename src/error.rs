@@ -0,0 +1,60 @@
+//! Errors that can occur while inverting a Laplace transform.
+
+use std::fmt::{Display, Formatter};
+
+use nalgebra::Complex;
+
+/// Reason a Laplace inversion could not be computed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Error {
+    /// More function evaluations were requested than there are coefficients for.
+    TooManyEvaluations {
+        /// Requested maximum function evaluations.
+        requested: usize,
+        /// Largest supported maximum function evaluations.
+        max: usize,
+    },
+    /// The inversion was requested at a time that's zero or negative.
+    NonPositiveTime(f64),
+    /// The Laplace transform function returned NaN or infinity.
+    NonFiniteTransform {
+        /// Index of the node that was evaluated, starting at zero.
+        node: usize,
+        /// Node of the inversion method, the transform was evaluated at `beta / t`.
+        beta: Complex<f64>,
+        /// Argument passed to the Laplace transform function.
+        s: Complex<f64>,
+        /// Non-finite value that was returned.
+        value: Complex<f64>,
+    },
+    /// The width of the smoothing window is negative or NaN.
+    InvalidSmoothing(f64),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooManyEvaluations { requested, max } => write!(
+                f,
+                "Laplace maximum function evaluations must be less or equal to {max}, got {requested}"
+            ),
+            Self::NonPositiveTime(t) => {
+                write!(f, "Laplace inversion is only defined for positive times, got {t}")
+            }
+            Self::NonFiniteTransform {
+                node,
+                beta,
+                s,
+                value,
+            } => write!(
+                f,
+                "Laplace transform function returned {value} at node {node} (beta = {beta}, s = {s})"
+            ),
+            Self::InvalidSmoothing(width) => {
+                write!(f, "Smoothing width must not be negative, got {width}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,156 @@
+//! Fitting a sum of exponentials $f(t) \approx \sum_k w_k e^{-\lambda_k t}$ directly from evaluations of the transform.
+//!
+//! The transform is approximated with a [`RationalFit`], a barycentric rational function whose support points are chosen greedily where the error is largest.
+//! Its poles and residues give the partial fractions, which are the Laplace transforms of the exponentials.
+//! A constant term of the rational function would be an impulse at $t = 0$ and isn't part of the sum.
+//!
+//! Only the sample points matter, they can be on the real axis when the transform can't be evaluated anywhere else.
+//! The transform at the conjugate of a complex point is its conjugate, so the poles come in conjugate pairs and the inverse is real.
+//!
+//! # Example
+//!
+//! ```rust
+//! # fn main() {
+//! use iltcme::exponentials::ExponentialSum;
+//! use nalgebra::Complex;
+//!
+//! // Transform of `2 e^(-t) + e^(-3t)` sampled on the real axis
+//! let points = (0..20)
+//!     .map(|k| Complex::from(0.1 * 1.5_f64.powi(k)))
+//!     .collect::<Vec<_>>();
+//! let sum = ExponentialSum::fit(|s| 2.0 / (s + 1.0) + 1.0 / (s + 3.0), &points, 1e-12, 10);
+//! assert_eq!(sum.terms().len(), 2);
+//! approx::assert_relative_eq!(sum.inverse(1.0), 2.0 * (-1.0_f64).exp() + (-3.0_f64).exp(), epsilon = 1e-9);
+//! # }
+//! ```
+
+use nalgebra::Complex;
+
+use crate::{poles::Pole, rational::RationalFit};
+
+/// Sum of exponentials $\sum_k r_k e^{p_k t}$, the inverse of the partial fractions $\sum_k \frac{r_k}{s - p_k}$.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExponentialSum {
+    /// Simple poles with their residues.
+    terms: Vec<Pole>,
+    /// Largest error of the rational approximation at the sample points.
+    error: f64,
+}
+
+impl ExponentialSum {
+    /// Create a sum from simple poles, the rates are the negated locations and the weights the residues.
+    pub fn new(terms: Vec<Pole>) -> Self {
+        assert!(
+            terms.iter().all(|pole| pole.order == 1),
+            "Exponential sums only have simple poles"
+        );
+
+        Self { terms, error: 0.0 }
+    }
+
+    /// Fit a sum of at most `max_terms` exponentials to the Laplace transform function evaluated at `points`.
+    ///
+    /// Support points are added until the largest error at the points is below `tolerance` relative to the largest value of the transform.
+    /// There should be at least twice as many points, including conjugates, as the number of terms.
+    pub fn fit(
+        mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+        points: &[Complex<f64>],
+        tolerance: f64,
+        max_terms: usize,
+    ) -> Self {
+        let samples = points
+            .iter()
+            .map(|s| (*s, laplace_func(*s)))
+            .collect::<Vec<_>>();
+        // A barycentric form with `m` support points has `m - 1` poles
+        let fit = RationalFit::fit(&samples, tolerance, max_terms + 1);
+
+        Self {
+            terms: fit.poles(),
+            error: fit.max_error(),
+        }
+    }
+
+    /// Simple poles with their residues, the rates are the negated locations and the weights the residues.
+    pub fn terms(&self) -> &[Pole] {
+        &self.terms
+    }
+
+    /// Largest error of the rational approximation at the sample points, zero when the sum wasn't fitted.
+    pub fn error(&self) -> f64 {
+        self.error
+    }
+
+    /// Evaluate the partial fractions $\sum_k \frac{r_k}{s - p_k}$.
+    pub fn transform(&self, s: Complex<f64>) -> Complex<f64> {
+        self.terms.iter().map(|pole| pole.transform(s)).sum()
+    }
+
+    /// Evaluate the sum of exponentials at time `t`.
+    pub fn inverse(&self, t: f64) -> f64 {
+        self.terms
+            .iter()
+            .map(|pole| pole.inverse(t))
+            .sum::<Complex<f64>>()
+            .re
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exponential_fits() {
+        // Two decaying exponentials and a damped oscillation
+        let transform = |s: Complex<f64>| {
+            2.0 / (s + 1.0) + 0.5 / (s + 3.0) + (s + 0.5) / ((s + 0.5).powi(2) + 4.0)
+        };
+        let exact =
+            |t: f64| 2.0 * (-t).exp() + 0.5 * (-3.0 * t).exp() + (-0.5 * t).exp() * (2.0 * t).cos();
+        let points = (0..30)
+            .map(|k| Complex::from(0.05 * 100.0_f64.powf(k as f64 / 29.0)))
+            .collect::<Vec<_>>();
+        let sum = ExponentialSum::fit(transform, &points, 1e-12, 10);
+        assert_eq!(sum.terms().len(), 4);
+        assert!(sum.terms().iter().all(|pole| pole.location.re < 0.0));
+        assert!(sum.error() < 1e-12);
+        for t in [0.1_f64, 1.0, 5.0] {
+            assert!(approx::relative_eq!(
+                sum.inverse(t),
+                exact(t),
+                epsilon = 1e-9
+            ));
+        }
+        let s = Complex::new(0.3, 2.0);
+        assert!((sum.transform(s) - transform(s)).norm() < 1e-9);
+
+        // Complex points are sampled with their conjugates
+        let points = (0..8)
+            .map(|k| Complex::new(1.0, k as f64))
+            .collect::<Vec<_>>();
+        let sine = ExponentialSum::fit(|s| 1.0 / (s * s + 1.0), &points, 1e-12, 10);
+        assert_eq!(sine.terms().len(), 2);
+        assert!(approx::relative_eq!(
+            sine.inverse(2.0),
+            2.0_f64.sin(),
+            epsilon = 1e-9
+        ));
+
+        // Branch cut approximated by poles on the negative real axis
+        let sqrt = |s: Complex<f64>| 1.0 / (s + 1.0).sqrt();
+        let points = (0..60)
+            .map(|k| Complex::from(0.01 * 1e4_f64.powf(k as f64 / 59.0)))
+            .collect::<Vec<_>>();
+        let sum = ExponentialSum::fit(sqrt, &points, 1e-10, 30);
+        assert!(sum.terms().len() < 30);
+        for t in [0.1_f64, 1.0, 5.0] {
+            let expected = (-t).exp() / (std::f64::consts::PI * t).sqrt();
+            assert!(approx::relative_eq!(
+                sum.inverse(t),
+                expected,
+                max_relative = 1e-3
+            ));
+        }
+    }
+}
@@ -0,0 +1,530 @@
+//! Transforms parsed from expressions in the complex variable `s` at runtime.
+//!
+//! Inversions configured from files or requests get their transform as text, [`Transform::parse`] turns it into a function that can be inverted.
+//! Names that aren't functions or constants are parameters, bound to a value with [`Transform::with_parameter`].
+//!
+//! # Example
+//!
+//! ```rust
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use iltcme::expression::Transform;
+//!
+//! let transform = Transform::parse("1/(s*(s+a))")?.with_parameter("a", 2.0);
+//! let result = iltcme::laplace_inversion(transform.boxed(), 1.0, 50);
+//! approx::assert_relative_eq!(result, (1.0 - (-2.0_f64).exp()) / 2.0, epsilon = 1e-3);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use nalgebra::Complex;
+
+/// Transform parsed from an expression, with the values of its parameters.
+///
+/// Supports numbers, `s`, the imaginary unit `i`, the constants `pi` and `e`, the operators `+`, `-`, `*`, `/` and `^`, parentheses and the functions `exp`, `ln`, `sqrt`, `sin`, `cos`, `tan`, `sinh`, `cosh`, `tanh` and `atan`.
+/// Like in mathematical notation `^` is a right associative power that binds stronger than a sign, so `-s^2` is $-(s^2)$.
+/// Parameters without a value evaluate to NaN, so inverting before binding all of them fails with [`crate::Error::NonFiniteTransform`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transform {
+    /// Parsed expression, parameters are indices into the parameters.
+    expr: Expr,
+    /// Names of the parameters in order of appearance, with their values once bound.
+    parameters: Vec<(String, Option<Complex<f64>>)>,
+}
+
+impl Transform {
+    /// Parse an expression like `1/(s^2+1)` or `k/(s+a)` with the parameters `k` and `a`.
+    ///
+    /// Fails on expressions nested more than 256 levels deep, where every parenthesis, call, sign, power and operator chained in a sum or product is a level.
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            source,
+            position: 0,
+            parameters: Vec::new(),
+            depth: 0,
+        };
+        let expr = parser.sum()?;
+        parser.skip_whitespace();
+        if let Some(c) = parser.peek() {
+            return Err(parser.error(format!("unexpected '{c}'")));
+        }
+
+        Ok(Self {
+            expr,
+            parameters: parser
+                .parameters
+                .into_iter()
+                .map(|name| (name.to_string(), None))
+                .collect(),
+        })
+    }
+
+    /// Bind a parameter to a value.
+    ///
+    /// Panics when the expression has no parameter with the name.
+    pub fn with_parameter(mut self, name: &str, value: impl Into<Complex<f64>>) -> Self {
+        let parameter = self
+            .parameters
+            .iter_mut()
+            .find(|(parameter, _)| parameter == name);
+        assert!(parameter.is_some(), "Expression has no parameter {name}");
+        if let Some((_, bound)) = parameter {
+            *bound = Some(value.into());
+        }
+
+        self
+    }
+
+    /// Names of the parameters in order of appearance, with their values once bound.
+    pub fn parameters(&self) -> impl Iterator<Item = (&str, Option<Complex<f64>>)> {
+        self.parameters
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value))
+    }
+
+    /// Evaluate the transform at `s`.
+    pub fn evaluate(&self, s: Complex<f64>) -> Complex<f64> {
+        self.expr.evaluate(s, &self.parameters)
+    }
+
+    /// Transform as a boxed function that can be passed to the inverters and shared between threads.
+    pub fn boxed(self) -> Box<dyn Fn(Complex<f64>) -> Complex<f64> + Send + Sync> {
+        Box::new(move |s| self.evaluate(s))
+    }
+}
+
+impl FromStr for Transform {
+    type Err = ParseError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        Self::parse(source)
+    }
+}
+
+/// Error while parsing an expression, with the byte position where it occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Byte position in the source.
+    pub position: usize,
+    /// What went wrong.
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parsed expression that can be evaluated for any complex `s`.
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    /// Complex constant.
+    Constant(Complex<f64>),
+    /// The transform variable `s`.
+    Variable,
+    /// Parameter with the index in the parameters of the transform.
+    Parameter(usize),
+    /// Negation.
+    Neg(Box<Expr>),
+    /// Function applied to an argument.
+    Call(Function, Box<Expr>),
+    /// Binary operator applied to two operands.
+    Binary(Operator, Box<Expr>, Box<Expr>),
+}
+
+/// Functions that can be called in an expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Function {
+    Exp,
+    Ln,
+    Sqrt,
+    Sin,
+    Cos,
+    Tan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Atan,
+}
+
+/// Binary operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+impl Expr {
+    /// Evaluate the expression at `s`, unbound parameters are NaN.
+    fn evaluate(
+        &self,
+        s: Complex<f64>,
+        parameters: &[(String, Option<Complex<f64>>)],
+    ) -> Complex<f64> {
+        let evaluate = |expr: &Self| expr.evaluate(s, parameters);
+
+        match self {
+            Self::Constant(value) => *value,
+            Self::Variable => s,
+            Self::Parameter(index) => parameters[*index]
+                .1
+                .unwrap_or(Complex::new(f64::NAN, f64::NAN)),
+            Self::Neg(expr) => -evaluate(expr),
+            Self::Call(function, argument) => {
+                let x = evaluate(argument);
+                match function {
+                    Function::Exp => x.exp(),
+                    Function::Ln => x.ln(),
+                    Function::Sqrt => x.sqrt(),
+                    Function::Sin => x.sin(),
+                    Function::Cos => x.cos(),
+                    Function::Tan => x.tan(),
+                    Function::Sinh => x.sinh(),
+                    Function::Cosh => x.cosh(),
+                    Function::Tanh => x.tanh(),
+                    Function::Atan => x.atan(),
+                }
+            }
+            Self::Binary(operator, left, right) => {
+                let left = evaluate(left);
+                match (operator, right.as_ref()) {
+                    // Integer powers are exact and much faster
+                    (Operator::Pow, Self::Constant(exponent))
+                        if exponent.im == 0.0
+                            && exponent.re.fract() == 0.0
+                            && exponent.re.abs() <= i32::MAX as f64 =>
+                    {
+                        left.powi(exponent.re as i32)
+                    }
+                    (Operator::Pow, right) => left.powc(evaluate(right)),
+                    (Operator::Add, right) => left + evaluate(right),
+                    (Operator::Sub, right) => left - evaluate(right),
+                    (Operator::Mul, right) => left * evaluate(right),
+                    (Operator::Div, right) => left / evaluate(right),
+                }
+            }
+        }
+    }
+}
+
+/// Deepest nesting of parentheses, calls, signs, powers and chained operators,
+/// which bounds the recursion when parsing, evaluating and dropping the tree.
+const MAX_DEPTH: usize = 256;
+
+/// Recursive descent parser, each method parses one precedence level.
+struct Parser<'a> {
+    source: &'a str,
+    position: usize,
+    /// Names of the parameters found so far.
+    parameters: Vec<&'a str>,
+    /// Nesting of the expression being parsed.
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Terms separated by `+` and `-`.
+    fn sum(&mut self) -> Result<Expr, ParseError> {
+        let depth = self.depth;
+        let mut expr = self.product()?;
+        loop {
+            let operator = match self.next_if(|c| c == '+' || c == '-') {
+                Some('+') => Operator::Add,
+                Some(_) => Operator::Sub,
+                None => {
+                    self.depth = depth;
+
+                    return Ok(expr);
+                }
+            };
+            // Each operator nests the terms before it one level deeper
+            self.enter()?;
+            expr = Expr::Binary(operator, Box::new(expr), Box::new(self.product()?));
+        }
+    }
+
+    /// Factors separated by `*` and `/`.
+    fn product(&mut self) -> Result<Expr, ParseError> {
+        let depth = self.depth;
+        let mut expr = self.unary()?;
+        loop {
+            let operator = match self.next_if(|c| c == '*' || c == '/') {
+                Some('*') => Operator::Mul,
+                Some(_) => Operator::Div,
+                None => {
+                    self.depth = depth;
+
+                    return Ok(expr);
+                }
+            };
+            self.enter()?;
+            expr = Expr::Binary(operator, Box::new(expr), Box::new(self.unary()?));
+        }
+    }
+
+    /// Signs, binding weaker than powers so `-s^2` is `-(s^2)`.
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        match self.next_if(|c| c == '+' || c == '-') {
+            Some('-') => Ok(Expr::Neg(Box::new(self.nested(Self::unary)?))),
+            Some(_) => self.nested(Self::unary),
+            None => self.power(),
+        }
+    }
+
+    /// Right associative powers.
+    fn power(&mut self) -> Result<Expr, ParseError> {
+        let base = self.atom()?;
+        if self.next_if(|c| c == '^').is_some() {
+            Ok(Expr::Binary(
+                Operator::Pow,
+                Box::new(base),
+                Box::new(self.nested(Self::unary)?),
+            ))
+        } else {
+            Ok(base)
+        }
+    }
+
+    /// Numbers, names, function calls and parenthesized expressions.
+    fn atom(&mut self) -> Result<Expr, ParseError> {
+        self.skip_whitespace();
+        let start = self.position;
+
+        match self.peek() {
+            Some('(') => {
+                self.position += 1;
+                let expr = self.nested(Self::sum)?;
+                self.expect(')')?;
+
+                Ok(expr)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                self.take_while(|c| c.is_ascii_digit() || c == '.');
+                // Exponent of scientific notation
+                let rest = &self.source[self.position..];
+                let mut exponent = 0;
+                if rest.starts_with(['e', 'E']) {
+                    let digits = rest[1..].trim_start_matches(['+', '-']);
+                    if digits.starts_with(|c: char| c.is_ascii_digit()) {
+                        let digits_len = digits
+                            .find(|c: char| !c.is_ascii_digit())
+                            .unwrap_or(digits.len());
+                        exponent = rest.len() - digits.len() + digits_len;
+                    }
+                }
+                self.position += exponent;
+                let number = &self.source[start..self.position];
+
+                number
+                    .parse::<f64>()
+                    .map(|value| Expr::Constant(value.into()))
+                    .map_err(|_| ParseError {
+                        position: start,
+                        message: format!("invalid number '{number}'"),
+                    })
+            }
+            Some(c) if c.is_alphabetic() => {
+                let name = self.take_while(|c| c.is_alphanumeric() || c == '_');
+                if self.next_if(|c| c == '(').is_none() {
+                    return Ok(match name {
+                        "s" => Expr::Variable,
+                        "i" => Expr::Constant(Complex::i()),
+                        "pi" => Expr::Constant(std::f64::consts::PI.into()),
+                        "e" => Expr::Constant(std::f64::consts::E.into()),
+                        _ => Expr::Parameter(self.parameter(name)),
+                    });
+                }
+
+                let function = match name {
+                    "exp" => Function::Exp,
+                    "ln" | "log" => Function::Ln,
+                    "sqrt" => Function::Sqrt,
+                    "sin" => Function::Sin,
+                    "cos" => Function::Cos,
+                    "tan" => Function::Tan,
+                    "sinh" => Function::Sinh,
+                    "cosh" => Function::Cosh,
+                    "tanh" => Function::Tanh,
+                    "atan" => Function::Atan,
+                    _ => {
+                        return Err(ParseError {
+                            position: start,
+                            message: format!("unknown function '{name}'"),
+                        })
+                    }
+                };
+
+                let argument = self.nested(Self::sum)?;
+                self.expect(')')?;
+
+                Ok(Expr::Call(function, Box::new(argument)))
+            }
+            Some(c) => Err(self.error(format!("unexpected '{c}'"))),
+            None => Err(self.error("unexpected end of expression".to_string())),
+        }
+    }
+
+    /// Parse one level deeper.
+    fn nested(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<Expr, ParseError>,
+    ) -> Result<Expr, ParseError> {
+        self.enter()?;
+        let expr = parse(self)?;
+        self.depth -= 1;
+
+        Ok(expr)
+    }
+
+    /// Go one level deeper or fail past the maximum depth.
+    fn enter(&mut self) -> Result<(), ParseError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("expression nested too deeply".to_string()));
+        }
+        self.depth += 1;
+
+        Ok(())
+    }
+
+    /// Index of the parameter, added when it's new.
+    fn parameter(&mut self, name: &'a str) -> usize {
+        match self
+            .parameters
+            .iter()
+            .position(|parameter| *parameter == name)
+        {
+            Some(index) => index,
+            None => {
+                self.parameters.push(name);
+
+                self.parameters.len() - 1
+            }
+        }
+    }
+
+    /// Consume the next non-whitespace character or fail.
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        match self.next_if(|c| c == expected) {
+            Some(_) => Ok(()),
+            None => Err(self.error(format!("expected '{expected}'"))),
+        }
+    }
+
+    /// Consume the next non-whitespace character when it matches.
+    fn next_if(&mut self, predicate: impl Fn(char) -> bool) -> Option<char> {
+        self.skip_whitespace();
+        let c = self.peek().filter(|c| predicate(*c))?;
+        self.position += c.len_utf8();
+
+        Some(c)
+    }
+
+    /// Consume characters while they match.
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let start = self.position;
+        let rest = &self.source[start..];
+        self.position += rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+
+        &self.source[start..self.position]
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.position..].chars().next()
+    }
+
+    fn error(&self, message: String) -> ParseError {
+        ParseError {
+            position: self.position,
+            message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let s = Complex::new(0.5, 2.0);
+
+        for (source, expected) in [
+            ("1/(s^2+1)", 1.0 / (s * s + 1.0)),
+            ("-s^2", -(s * s)),
+            ("2^3^2", Complex::from(512.0)),
+            ("exp(-s) / s", (-s).exp() / s),
+            ("1.5e-1 * s + 2*i", s * 0.15 + Complex::new(0.0, 2.0)),
+            ("sqrt(s) - pi", s.sqrt() - std::f64::consts::PI),
+        ] {
+            let result = Transform::parse(source).unwrap().evaluate(s);
+            assert!(
+                (result - expected).norm() < 1e-12,
+                "{source} is {result}, expected {expected}"
+            );
+        }
+
+        assert_eq!(Transform::parse("1/(s+1").unwrap_err().position, 6);
+        assert_eq!(Transform::parse("foo(s)").unwrap_err().position, 0);
+        assert!(Transform::parse("s s").is_err());
+        assert!(Transform::parse("").is_err());
+
+        // Deep nesting fails instead of overflowing the stack
+        for source in [
+            format!("{}s{}", "(".repeat(100_000), ")".repeat(100_000)),
+            format!("{}s", "-".repeat(100_000)),
+            format!("s{}", "^-s".repeat(100_000)),
+            format!("{}s{}", "exp(".repeat(100_000), ")".repeat(100_000)),
+            format!("s{}", "+s".repeat(100_000)),
+        ] {
+            assert_eq!(
+                Transform::parse(&source).unwrap_err().message,
+                "expression nested too deeply"
+            );
+        }
+        let nested = format!("{}s{}", "(".repeat(200), ")".repeat(200));
+        assert_eq!(Transform::parse(&nested).unwrap().evaluate(s), s);
+    }
+
+    #[test]
+    fn parameters() {
+        let s = Complex::new(0.5, 2.0);
+        let transform: Transform = "k / (s + a) + a".parse().unwrap();
+        assert_eq!(
+            transform.parameters().collect::<Vec<_>>(),
+            [("k", None), ("a", None)]
+        );
+        assert!(transform.evaluate(s).is_nan());
+
+        let transform = transform
+            .with_parameter("a", 2.0)
+            .with_parameter("k", Complex::new(0.0, 3.0));
+        assert_eq!(
+            transform.parameters().collect::<Vec<_>>(),
+            [("k", Some(Complex::new(0.0, 3.0))), ("a", Some(2.0.into()))]
+        );
+        assert_eq!(
+            transform.evaluate(s),
+            Complex::new(0.0, 3.0) / (s + 2.0) + 2.0
+        );
+
+        // Inverting with an unbound parameter fails at the first node
+        let unbound = Transform::parse("1 / (s + a)").unwrap().boxed();
+        assert!(matches!(
+            crate::CmeInverter::new(50).unwrap().invert(unbound, 1.0),
+            Err(crate::Error::NonFiniteTransform { node: 0, .. })
+        ));
+    }
+}
@@ -0,0 +1,402 @@
+//! European option prices for models with a known moment generating function of the log price.
+//!
+//! The call price as a function of the log-strike $k = \ln(K / F)$, relative to the forward $F$, has the bilateral transform
+//!
+//! $$
+//! \int_{-\infty}^{\infty} e^{sk} C(k) \, dk = e^{-rT} F \frac{M(1 + s)}{s (s + 1)}, \quad \operatorname{Re}(s) > 0
+//! $$
+//!
+//! where $M(u) = E[(S_T / F)^u]$. The price is recovered by integrating along the vertical contour $\operatorname{Re}(s) = \sigma$, which must lie inside the strip where the moment $M(1 + \sigma)$ exists.
+
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use nalgebra::{Complex, ComplexField};
+
+use crate::special::norm_cdf;
+
+/// Default real part of the integration contour.
+const DEFAULT_CONTOUR: f64 = 0.5;
+
+/// Step size of the double exponential quadrature along the contour.
+const QUADRATURE_STEP: f64 = 1.0 / 64.0;
+
+/// Bound of the double exponential quadrature variable on both sides.
+const QUADRATURE_BOUND: f64 = 4.5;
+
+/// Kind of European option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OptionKind {
+    /// Right to buy at the strike.
+    Call,
+    /// Right to sell at the strike.
+    Put,
+}
+
+/// Model of the price of the underlying at maturity.
+pub trait PricingModel {
+    /// Current price of the underlying.
+    fn spot(&self) -> f64;
+
+    /// Continuously compounded risk-free interest rate.
+    fn rate(&self) -> f64;
+
+    /// Moment generating function $E[e^{u X}]$ of the log-return $X = \ln(S_T / S_0) - rT$.
+    ///
+    /// Must equal one at $u = 1$ for the discounted price to be a martingale.
+    fn log_return_mgf(&self, u: Complex<f64>, maturity: f64) -> Complex<f64>;
+
+    /// Largest real $u$ for which the moment $E[e^{u X}]$ is finite.
+    fn moment_bound(&self, _maturity: f64) -> f64 {
+        f64::INFINITY
+    }
+
+    /// Forward price of the underlying at maturity.
+    fn forward(&self, maturity: f64) -> f64 {
+        self.spot() * (self.rate() * maturity).exp()
+    }
+}
+
+/// Black-Scholes model with a constant volatility.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlackScholes {
+    /// Current price of the underlying.
+    pub spot: f64,
+    /// Risk-free interest rate.
+    pub rate: f64,
+    /// Volatility of the log price.
+    pub volatility: f64,
+}
+
+impl BlackScholes {
+    /// Closed-form Black-Scholes price, used as a reference.
+    pub fn analytic_price(&self, kind: OptionKind, strike: f64, maturity: f64) -> f64 {
+        let deviation = self.volatility * maturity.sqrt();
+        let d1 = ((self.spot / strike).ln() + self.rate * maturity) / deviation + deviation / 2.0;
+        let d2 = d1 - deviation;
+        let discount = (-self.rate * maturity).exp();
+
+        match kind {
+            OptionKind::Call => self.spot * norm_cdf(d1) - strike * discount * norm_cdf(d2),
+            OptionKind::Put => strike * discount * norm_cdf(-d2) - self.spot * norm_cdf(-d1),
+        }
+    }
+}
+
+impl PricingModel for BlackScholes {
+    fn spot(&self) -> f64 {
+        self.spot
+    }
+
+    fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    fn log_return_mgf(&self, u: Complex<f64>, maturity: f64) -> Complex<f64> {
+        let variance = self.volatility.powi(2) * maturity;
+
+        (u * (u - 1.0) * variance / 2.0).exp()
+    }
+}
+
+/// Heston stochastic volatility model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Heston {
+    /// Current price of the underlying.
+    pub spot: f64,
+    /// Risk-free interest rate.
+    pub rate: f64,
+    /// Current variance.
+    pub initial_variance: f64,
+    /// Rate at which the variance reverts to the long-term mean.
+    pub mean_reversion: f64,
+    /// Long-term mean of the variance.
+    pub long_term_variance: f64,
+    /// Volatility of the variance.
+    pub vol_of_vol: f64,
+    /// Correlation between the price and the variance.
+    pub correlation: f64,
+}
+
+impl PricingModel for Heston {
+    fn spot(&self) -> f64 {
+        self.spot
+    }
+
+    fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    fn log_return_mgf(&self, u: Complex<f64>, maturity: f64) -> Complex<f64> {
+        let (kappa, xi) = (self.mean_reversion, self.vol_of_vol);
+
+        // Formulation that avoids branch cuts of the complex logarithm
+        let b = kappa - u * self.correlation * xi;
+        let d = (b.powi(2) - u * (u - 1.0) * xi.powi(2)).sqrt();
+        let g = (b - d) / (b + d);
+        let decay = (-d * maturity).exp();
+
+        let c = kappa * self.long_term_variance / xi.powi(2)
+            * ((b - d) * maturity - ((-g * decay + 1.0) / (-g + 1.0)).ln() * 2.0);
+        let v = (b - d) / xi.powi(2) * (-decay + 1.0) / (-g * decay + 1.0);
+
+        (c + v * self.initial_variance).exp()
+    }
+}
+
+/// Variance-gamma model, Brownian motion with drift evaluated at a gamma distributed time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VarianceGamma {
+    /// Current price of the underlying.
+    pub spot: f64,
+    /// Risk-free interest rate.
+    pub rate: f64,
+    /// Volatility of the Brownian motion.
+    pub volatility: f64,
+    /// Variance rate of the gamma time change.
+    pub variance_rate: f64,
+    /// Drift of the Brownian motion, controls the skewness.
+    pub drift: f64,
+}
+
+impl VarianceGamma {
+    /// Base of the moment generating function $1 - \theta \nu u - \frac{1}{2} \sigma^2 \nu u^2$.
+    fn base(&self, u: Complex<f64>) -> Complex<f64> {
+        -u * self.drift * self.variance_rate
+            - u.powi(2) * self.volatility.powi(2) * self.variance_rate / 2.0
+            + 1.0
+    }
+}
+
+impl PricingModel for VarianceGamma {
+    fn spot(&self) -> f64 {
+        self.spot
+    }
+
+    fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    fn log_return_mgf(&self, u: Complex<f64>, maturity: f64) -> Complex<f64> {
+        let exponent = -maturity / self.variance_rate;
+        // Drift compensating the convexity of the exponent
+        let compensator = -self.base(Complex::new(1.0, 0.0)).re.ln() * exponent;
+
+        self.base(u).powc(Complex::from(exponent)) * (u * compensator).exp()
+    }
+
+    fn moment_bound(&self, _maturity: f64) -> f64 {
+        // Positive root of the base
+        let a = self.volatility.powi(2) * self.variance_rate / 2.0;
+        let b = self.drift * self.variance_rate;
+
+        (-b + (b.powi(2) + 4.0 * a).sqrt()) / (2.0 * a)
+    }
+}
+
+/// Transform of the call price in the log-strike relative to the forward, see the module documentation.
+pub fn call_transform(model: &impl PricingModel, maturity: f64, s: Complex<f64>) -> Complex<f64> {
+    let scale = (-model.rate() * maturity).exp() * model.forward(maturity);
+
+    model.log_return_mgf(s + 1.0, maturity) / (s * (s + 1.0)) * scale
+}
+
+/// Calculate the price of a European option.
+///
+/// The integration contour is chosen halfway inside the strip where the moments of the model exist.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::finance::{price, BlackScholes, OptionKind};
+///
+/// let model = BlackScholes {
+///     spot: 100.0,
+///     rate: 0.05,
+///     volatility: 0.2,
+/// };
+/// let call = price(&model, OptionKind::Call, 110.0, 1.0);
+/// approx::assert_relative_eq!(call, model.analytic_price(OptionKind::Call, 110.0, 1.0), epsilon = 1e-8);
+/// # }
+/// ```
+pub fn price(model: &impl PricingModel, kind: OptionKind, strike: f64, maturity: f64) -> f64 {
+    let bound = model.moment_bound(maturity) - 1.0;
+    assert!(bound > 0.0, "Model has no finite moments beyond the first");
+
+    price_with_contour(
+        model,
+        kind,
+        strike,
+        maturity,
+        DEFAULT_CONTOUR.min(bound / 2.0),
+    )
+}
+
+/// Calculate the price of a European option integrating along the contour $\operatorname{Re}(s) = \sigma$.
+///
+/// The contour must be positive and $1 + \sigma$ must lie below the moment bound of the model.
+pub fn price_with_contour(
+    model: &impl PricingModel,
+    kind: OptionKind,
+    strike: f64,
+    maturity: f64,
+    sigma: f64,
+) -> f64 {
+    assert!(sigma > 0.0, "Integration contour must lie right of zero");
+    assert!(
+        1.0 + sigma < model.moment_bound(maturity),
+        "Integration contour must lie inside the strip of finite moments"
+    );
+
+    let forward = model.forward(maturity);
+    let discount = (-model.rate() * maturity).exp();
+    let log_strike = (strike / forward).ln();
+
+    // Double exponential substitution `u = exp(pi / 2 sinh(x))` for the integral over `[0, inf)`
+    let steps = (QUADRATURE_BOUND / QUADRATURE_STEP) as i32;
+    let integral = (-steps..=steps)
+        .map(|i| {
+            let x = f64::from(i) * QUADRATURE_STEP;
+            let u = (FRAC_PI_2 * x.sinh()).exp();
+            let weight = FRAC_PI_2 * x.cosh() * u;
+            let s = Complex::new(sigma, u);
+
+            let term = (Complex::new(0.0, -u * log_strike).exp()
+                * call_transform(model, maturity, s))
+            .re * weight;
+
+            // Far tail of the contour underflows
+            if term.is_finite() {
+                term
+            } else {
+                0.0
+            }
+        })
+        .sum::<f64>()
+        * QUADRATURE_STEP;
+
+    let call = (-sigma * log_strike).exp() / PI * integral;
+
+    match kind {
+        OptionKind::Call => call,
+        // Put-call parity
+        OptionKind::Put => call - discount * (forward - strike),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn black_scholes() {
+        let model = BlackScholes {
+            spot: 100.0,
+            rate: 0.03,
+            volatility: 0.25,
+        };
+
+        for strike in [60.0, 90.0, 100.0, 120.0, 180.0] {
+            for maturity in [0.1, 1.0, 5.0] {
+                for kind in [OptionKind::Call, OptionKind::Put] {
+                    assert!(approx::relative_eq!(
+                        price(&model, kind, strike, maturity),
+                        model.analytic_price(kind, strike, maturity),
+                        epsilon = 1e-8
+                    ));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn heston_without_vol_of_vol() {
+        let model = Heston {
+            spot: 100.0,
+            rate: 0.02,
+            initial_variance: 0.04,
+            mean_reversion: 1.5,
+            long_term_variance: 0.09,
+            vol_of_vol: 1e-4,
+            correlation: -0.7,
+        };
+        let maturity = 2.0;
+
+        // Deterministic variance integrates to Black-Scholes with the average variance
+        let kappa_t = model.mean_reversion * maturity;
+        let variance = model.long_term_variance
+            + (model.initial_variance - model.long_term_variance) * (1.0 - (-kappa_t).exp())
+                / kappa_t;
+        let reference = BlackScholes {
+            spot: model.spot,
+            rate: model.rate,
+            volatility: variance.sqrt(),
+        };
+
+        for strike in [80.0, 100.0, 125.0] {
+            assert!(approx::relative_eq!(
+                price(&model, OptionKind::Call, strike, maturity),
+                reference.analytic_price(OptionKind::Call, strike, maturity),
+                epsilon = 1e-3
+            ));
+        }
+    }
+
+    #[test]
+    fn heston_put_call_parity() {
+        let model = Heston {
+            spot: 100.0,
+            rate: 0.01,
+            initial_variance: 0.04,
+            mean_reversion: 2.0,
+            long_term_variance: 0.04,
+            vol_of_vol: 0.5,
+            correlation: -0.5,
+        };
+
+        let call = price(&model, OptionKind::Call, 100.0, 1.0);
+        let put = price(&model, OptionKind::Put, 100.0, 1.0);
+        // Contour shift doesn't change the price
+        let shifted = price_with_contour(&model, OptionKind::Call, 100.0, 1.0, 0.25);
+
+        assert!(call > 0.0 && put > 0.0);
+        assert!(approx::relative_eq!(call, shifted, epsilon = 1e-6));
+    }
+
+    #[test]
+    fn variance_gamma() {
+        let model = VarianceGamma {
+            spot: 100.0,
+            rate: 0.05,
+            volatility: 0.2,
+            variance_rate: 1e-5,
+            drift: 0.0,
+        };
+        let reference = BlackScholes {
+            spot: 100.0,
+            rate: 0.05,
+            volatility: 0.2,
+        };
+
+        // Small variance rate tends to Black-Scholes
+        assert!(approx::relative_eq!(
+            price(&model, OptionKind::Call, 105.0, 1.0),
+            reference.analytic_price(OptionKind::Call, 105.0, 1.0),
+            epsilon = 1e-3
+        ));
+
+        let skewed = VarianceGamma {
+            variance_rate: 0.5,
+            drift: -0.3,
+            ..model
+        };
+        assert!(skewed.moment_bound(1.0) > 1.0);
+        let call = price(&skewed, OptionKind::Call, 100.0, 1.0);
+        let shifted = price_with_contour(&skewed, OptionKind::Call, 100.0, 1.0, 0.1);
+        assert!(approx::relative_eq!(call, shifted, epsilon = 1e-6));
+    }
+}
@@ -0,0 +1,390 @@
+//! First-passage times of one-dimensional diffusions $dX = \mu(X) dt + \sigma(X) dW$ to a level.
+//!
+//! The transform of the first time $T$ the diffusion started at $x$ reaches the level $b$ is
+//!
+//! $$
+//! E[e^{-sT}] = \frac{\phi_s(x)}{\phi_s(b)}
+//! $$
+//!
+//! where $\phi_s$ solves $\frac{\sigma^2}{2} \phi'' + \mu \phi' = s \phi$ and is increasing for a level above the start, decreasing for a level below it.
+//! For Brownian motion with drift it's an exponential, for the Ornstein-Uhlenbeck and Cox-Ingersoll-Ross processes $\phi_s$ is a parabolic cylinder or confluent hypergeometric function.
+//! Those are evaluated by integrating the Riccati equation of the logarithmic derivative $w = \phi_s' / \phi_s$,
+//!
+//! $$
+//! w' = \frac{2 (s - \mu w)}{\sigma^2} - w^2
+//! $$
+//!
+//! from far away from the start and level, or from the origin for the Cox-Ingersoll-Ross process, towards them.
+//! In that direction the wanted solution dominates, so the errors of the starting value die out.
+//!
+//! The densities have the singularities of the transform on the negative real axis.
+//! For Brownian motion with drift the branch point $-\frac{\mu^2}{2 \sigma^2}$ is moved to the origin before the inversion, and the exponential decay it causes is multiplied back afterwards, so the tail keeps its relative accuracy.
+
+use nalgebra::Complex;
+
+/// Number of stationary standard deviations between the mean and where the integration starts.
+const FAR_DEVIATIONS: f64 = 10.0;
+
+/// Largest product of the step size and the stiffness of the Riccati equation.
+const STEP_FACTOR: f64 = 0.25;
+
+/// Minimum number of steps between the start and the level.
+const MIN_STEPS: f64 = 200.0;
+
+/// Diffusion with a classical first-passage transform.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::first_passage::Diffusion;
+///
+/// // Inverse Gaussian density of Brownian motion with drift 1 reaching 2
+/// let diffusion = Diffusion::BrownianMotion {
+///     drift: 1.0,
+///     volatility: 1.0,
+/// };
+/// let pdf = diffusion.first_passage_pdf(0.0, 2.0, &[2.0], 100);
+/// let exact = 2.0 / (2.0 * std::f64::consts::PI * 8.0_f64).sqrt();
+/// approx::assert_relative_eq!(pdf[0], exact, epsilon = 1e-5);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Diffusion {
+    /// Brownian motion $dX = \mu dt + \sigma dW$.
+    BrownianMotion {
+        /// Drift $\mu$.
+        drift: f64,
+        /// Volatility $\sigma$.
+        volatility: f64,
+    },
+    /// Ornstein-Uhlenbeck process $dX = \theta (m - X) dt + \sigma dW$, for example the membrane potential of a leaky integrate-and-fire neuron.
+    OrnsteinUhlenbeck {
+        /// Rate of mean reversion $\theta$.
+        reversion: f64,
+        /// Long-term mean $m$.
+        mean: f64,
+        /// Volatility $\sigma$.
+        volatility: f64,
+    },
+    /// Cox-Ingersoll-Ross process $dX = \kappa (m - X) dt + \sigma \sqrt{X} dW$ on the positive half-line, for example a short rate or a default intensity.
+    CoxIngersollRoss {
+        /// Rate of mean reversion $\kappa$.
+        reversion: f64,
+        /// Long-term mean $m$.
+        mean: f64,
+        /// Volatility $\sigma$.
+        volatility: f64,
+    },
+}
+
+impl Diffusion {
+    /// Drift $\mu(x)$.
+    pub fn drift(&self, x: f64) -> f64 {
+        match *self {
+            Self::BrownianMotion { drift, .. } => drift,
+            Self::OrnsteinUhlenbeck {
+                reversion, mean, ..
+            }
+            | Self::CoxIngersollRoss {
+                reversion, mean, ..
+            } => reversion * (mean - x),
+        }
+    }
+
+    /// Variance rate $\sigma^2(x)$.
+    pub fn variance(&self, x: f64) -> f64 {
+        match *self {
+            Self::BrownianMotion { volatility, .. }
+            | Self::OrnsteinUhlenbeck { volatility, .. } => volatility * volatility,
+            Self::CoxIngersollRoss { volatility, .. } => volatility * volatility * x,
+        }
+    }
+
+    /// Evaluate the transform $E[e^{-sT}]$ of the first time the diffusion started at `start` reaches `level`.
+    ///
+    /// The density is defective when the level isn't reached with probability one, like for Brownian motion drifting away from it.
+    pub fn first_passage_transform(&self, start: f64, level: f64, s: Complex<f64>) -> Complex<f64> {
+        self.validate(start, level);
+        if start == level {
+            return Complex::from(1.0);
+        }
+
+        match *self {
+            Self::BrownianMotion { drift, volatility } => {
+                let distance = level - start;
+                let variance = volatility * volatility;
+
+                ((drift * distance - distance.abs() * (drift * drift + 2.0 * variance * s).sqrt())
+                    / variance)
+                    .exp()
+            }
+            _ => (-self.log_derivative_integral(start, level, s)).exp(),
+        }
+    }
+
+    /// Calculate the density of the first-passage time from `start` to `level` at each of the times.
+    pub fn first_passage_pdf(
+        &self,
+        start: f64,
+        level: f64,
+        times: &[f64],
+        max_function_evals: usize,
+    ) -> Vec<f64> {
+        let shift = self.abscissa();
+
+        times
+            .iter()
+            .map(|t| {
+                (shift * t).exp()
+                    * crate::laplace_inversion(
+                        |s| self.first_passage_transform(start, level, s + shift),
+                        *t,
+                        max_function_evals,
+                    )
+            })
+            .collect()
+    }
+
+    /// Calculate the probability that `level` is reached from `start` before each of the times.
+    pub fn first_passage_cdf(
+        &self,
+        start: f64,
+        level: f64,
+        times: &[f64],
+        max_function_evals: usize,
+    ) -> Vec<f64> {
+        times
+            .iter()
+            .map(|t| {
+                crate::laplace_inversion(
+                    |s| self.first_passage_transform(start, level, s) / s,
+                    *t,
+                    max_function_evals,
+                )
+            })
+            .collect()
+    }
+
+    /// Rightmost singularity of the transform when it's known in closed form, zero otherwise.
+    fn abscissa(&self) -> f64 {
+        match *self {
+            Self::BrownianMotion { drift, volatility } => {
+                -drift * drift / (2.0 * volatility * volatility)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Check the parameters of the diffusion and the states.
+    fn validate(&self, start: f64, level: f64) {
+        let (reversion, volatility) = match *self {
+            Self::BrownianMotion { volatility, .. } => (1.0, volatility),
+            Self::OrnsteinUhlenbeck {
+                reversion,
+                volatility,
+                ..
+            } => (reversion, volatility),
+            Self::CoxIngersollRoss {
+                reversion,
+                mean,
+                volatility,
+            } => {
+                assert!(mean > 0.0, "Long-term mean must be positive");
+                assert!(
+                    start > 0.0 && level > 0.0,
+                    "Start and level must be positive"
+                );
+                (reversion, volatility)
+            }
+        };
+        assert!(reversion > 0.0, "Rate of mean reversion must be positive");
+        assert!(volatility > 0.0, "Volatility must be positive");
+    }
+
+    /// Integral of the logarithmic derivative of $\phi_s$ from `start` to `level`.
+    fn log_derivative_integral(&self, start: f64, level: f64, s: Complex<f64>) -> Complex<f64> {
+        let upward = level > start;
+        let (far, w) = self.far_solution(start.min(level), start.max(level), upward, s);
+
+        // Towards the start without keeping the integral, then on to the level
+        let (w, _) = self.integrate_riccati(far, start, w, s, (start - far).abs() / MIN_STEPS);
+        let (_, integral) =
+            self.integrate_riccati(start, level, w, s, (level - start).abs() / MIN_STEPS);
+
+        integral
+    }
+
+    /// Where the integration starts for states between `low` and `high`, with the logarithmic derivative there.
+    fn far_solution(
+        &self,
+        low: f64,
+        high: f64,
+        upward: bool,
+        s: Complex<f64>,
+    ) -> (f64, Complex<f64>) {
+        let (reversion, mean, volatility) = match *self {
+            Self::OrnsteinUhlenbeck {
+                reversion,
+                mean,
+                volatility,
+            }
+            | Self::CoxIngersollRoss {
+                reversion,
+                mean,
+                volatility,
+            } => (reversion, mean, volatility),
+            Self::BrownianMotion { .. } => unreachable!("Brownian motion has a closed form"),
+        };
+
+        if let (Self::CoxIngersollRoss { .. }, true) = (self, upward) {
+            // Solution that is regular at the origin, `M(s / kappa, 2 kappa m / sigma^2, 2 kappa x / sigma^2)`
+            let (a, b, scale) = (
+                s / reversion,
+                2.0 * reversion * mean / (volatility * volatility),
+                2.0 * reversion / (volatility * volatility),
+            );
+            let x = low * 1e-9;
+            let first = a / b * scale;
+            let second = a * (a + 1.0) / (b * (b + 1.0)) * scale * scale;
+
+            return (x, first + (second - first * first) * x);
+        }
+
+        // Far in the tail of the stationary distribution the coefficients barely change, so `w` solves the quadratic
+        let deviation = (self.variance(mean) / (2.0 * reversion)).sqrt();
+        let x = if upward {
+            low.min(mean - FAR_DEVIATIONS * deviation)
+        } else {
+            high.max(mean + FAR_DEVIATIONS * deviation)
+        };
+        let (drift, variance) = (self.drift(x), self.variance(x));
+        let root = (drift * drift + 2.0 * variance * s).sqrt();
+        let sign = if upward { 1.0 } else { -1.0 };
+
+        (x, (-drift + sign * root) / variance)
+    }
+
+    /// Integrate the Riccati equation from `from` to `to` with the classical Runge-Kutta method, returning the final value and the integral of `w`.
+    fn integrate_riccati(
+        &self,
+        from: f64,
+        to: f64,
+        mut w: Complex<f64>,
+        s: Complex<f64>,
+        max_step: f64,
+    ) -> (Complex<f64>, Complex<f64>) {
+        let riccati =
+            |x: f64, w: Complex<f64>| 2.0 * (s - self.drift(x) * w) / self.variance(x) - w * w;
+        let direction = (to - from).signum();
+
+        let (mut x, mut integral) = (from, Complex::from(0.0));
+        while (to - x) * direction > 0.0 {
+            // Explicit steps must stay below the decay rate of the perturbations
+            let stiffness = 2.0 * w.norm() + 2.0 * self.drift(x).abs() / self.variance(x);
+            let step = (STEP_FACTOR / stiffness).min(max_step).min((to - x).abs()) * direction;
+
+            let k1 = riccati(x, w);
+            let k2 = riccati(x + step / 2.0, w + k1 * step / 2.0);
+            let k3 = riccati(x + step / 2.0, w + k2 * step / 2.0);
+            let k4 = riccati(x + step, w + k3 * step);
+            integral += (w * 6.0 + (k1 + k2 + k3) * step) * step / 6.0;
+            w += (k1 + k2 * 2.0 + k3 * 2.0 + k4) * step / 6.0;
+            x = if (to - x - step) * direction > 0.0 {
+                x + step
+            } else {
+                to
+            };
+        }
+
+        (w, integral)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::special::erfc;
+
+    #[test]
+    fn first_passage_times() {
+        // Inverse Gaussian density, and a defective one when drifting away from the level
+        let brownian = Diffusion::BrownianMotion {
+            drift: 0.5,
+            volatility: 0.8,
+        };
+        let times = [0.5, 2.0, 10.0, 40.0];
+        let pdf = brownian.first_passage_pdf(1.0, 3.0, &times, 100);
+        for (t, density) in times.iter().zip(pdf) {
+            let exact = 2.0 / (0.8 * (2.0 * PI * t.powi(3)).sqrt())
+                * (-(2.0 - 0.5 * t).powi(2) / (2.0 * 0.64 * t)).exp();
+            assert!(approx::relative_eq!(density, exact, epsilon = 1e-4));
+        }
+        let away = brownian.first_passage_cdf(1.0, -1.0, &[500.0], 100)[0];
+        assert!(approx::relative_eq!(
+            away,
+            (-2.0 * 0.5 * 2.0 / 0.64_f64).exp(),
+            epsilon = 1e-4
+        ));
+
+        // Ornstein-Uhlenbeck process reaching its mean is Brownian motion on the clock `sigma^2 (e^(2 theta t) - 1) / (2 theta)`
+        let (reversion, volatility) = (0.7, 0.4);
+        let process = Diffusion::OrnsteinUhlenbeck {
+            reversion,
+            mean: 0.2,
+            volatility,
+        };
+        let cdf = process.first_passage_cdf(0.5, 0.2, &times[..3], 100);
+        for (t, probability) in times.iter().zip(cdf) {
+            let clock =
+                volatility.powi(2) * ((2.0 * reversion * t).exp() - 1.0) / (2.0 * reversion);
+            assert!(approx::relative_eq!(
+                probability,
+                erfc(0.3 / (2.0 * clock).sqrt()),
+                epsilon = 1e-4
+            ));
+        }
+        // By symmetry the mirrored start reaches the mean upwards in the same time
+        let mirrored = process.first_passage_cdf(-0.1, 0.2, &times[..3], 100);
+        let cdf = process.first_passage_cdf(0.5, 0.2, &times[..3], 100);
+        assert!(mirrored.iter().zip(cdf).all(|(a, b)| (a - b).abs() < 1e-6));
+
+        // Cox-Ingersoll-Ross transform at real arguments is a ratio of Kummer functions
+        let (kappa, mean, sigma) = (1.5, 0.04, 0.2);
+        let rate = Diffusion::CoxIngersollRoss {
+            reversion: kappa,
+            mean,
+            volatility: sigma,
+        };
+        let kummer = |a: f64, x: f64| {
+            let (b, z) = (
+                2.0 * kappa * mean / (sigma * sigma),
+                2.0 * kappa * x / (sigma * sigma),
+            );
+            let (mut term, mut sum) = (1.0, 1.0);
+            for n in 0..500 {
+                let n = n as f64;
+                term *= (a + n) / (b + n) * z / (n + 1.0);
+                sum += term;
+            }
+            sum
+        };
+        for s in [0.1, 1.0, 10.0] {
+            let exact = kummer(s / kappa, 0.03) / kummer(s / kappa, 0.08);
+            assert!(approx::relative_eq!(
+                rate.first_passage_transform(0.03, 0.08, Complex::from(s))
+                    .re,
+                exact,
+                max_relative = 1e-8
+            ));
+        }
+        // Mean reversion makes both levels certain to be reached
+        for level in [0.01, 0.08] {
+            let cdf = rate.first_passage_cdf(0.04, level, &[200.0], 100)[0];
+            assert!(approx::relative_eq!(cdf, 1.0, epsilon = 1e-4));
+        }
+    }
+}
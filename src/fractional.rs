@@ -0,0 +1,158 @@
+//! Solve linear fractional differential equations with Caputo derivatives.
+//!
+//! The equation
+//!
+//! $$
+//! \sum_k a_k D^{\alpha_k} y(t) = g(t)
+//! $$
+//!
+//! is transformed to the Laplace domain using $\mathcal{L}[D^\alpha y](s) = s^\alpha Y(s) - \sum_{j=0}^{\lceil \alpha \rceil - 1} s^{\alpha - 1 - j} y^{(j)}(0)$, solved for $Y(s)$ and inverted on a time grid.
+
+use nalgebra::Complex;
+
+/// Linear fractional differential equation $\sum_k a_k D^{\alpha_k} y(t) = g(t)$ with Caputo derivatives.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::fractional::FractionalOde;
+///
+/// // Fractional relaxation `D^0.5 y = -y` with `y(0) = 1`
+/// let ode = FractionalOde::new()
+///     .with_term(1.0, 0.5)
+///     .with_term(1.0, 0.0)
+///     .with_initial_values(&[1.0]);
+/// let solution = ode.solve(&[1.0], 100);
+/// approx::assert_relative_eq!(
+///     solution.y[0],
+///     iltcme::mittag_leffler::mittag_leffler(0.5, 1.0, -1.0, 100),
+///     epsilon = 1e-4
+/// );
+/// # }
+/// ```
+#[derive(Default)]
+pub struct FractionalOde<'a> {
+    /// Coefficient $a_k$ and derivative order $\alpha_k$ of each term.
+    terms: Vec<(f64, f64)>,
+    /// Initial values $y(0), y'(0), \ldots$.
+    initial_values: Vec<f64>,
+    /// Laplace transform of the forcing function $g$.
+    forcing: Option<crate::BoxedLaplaceFunc<'a>>,
+}
+
+/// Solution of a fractional differential equation on a time grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution {
+    /// Times the solution was computed at.
+    pub t: Vec<f64>,
+    /// Value of the solution at each time.
+    pub y: Vec<f64>,
+}
+
+impl<'a> FractionalOde<'a> {
+    /// Construct an equation without any terms, initial values or forcing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the term $a D^\alpha y$, an order of zero is the function itself.
+    pub fn with_term(mut self, coefficient: f64, order: f64) -> Self {
+        assert!(order >= 0.0, "Derivative order can't be negative");
+
+        self.terms.push((coefficient, order));
+
+        self
+    }
+
+    /// Set the initial values $y(0), y'(0), \ldots$, missing derivatives are zero.
+    pub fn with_initial_values(mut self, initial_values: &[f64]) -> Self {
+        self.initial_values = initial_values.to_vec();
+
+        self
+    }
+
+    /// Set the Laplace transform of the forcing function $g$ on the right hand side.
+    pub fn with_forcing(
+        mut self,
+        laplace_func: impl Fn(Complex<f64>) -> Complex<f64> + 'a,
+    ) -> Self {
+        self.forcing = Some(Box::new(laplace_func));
+
+        self
+    }
+
+    /// Evaluate the Laplace transform $Y(s)$ of the solution.
+    pub fn transform(&self, s: Complex<f64>) -> Complex<f64> {
+        let mut numerator = self
+            .forcing
+            .as_ref()
+            .map_or(Complex::new(0.0, 0.0), |forcing| forcing(s));
+        let mut denominator = Complex::new(0.0, 0.0);
+        for (coefficient, order) in &self.terms {
+            denominator += s.powf(*order) * *coefficient;
+
+            // Initial values required by the Caputo derivative
+            let required = order.ceil() as usize;
+            for (j, initial_value) in self.initial_values.iter().take(required).enumerate() {
+                numerator += s.powf(order - 1.0 - j as f64) * *coefficient * *initial_value;
+            }
+        }
+
+        numerator / denominator
+    }
+
+    /// Solve the equation at each of the times.
+    ///
+    /// Maximum number of evaluations is given by [`crate::coefficients::max_evaluations`].
+    pub fn solve(&self, times: &[f64], max_function_evals: usize) -> Solution {
+        let y = times
+            .iter()
+            .map(|t| crate::laplace_inversion(|s| self.transform(s), *t, max_function_evals))
+            .collect();
+
+        Solution {
+            t: times.to_vec(),
+            y,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_orders() {
+        // Damped oscillator `y'' + y = 1` with `y(0) = 0` and `y'(0) = 1`
+        let ode = FractionalOde::new()
+            .with_term(1.0, 2.0)
+            .with_term(1.0, 0.0)
+            .with_initial_values(&[0.0, 1.0])
+            .with_forcing(|s| 1.0 / s);
+        let times = [0.5, 1.0, 2.0];
+        let solution = ode.solve(&times, 100);
+
+        assert_eq!(solution.t, times);
+        for (t, y) in solution.t.iter().zip(solution.y) {
+            let expected = 1.0 - t.cos() + t.sin();
+            assert!(approx::relative_eq!(y, expected, epsilon = 1e-3));
+        }
+    }
+
+    #[test]
+    fn fractional_relaxation() {
+        // `D^0.8 y = -2 y` with `y(0) = 3` is solved by `3 E_0.8(-2 t^0.8)`
+        let ode = FractionalOde::new()
+            .with_term(1.0, 0.8)
+            .with_term(2.0, 0.0)
+            .with_initial_values(&[3.0]);
+        let solution = ode.solve(&[0.2, 1.0, 4.0], 100);
+
+        for (t, y) in solution.t.iter().zip(solution.y) {
+            let expected =
+                3.0 * crate::mittag_leffler::mittag_leffler(0.8, 1.0, -2.0 * t.powf(0.8), 100);
+            assert!(approx::relative_eq!(y, expected, epsilon = 1e-4));
+        }
+    }
+}
@@ -0,0 +1,375 @@
+//! Transient heat conduction in semi-infinite and layered media after a step at the surface.
+//!
+//! In each layer with conductivity $k$ and diffusivity $\alpha$ the transform of the temperature rise satisfies $\frac{d^2 \bar{\theta}}{dz^2} = q^2 \bar{\theta}$ with $q = \sqrt{s / \alpha}$.
+//! A layer of thickness $L$ relates the temperature and the heat flux $\bar{\phi} = -k \frac{d \bar{\theta}}{dz}$ at its faces by the quadrupole
+//!
+//! $$
+//! \begin{pmatrix} \bar{\theta}_0 \\\\ \bar{\phi}_0 \end{pmatrix} = \begin{pmatrix} \cosh(qL) & \frac{\sinh(qL)}{kq} \\\\ kq \sinh(qL) & \cosh(qL) \end{pmatrix} \begin{pmatrix} \bar{\theta}_L \\\\ \bar{\phi}_L \end{pmatrix}
+//! $$
+//!
+//! so a stack of layers on a substrate only needs products of these matrices.
+//! They are evaluated as the admittances $\bar{\phi} / \bar{\theta}$ at the interfaces from the back to the surface, scaled so the hyperbolic functions don't overflow for large $qL$.
+//!
+//! The solutions have a branch point at $s = 0$ with a cut along the negative real axis, or poles on the negative real axis for a finite slab.
+//! They are inverted along a [`HyperbolicInverter`] contour around the negative real axis, which converges much faster for such transforms than the Bromwich line.
+
+use nalgebra::Complex;
+
+use crate::{
+    contour::{HyperbolicInverter, SingularityRegion},
+    Error,
+};
+
+/// Thermal properties of a homogeneous material.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Material {
+    /// Thermal conductivity $k$.
+    conductivity: f64,
+    /// Thermal diffusivity $\alpha = k / (\rho c)$.
+    diffusivity: f64,
+}
+
+impl Material {
+    /// Construct from the thermal conductivity $k$ and the thermal diffusivity $\alpha$.
+    pub fn new(conductivity: f64, diffusivity: f64) -> Self {
+        assert!(conductivity > 0.0, "Conductivity must be positive");
+        assert!(diffusivity > 0.0, "Diffusivity must be positive");
+
+        Self {
+            conductivity,
+            diffusivity,
+        }
+    }
+
+    /// Thermal conductivity $k$.
+    pub fn conductivity(&self) -> f64 {
+        self.conductivity
+    }
+
+    /// Thermal diffusivity $\alpha$.
+    pub fn diffusivity(&self) -> f64 {
+        self.diffusivity
+    }
+
+    /// Wave number $q = \sqrt{s / \alpha}$ and the product $kq$.
+    fn wave_number(&self, s: Complex<f64>) -> (Complex<f64>, Complex<f64>) {
+        let q = (s / self.diffusivity).sqrt();
+
+        (q, self.conductivity * q)
+    }
+}
+
+/// Layer of a slab.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Layer {
+    /// Material of the layer.
+    pub material: Material,
+    /// Thickness of the layer.
+    pub thickness: f64,
+}
+
+/// Condition at the back of the last layer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Backing {
+    /// No heat flows through the back face.
+    Insulated,
+    /// Back face is kept at the initial temperature.
+    Isothermal,
+    /// Layers lie on a semi-infinite substrate of the material.
+    SemiInfinite(Material),
+}
+
+/// Step applied at the surface at $t = 0$, with the medium at a uniform initial temperature.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SurfaceStep {
+    /// Surface temperature rises by this amount.
+    Temperature(f64),
+    /// Constant heat flux into the surface.
+    Flux(f64),
+}
+
+/// Temperature rise in a medium of layers on a backing after a step at the surface.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), iltcme::Error> {
+/// use iltcme::heat::{Conduction, Material, SurfaceStep};
+///
+/// // Surface of concrete heated by 100 K, after an hour the temperature at 5 cm follows the complementary error function
+/// let concrete = Material::new(1.7, 8e-7);
+/// let conduction = Conduction::semi_infinite(concrete, SurfaceStep::Temperature(100.0));
+/// let temperature = conduction.temperature(&[0.05], 3600.0, 40)?;
+/// let exact = 100.0 * iltcme::special::erfc(0.05 / (2.0 * (8e-7 * 3600.0_f64).sqrt()));
+/// approx::assert_relative_eq!(temperature[0], exact, epsilon = 1e-6);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Conduction {
+    /// Layers from the surface to the back.
+    layers: Vec<Layer>,
+    /// Condition at the back of the last layer.
+    backing: Backing,
+    /// Step applied at the surface.
+    step: SurfaceStep,
+}
+
+impl Conduction {
+    /// Half-space of a single material.
+    pub fn semi_infinite(material: Material, step: SurfaceStep) -> Self {
+        Self {
+            layers: Vec::new(),
+            backing: Backing::SemiInfinite(material),
+            step,
+        }
+    }
+
+    /// Layers from the surface to the back, with the `backing` behind the last layer.
+    pub fn layered(layers: Vec<Layer>, backing: Backing, step: SurfaceStep) -> Self {
+        assert!(!layers.is_empty(), "Slab needs at least one layer");
+        assert!(
+            layers.iter().all(|layer| layer.thickness > 0.0),
+            "Layer thickness must be positive"
+        );
+
+        Self {
+            layers,
+            backing,
+            step,
+        }
+    }
+
+    /// Layers from the surface to the back.
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// Condition at the back of the last layer.
+    pub fn backing(&self) -> Backing {
+        self.backing
+    }
+
+    /// Step applied at the surface.
+    pub fn step(&self) -> SurfaceStep {
+        self.step
+    }
+
+    /// Evaluate the transform of the temperature rise at `depth` below the surface.
+    pub fn temperature_transform(&self, depth: f64, s: Complex<f64>) -> Complex<f64> {
+        assert!(depth >= 0.0, "Depth can't be negative");
+        assert!(
+            matches!(self.backing, Backing::SemiInfinite(_))
+                || depth <= self.layers.iter().map(|layer| layer.thickness).sum::<f64>(),
+            "Depth must be inside the slab"
+        );
+
+        let (profiles, admittance) = self.profiles(s);
+        let mut temperature = match self.step {
+            SurfaceStep::Temperature(rise) => rise / s,
+            SurfaceStep::Flux(flux) => flux / (s * admittance),
+        };
+
+        let mut depth = depth;
+        for (layer, Profile { q, c, d }) in self.layers.iter().zip(profiles) {
+            let z = depth.min(layer.thickness);
+            // Profile `c cosh(q (L - z)) + d sinh(q (L - z))` relative to its value at the top, scaled by `e^(-qL) / 2`
+            let (near, far) = ((-q * z).exp(), (-q * (2.0 * layer.thickness - z)).exp());
+            let top = (-2.0 * q * layer.thickness).exp();
+            temperature *=
+                (c * (near + far) + d * (near - far)) / (c * (1.0 + top) + d * (1.0 - top));
+
+            if depth <= layer.thickness {
+                return temperature;
+            }
+            depth -= layer.thickness;
+        }
+
+        // Decaying solution in the substrate
+        match self.backing {
+            Backing::SemiInfinite(material) => {
+                temperature * (-material.wave_number(s).0 * depth).exp()
+            }
+            _ => temperature,
+        }
+    }
+
+    /// Evaluate the transform of the heat flux into the surface.
+    pub fn surface_flux_transform(&self, s: Complex<f64>) -> Complex<f64> {
+        match self.step {
+            SurfaceStep::Temperature(rise) => rise / s * self.profiles(s).1,
+            SurfaceStep::Flux(flux) => flux / s,
+        }
+    }
+
+    /// Calculate the temperature rise at each of the depths at time `t`, inverting along a hyperbolic contour with `points + 1` evaluations.
+    pub fn temperature(&self, depths: &[f64], t: f64, points: usize) -> Result<Vec<f64>, Error> {
+        let inverter = inverter(points);
+
+        depths
+            .iter()
+            .map(|depth| inverter.invert(|s| self.temperature_transform(*depth, s), t))
+            .collect()
+    }
+
+    /// Calculate the temperature rise of the surface at each of the times.
+    pub fn surface_temperature(&self, times: &[f64], points: usize) -> Result<Vec<f64>, Error> {
+        let inverter = inverter(points);
+
+        times
+            .iter()
+            .map(|t| inverter.invert(|s| self.temperature_transform(0.0, s), *t))
+            .collect()
+    }
+
+    /// Calculate the heat flux into the surface at each of the times.
+    pub fn surface_flux(&self, times: &[f64], points: usize) -> Result<Vec<f64>, Error> {
+        let inverter = inverter(points);
+
+        times
+            .iter()
+            .map(|t| inverter.invert(|s| self.surface_flux_transform(s), *t))
+            .collect()
+    }
+
+    /// Temperature profile in each layer, with the admittance of the surface.
+    fn profiles(&self, s: Complex<f64>) -> (Vec<Profile>, Complex<f64>) {
+        let mut profiles = Vec::with_capacity(self.layers.len());
+        // Admittance behind the current layer as the fraction `numerator / denominator`, infinite for an isothermal back
+        let (mut numerator, mut denominator) = match self.backing {
+            Backing::Insulated => (Complex::from(0.0), Complex::from(1.0)),
+            Backing::Isothermal => (Complex::from(1.0), Complex::from(0.0)),
+            Backing::SemiInfinite(material) => (material.wave_number(s).1, Complex::from(1.0)),
+        };
+
+        for layer in self.layers.iter().rev() {
+            let (q, kq) = layer.material.wave_number(s);
+            let (c, d) = (kq * denominator, numerator);
+            let scale = c.norm().max(d.norm());
+            let (c, d) = (c / scale, d / scale);
+            profiles.push(Profile { q, c, d });
+
+            // Admittance `kq (c sinh(qL) + d cosh(qL)) / (c cosh(qL) + d sinh(qL))` at the top of the layer
+            let top = (-2.0 * q * layer.thickness).exp();
+            numerator = kq * (c * (1.0 - top) + d * (1.0 + top));
+            denominator = c * (1.0 + top) + d * (1.0 - top);
+        }
+
+        profiles.reverse();
+
+        (profiles, numerator / denominator)
+    }
+}
+
+/// Profile $c \cosh(q (L - z)) + d \sinh(q (L - z))$ in a layer, which has the admittance of the layers behind it at $z = L$.
+struct Profile {
+    /// Wave number $q$ of the layer.
+    q: Complex<f64>,
+    /// Coefficient of the hyperbolic cosine.
+    c: Complex<f64>,
+    /// Coefficient of the hyperbolic sine.
+    d: Complex<f64>,
+}
+
+/// Contour around the negative real axis, where the branch cut and the poles of conduction problems are.
+fn inverter(points: usize) -> HyperbolicInverter {
+    HyperbolicInverter::new(SingularityRegion::default(), points)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::special::erfc;
+
+    #[test]
+    fn conduction() {
+        let (conductivity, diffusivity) = (1.5, 1e-6);
+        let material = Material::new(conductivity, diffusivity);
+        let depths = [0.0, 0.01, 0.03, 0.06, 0.1];
+        let t = 1000.0;
+        let argument = |depth: f64| depth / (2.0 * (diffusivity * t).sqrt());
+
+        // Classical solutions of the half-space
+        let heated = Conduction::semi_infinite(material, SurfaceStep::Temperature(10.0));
+        for (depth, temperature) in depths
+            .iter()
+            .zip(heated.temperature(&depths, t, 40).unwrap())
+        {
+            assert!(approx::relative_eq!(
+                temperature,
+                10.0 * erfc(argument(*depth)),
+                epsilon = 1e-8
+            ));
+        }
+        assert!(approx::relative_eq!(
+            heated.surface_flux(&[t], 40).unwrap()[0],
+            conductivity * 10.0 / (PI * diffusivity * t).sqrt(),
+            max_relative = 1e-8
+        ));
+        let flux = Conduction::semi_infinite(material, SurfaceStep::Flux(500.0));
+        for (depth, temperature) in depths.iter().zip(flux.temperature(&depths, t, 40).unwrap()) {
+            let x = argument(*depth);
+            let exact = 2.0 * 500.0 / conductivity * (diffusivity * t / PI).sqrt() * (-x * x).exp()
+                - 500.0 * depth / conductivity * erfc(x);
+            assert!(approx::relative_eq!(temperature, exact, epsilon = 1e-8));
+        }
+
+        // Layers of the same material as the substrate don't change anything
+        let layer = Layer {
+            material,
+            thickness: 0.02,
+        };
+        for step in [SurfaceStep::Temperature(10.0), SurfaceStep::Flux(500.0)] {
+            let layered =
+                Conduction::layered(vec![layer; 2], Backing::SemiInfinite(material), step);
+            let expected = Conduction::semi_infinite(material, step).temperature(&depths, t, 40);
+            assert!(layered
+                .temperature(&depths, t, 40)
+                .unwrap()
+                .iter()
+                .zip(expected.unwrap())
+                .all(|(result, expected)| (result - expected).abs() < 1e-8));
+        }
+
+        // Steady state through a wall of two layers in series
+        let insulation = Layer {
+            material: Material::new(0.05, 5e-7),
+            thickness: 0.01,
+        };
+        let wall = Conduction::layered(
+            vec![layer, insulation],
+            Backing::Isothermal,
+            SurfaceStep::Temperature(10.0),
+        );
+        let steady = 10.0 / (0.02 / conductivity + 0.01 / 0.05);
+        let result = wall.temperature(&[0.02, 0.03], 1e5, 40).unwrap();
+        assert!(approx::relative_eq!(
+            result[0],
+            10.0 - steady * 0.02 / conductivity,
+            epsilon = 1e-6
+        ));
+        assert!(result[1].abs() < 1e-6);
+        assert!(approx::relative_eq!(
+            wall.surface_flux(&[1e5], 40).unwrap()[0],
+            steady,
+            max_relative = 1e-6
+        ));
+
+        // Insulated slab heats up linearly on top of the established profile
+        let slab = Conduction::layered(vec![layer], Backing::Insulated, SurfaceStep::Flux(500.0));
+        let late = 5e3;
+        let fourier = diffusivity * late / 0.02_f64.powi(2);
+        assert!(approx::relative_eq!(
+            slab.surface_temperature(&[late], 40).unwrap()[0],
+            500.0 * 0.02 / conductivity * (fourier + 1.0 / 3.0),
+            max_relative = 1e-6
+        ));
+    }
+}
@@ -0,0 +1,192 @@
+//! Inversion that switches to an Euler-summed Fourier series when the CME result oscillates.
+//!
+//! The CME method never overshoots, but it smears out oscillations, so inverses with fast oscillations converge slowly.
+//! The Euler algorithm of Abate and Whitt, the Bromwich integral with Euler summation, converges much faster for them.
+//! For jumps, such as in square waves and staircases, the terms of the Bromwich integral don't alternate and Euler summation doesn't help, the error estimates then keep the result close to the CME result.
+//!
+//! Strong oscillation shows up as disagreement between the CME result and the result of half the order.
+//! The imaginary residual of the weighted sum isn't used, the CME nodes aren't conjugate symmetric so it's large for smooth inverses as well.
+//! When the disagreement exceeds the tolerance the Euler result is computed as well, and both are averaged with weights inversely proportional to their squared error estimates.
+//! The error estimate of the Euler result is the change when the last term is dropped.
+
+use nalgebra::Complex;
+
+use crate::{acceleration::Acceleration, bromwich::BromwichInverter, CmeInverter, Error};
+
+/// Product $\sigma t$ of the contour abscissa and the time of the default Euler algorithm, for a discretization error of about $10^{-8}$.
+pub const DEFAULT_EULER_ABSCISSA: f64 = 9.2;
+
+/// Laplace inverter blending the CME method with the Euler algorithm for oscillating inverses.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), iltcme::Error> {
+/// use iltcme::hybrid::HybridInverter;
+///
+/// // Fast oscillations that 50 evaluations of the CME method don't resolve
+/// let inverter = HybridInverter::new(50)?;
+/// let result = inverter.invert(|s| 10.0 / (s * s + 100.0), 1.5)?;
+/// approx::assert_relative_eq!(result, 15.0_f64.sin(), epsilon = 1e-4);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HybridInverter {
+    /// Inverter used when the result doesn't oscillate.
+    cme: CmeInverter,
+    /// Inverter used for oscillating results.
+    euler: BromwichInverter,
+    /// Largest disagreement between the orders that is still trusted.
+    tolerance: f64,
+}
+
+impl HybridInverter {
+    /// Create an inverter evaluating the Laplace transform function at most `max_function_evals` times with the CME method.
+    ///
+    /// The Euler algorithm averages 12 partial sums after 15 terms, with the [`DEFAULT_EULER_ABSCISSA`].
+    /// The default tolerance is $10^{-3}$.
+    pub fn new(max_function_evals: usize) -> Result<Self, Error> {
+        Ok(Self {
+            cme: CmeInverter::new(max_function_evals)?,
+            euler: BromwichInverter::new(DEFAULT_EULER_ABSCISSA, 26)
+                .with_acceleration(Acceleration::Euler(11)),
+            tolerance: 1e-3,
+        })
+    }
+
+    /// Set the inverter used for oscillating results, the partial sums of the Bromwich integral are accelerated with its [`Acceleration`].
+    ///
+    /// An oscillation with angular frequency $\omega$ needs more than $\omega t / \pi$ terms, otherwise both results miss it and the error estimates can't tell.
+    pub fn with_euler(mut self, euler: BromwichInverter) -> Self {
+        self.euler = euler;
+
+        self
+    }
+
+    /// Set the largest disagreement between the CME result and the result of half the order that is still trusted.
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+
+        self
+    }
+
+    /// Inverter used when the result doesn't oscillate.
+    pub fn cme(&self) -> CmeInverter {
+        self.cme
+    }
+
+    /// Inverter used for oscillating results.
+    pub fn euler(&self) -> BromwichInverter {
+        self.euler
+    }
+
+    /// Largest disagreement between the orders that is still trusted.
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    /// Calculate the Laplace inversion for a function at time `t`.
+    ///
+    /// Returns [`Error::NonFiniteTransform`] for the first node where the Laplace transform function returned NaN or infinity.
+    pub fn invert(
+        &self,
+        laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+        t: f64,
+    ) -> Result<f64, Error> {
+        self.invert_with_weight(laplace_func, t)
+            .map(|(result, _)| result)
+    }
+
+    /// Calculate the Laplace inversion for a function at time `t` and the weight of the CME result in it.
+    ///
+    /// The weight is one when the CME result was trusted, and close to zero when the Euler result is much more accurate.
+    /// Times that are zero or negative are handled by the time policy of the CME inverter.
+    pub fn invert_with_weight(
+        &self,
+        mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+        t: f64,
+    ) -> Result<(f64, f64), Error> {
+        if t.is_nan() || t <= 0.0 {
+            return self.cme.invert(laplace_func, t).map(|result| (result, 1.0));
+        }
+
+        let cme = self.cme.invert(&mut laplace_func, t)?;
+        let lower =
+            CmeInverter::new(self.cme.max_function_evals() / 2)?.invert(&mut laplace_func, t)?;
+        let cme_error = (cme - lower).abs();
+        if cme_error <= self.tolerance {
+            return Ok((cme, 1.0));
+        }
+
+        let sums = self.euler.partial_sums(&mut laplace_func, t)?;
+        let acceleration = self.euler.acceleration();
+        let euler = acceleration.limit(&sums);
+        // Consecutive results can agree by accident, use the largest change over the averaged terms
+        let window = match acceleration {
+            Acceleration::Euler(averaged) => averaged.max(1),
+            _ => 1,
+        };
+        let euler_error = (1..=window.min(sums.len() - 1))
+            .map(|dropped| (euler - acceleration.limit(&sums[..sums.len() - dropped])).abs())
+            .fold(0.0, f64::max);
+
+        // Inverse variance weighting of both estimates
+        let weight = euler_error.powi(2) / (cme_error.powi(2) + euler_error.powi(2));
+
+        Ok((weight * cme + (1.0 - weight) * euler, weight))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hybrid_inversions() {
+        let inverter = HybridInverter::new(50).unwrap();
+
+        // Smooth inverses are left to the CME method
+        for t in [0.5, 1.0, 3.0] {
+            let exponential = |s: Complex<f64>| 1.0 / (s + 1.0);
+            assert_eq!(
+                inverter.invert_with_weight(exponential, t),
+                Ok((
+                    CmeInverter::new(50)
+                        .unwrap()
+                        .invert(exponential, t)
+                        .unwrap(),
+                    1.0
+                ))
+            );
+        }
+
+        // Oscillating inverses mostly use the Euler algorithm
+        for (frequency, t) in [(1.0_f64, 2.5), (1.0, 4.1), (10.0, 0.5), (10.0, 1.5)] {
+            let transform = |s: Complex<f64>| frequency / (s * s + frequency.powi(2));
+            let expected = (frequency * t).sin();
+            let cme = CmeInverter::new(50).unwrap().invert(transform, t).unwrap();
+            let (result, weight) = inverter.invert_with_weight(transform, t).unwrap();
+            assert!(weight < 0.01);
+            assert!((result - expected).abs() * 100.0 < (cme - expected).abs());
+        }
+
+        // Euler summation doesn't help for jumps, but the blend doesn't get worse either
+        let square_wave = |s: Complex<f64>| 1.0 / (s * (1.0 + s.exp()));
+        for t in [4.1_f64, 6.5, 9.5] {
+            let expected = t.floor() % 2.0;
+            let cme = CmeInverter::new(50)
+                .unwrap()
+                .invert(square_wave, t)
+                .unwrap();
+            let result = inverter.invert(square_wave, t).unwrap();
+            assert!((result - expected).abs() <= (cme - expected).abs() * 1.1);
+        }
+
+        assert_eq!(
+            inverter.invert(square_wave, 0.0),
+            Err(Error::NonPositiveTime(0.0))
+        );
+    }
+}
@@ -0,0 +1,400 @@
+//! Rigorous enclosures of the CME sum with interval arithmetic.
+//!
+//! Every operation rounds its bounds outward, so when the transform is evaluated with intervals as well the result is guaranteed to contain the exact weighted sum of the transform at the nodes.
+//! The truncation error of the CME method itself, the difference between that sum and the true inverse, isn't included and has to be bounded separately.
+//!
+//! # Example
+//!
+//! ```rust
+//! # fn main() {
+//! use iltcme::interval::{self, Interval};
+//!
+//! // Exponential with an uncertain rate between 0.9 and 1.1
+//! let rate = Interval::new(0.9, 1.1);
+//! let enclosure = interval::laplace_inversion(|s| 1.0 / (s + rate), 1.0, 50);
+//! assert!(enclosure.contains(iltcme::laplace_inversion(|s| 1.0 / (s + 1.0), 1.0, 50)));
+//! # }
+//! ```
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use nalgebra::Complex;
+
+use crate::coefficients;
+
+/// Closed interval of real numbers with outward rounded arithmetic.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    /// Lower bound.
+    lo: f64,
+    /// Upper bound.
+    hi: f64,
+}
+
+impl Interval {
+    /// Interval containing every real number, the result of undefined operations such as dividing by an interval containing zero.
+    pub const ENTIRE: Self = Self {
+        lo: f64::NEG_INFINITY,
+        hi: f64::INFINITY,
+    };
+
+    /// Construct from the bounds.
+    ///
+    /// Panics when `lo` is above `hi` or either is NaN.
+    pub fn new(lo: f64, hi: f64) -> Self {
+        assert!(
+            lo <= hi,
+            "Lower bound of an interval can't be above the upper bound"
+        );
+
+        Self { lo, hi }
+    }
+
+    /// Lower bound.
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+
+    /// Upper bound.
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    /// Midpoint, which isn't finite for unbounded intervals.
+    pub fn mid(&self) -> f64 {
+        self.lo / 2.0 + self.hi / 2.0
+    }
+
+    /// Distance between the bounds, rounded to nearest.
+    pub fn width(&self) -> f64 {
+        self.hi - self.lo
+    }
+
+    /// Whether `x` lies within the bounds.
+    pub fn contains(&self, x: f64) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+
+    /// Square, tighter than multiplying the interval with itself since the result can't be negative.
+    pub fn square(self) -> Self {
+        let (min, max) = (self.lo.abs(), self.hi.abs());
+        let (min, max) = (min.min(max), min.max(max));
+        let lo = if self.contains(0.0) { 0.0 } else { min * min };
+        let square = Self::outward(lo, max * max);
+
+        Self {
+            lo: square.lo.max(0.0),
+            hi: square.hi,
+        }
+    }
+
+    /// Widen bounds computed with rounding to nearest by a unit in the last place to contain the exact result.
+    ///
+    /// Correctly rounded operations are off by at most half a unit, undefined bounds become unbounded.
+    fn outward(lo: f64, hi: f64) -> Self {
+        Self {
+            lo: if lo.is_nan() {
+                f64::NEG_INFINITY
+            } else {
+                lo.next_down()
+            },
+            hi: if hi.is_nan() {
+                f64::INFINITY
+            } else {
+                hi.next_up()
+            },
+        }
+    }
+}
+
+impl From<f64> for Interval {
+    fn from(x: f64) -> Self {
+        Self::new(x, x)
+    }
+}
+
+impl Neg for Interval {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            lo: -self.hi,
+            hi: -self.lo,
+        }
+    }
+}
+
+impl<T: Into<Interval>> Add<T> for Interval {
+    type Output = Self;
+
+    fn add(self, rhs: T) -> Self {
+        let rhs = rhs.into();
+
+        Self::outward(self.lo + rhs.lo, self.hi + rhs.hi)
+    }
+}
+
+impl<T: Into<Interval>> Sub<T> for Interval {
+    type Output = Self;
+
+    fn sub(self, rhs: T) -> Self {
+        let rhs = rhs.into();
+
+        Self::outward(self.lo - rhs.hi, self.hi - rhs.lo)
+    }
+}
+
+impl<T: Into<Interval>> Mul<T> for Interval {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        let rhs = rhs.into();
+        // Zero times an unbounded bound is zero
+        let product = |x: f64, y: f64| if x == 0.0 || y == 0.0 { 0.0 } else { x * y };
+        let products = [
+            product(self.lo, rhs.lo),
+            product(self.lo, rhs.hi),
+            product(self.hi, rhs.lo),
+            product(self.hi, rhs.hi),
+        ];
+
+        Self::outward(
+            products.into_iter().fold(f64::INFINITY, f64::min),
+            products.into_iter().fold(f64::NEG_INFINITY, f64::max),
+        )
+    }
+}
+
+impl<T: Into<Interval>> Div<T> for Interval {
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self {
+        let rhs = rhs.into();
+        if rhs.contains(0.0) {
+            return Self::ENTIRE;
+        }
+
+        self * Self::outward(1.0 / rhs.hi, 1.0 / rhs.lo)
+    }
+}
+
+/// Rectangle in the complex plane with outward rounded arithmetic.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComplexInterval {
+    /// Real part.
+    pub re: Interval,
+    /// Imaginary part.
+    pub im: Interval,
+}
+
+impl ComplexInterval {
+    /// Construct from the real and imaginary parts.
+    pub fn new(re: Interval, im: Interval) -> Self {
+        Self { re, im }
+    }
+
+    /// Whether `z` lies within the rectangle.
+    pub fn contains(&self, z: Complex<f64>) -> bool {
+        self.re.contains(z.re) && self.im.contains(z.im)
+    }
+
+    /// Complex conjugate.
+    pub fn conj(self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+
+    /// Squared modulus.
+    pub fn norm_sqr(self) -> Interval {
+        self.re.square() + self.im.square()
+    }
+}
+
+impl From<f64> for ComplexInterval {
+    fn from(x: f64) -> Self {
+        Self::new(x.into(), 0.0.into())
+    }
+}
+
+impl From<Interval> for ComplexInterval {
+    fn from(re: Interval) -> Self {
+        Self::new(re, 0.0.into())
+    }
+}
+
+impl From<Complex<f64>> for ComplexInterval {
+    fn from(z: Complex<f64>) -> Self {
+        Self::new(z.re.into(), z.im.into())
+    }
+}
+
+impl Neg for ComplexInterval {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.re, -self.im)
+    }
+}
+
+impl<T: Into<ComplexInterval>> Add<T> for ComplexInterval {
+    type Output = Self;
+
+    fn add(self, rhs: T) -> Self {
+        let rhs = rhs.into();
+
+        Self::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl<T: Into<ComplexInterval>> Sub<T> for ComplexInterval {
+    type Output = Self;
+
+    fn sub(self, rhs: T) -> Self {
+        let rhs = rhs.into();
+
+        Self::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl<T: Into<ComplexInterval>> Mul<T> for ComplexInterval {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        let rhs = rhs.into();
+
+        Self::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+
+impl<T: Into<ComplexInterval>> Div<T> for ComplexInterval {
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self {
+        let rhs = rhs.into();
+        let denominator = rhs.norm_sqr();
+
+        Self::new(
+            (self.re * rhs.re + self.im * rhs.im) / denominator,
+            (self.im * rhs.re - self.re * rhs.im) / denominator,
+        )
+    }
+}
+
+/// Implement the arithmetic operators with a float on the left hand side.
+macro_rules! impl_float_ops {
+    ($type:ty) => {
+        impl Add<$type> for f64 {
+            type Output = $type;
+
+            fn add(self, rhs: $type) -> $type {
+                <$type>::from(self) + rhs
+            }
+        }
+
+        impl Sub<$type> for f64 {
+            type Output = $type;
+
+            fn sub(self, rhs: $type) -> $type {
+                <$type>::from(self) - rhs
+            }
+        }
+
+        impl Mul<$type> for f64 {
+            type Output = $type;
+
+            fn mul(self, rhs: $type) -> $type {
+                <$type>::from(self) * rhs
+            }
+        }
+
+        impl Div<$type> for f64 {
+            type Output = $type;
+
+            fn div(self, rhs: $type) -> $type {
+                <$type>::from(self) / rhs
+            }
+        }
+    };
+}
+
+impl_float_ops!(Interval);
+impl_float_ops!(ComplexInterval);
+
+/// Enclose the weighted CME sum of a transform evaluated with interval arithmetic at time `t`.
+///
+/// The weights and nodes are the floating point values of the coefficients, the nodes divided by the time are passed to the transform as intervals.
+/// Uncertain parameters of the transform can be intervals as well, the result then encloses the sum for every value of the parameters.
+///
+/// Maximum number of evaluations is given by [`crate::coefficients::max_evaluations`].
+pub fn laplace_inversion(
+    mut laplace_func: impl FnMut(ComplexInterval) -> ComplexInterval,
+    t: f64,
+    max_function_evals: usize,
+) -> Interval {
+    let t = Interval::from(t);
+
+    coefficients::order(max_function_evals)
+        .nodes()
+        .map(|(eta, beta)| {
+            let s = ComplexInterval::new(Interval::from(beta.re) / t, Interval::from(beta.im) / t);
+            let value = laplace_func(s);
+
+            // Only the real part of the weighted value is needed
+            eta.re * value.re - eta.im * value.im
+        })
+        .fold(Interval::from(0.0), |sum, term| sum + term)
+        / t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let third = Interval::from(1.0) / 3.0;
+        assert!(third.contains(1.0 / 3.0));
+        assert!(third.width() > 0.0);
+        assert!((3.0 * third).contains(1.0));
+
+        let interval = Interval::new(-1.0, 2.0);
+        assert_eq!(interval.square(), Interval::new(0.0, 4.0_f64.next_up()));
+        assert!((interval * interval).contains(-2.0));
+        assert_eq!(1.0 / interval, Interval::ENTIRE);
+        assert_eq!(
+            Interval::new(0.0, 1.0) * Interval::new(1.0, f64::INFINITY),
+            Interval::new(0.0_f64.next_down(), f64::INFINITY)
+        );
+
+        let z = Complex::new(0.3, -0.7);
+        let w = Complex::new(-1.1, 0.2);
+        let enclosure = ComplexInterval::from(z) / w;
+        assert!(enclosure.contains(z / w));
+        assert!((ComplexInterval::from(z) * w).contains(z * w));
+    }
+
+    #[test]
+    fn enclosures() {
+        // Far tighter than the error of the method
+        for t in [0.1, 1.0, 10.0] {
+            let enclosure = laplace_inversion(|s| 1.0 / (s + 1.0), t, 50);
+            let result = crate::laplace_inversion(|s| 1.0 / (s + 1.0), t, 50);
+            assert!(enclosure.contains(result), "{enclosure:?} {result}");
+            assert!(enclosure.width() < 1e-9);
+
+            let enclosure = laplace_inversion(|s| 1.0 / (s * s + 1.0), t, 50);
+            let result = crate::laplace_inversion(|s| 1.0 / (s * s + 1.0), t, 50);
+            assert!(enclosure.contains(result), "{enclosure:?} {result}");
+            assert!(enclosure.width() < 1e-9);
+        }
+
+        // Every rate within the interval is enclosed
+        let rate = Interval::new(0.9, 1.1);
+        let enclosure = laplace_inversion(|s| 1.0 / (s + rate), 1.0, 50);
+        for rate in [0.9, 1.0, 1.1] {
+            assert!(enclosure.contains(crate::laplace_inversion(|s| 1.0 / (s + rate), 1.0, 50)));
+        }
+    }
+}
@@ -0,0 +1,357 @@
+//! Configuration of a Laplace inversion with a builder, selecting the method and its options.
+
+use nalgebra::Complex;
+
+use crate::{
+    acceleration::Acceleration,
+    bromwich::BromwichInverter,
+    coefficients,
+    hybrid::{HybridInverter, DEFAULT_EULER_ABSCISSA},
+    CmeInverter, Error, Summation,
+};
+
+/// Tolerance of [`Order::Auto`].
+const AUTO_TOLERANCE: f64 = 1e-6;
+
+/// Lowest order tried by [`Order::Tolerance`].
+const MIN_ORDER: usize = 10;
+
+/// Method used to invert the Laplace transform.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Method {
+    /// CME method, see [`CmeInverter`].
+    #[default]
+    Cme,
+    /// Euler algorithm of Abate and Whitt, the Bromwich integral with Euler summation, see [`BromwichInverter`].
+    Euler,
+    /// CME method blended with the Euler algorithm for oscillating inverses, see [`HybridInverter`].
+    Hybrid,
+}
+
+/// Maximum number of function evaluations of the CME and hybrid methods, or number of terms of the Euler algorithm.
+///
+/// A plain number converts to [`Order::Fixed`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Order {
+    /// Increase the order until the result is accurate to about $10^{-6}$.
+    Auto,
+    /// Use this order.
+    Fixed(usize),
+    /// Double the order from 10 until the result changes less than the tolerance, up to the highest order of the CME method.
+    ///
+    /// Each time is inverted with every order tried, so a fixed order that's known to be sufficient is cheaper.
+    Tolerance(f64),
+}
+
+impl Default for Order {
+    fn default() -> Self {
+        Self::Fixed(50)
+    }
+}
+
+impl From<usize> for Order {
+    fn from(order: usize) -> Self {
+        Self::Fixed(order)
+    }
+}
+
+/// Laplace inversion with a method and options configured by an [`InversionBuilder`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), iltcme::Error> {
+/// use iltcme::{Inversion, Method, Order, Summation};
+///
+/// // Growing inverse `e^(2t)`, the nodes are shifted right of the pole
+/// let inversion = Inversion::builder()
+///     .method(Method::Cme)
+///     .order(Order::Auto)
+///     .sigma(2.5)
+///     .summation(Summation::Kahan)
+///     .build()?;
+/// let result = inversion.invert(|s| 1.0 / (s - 2.0), 10.0)?;
+/// approx::assert_relative_eq!(result, 20.0_f64.exp(), max_relative = 1e-3);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Inversion {
+    /// Method used to invert the Laplace transform.
+    method: Method,
+    /// Maximum number of function evaluations, or number of terms of the Euler algorithm.
+    order: Order,
+    /// Shift of the transform to the right.
+    sigma: f64,
+    /// How the terms of the CME sum are added.
+    summation: Summation,
+}
+
+impl Inversion {
+    /// Start configuring an inversion, with the CME method with a fixed order of 50 by default.
+    pub fn builder() -> InversionBuilder {
+        InversionBuilder::default()
+    }
+
+    /// Method used to invert the Laplace transform.
+    pub fn method(&self) -> Method {
+        self.method
+    }
+
+    /// Maximum number of function evaluations, or number of terms of the Euler algorithm.
+    pub fn order(&self) -> Order {
+        self.order
+    }
+
+    /// Shift of the transform to the right.
+    pub fn sigma(&self) -> f64 {
+        self.sigma
+    }
+
+    /// How the terms of the CME sum are added.
+    pub fn summation(&self) -> Summation {
+        self.summation
+    }
+
+    /// Calculate the Laplace inversion for a function at time `t`.
+    ///
+    /// Returns the errors of the configured method, like [`Error::NonFiniteTransform`] for the first node where the Laplace transform function returned NaN or infinity.
+    pub fn invert(
+        &self,
+        mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+        t: f64,
+    ) -> Result<f64, Error> {
+        let mut shifted = |s| laplace_func(s + self.sigma);
+        let tolerance = match self.order {
+            Order::Fixed(order) => return self.invert_with_order(&mut shifted, t, order),
+            Order::Auto => AUTO_TOLERANCE,
+            Order::Tolerance(tolerance) => tolerance,
+        };
+
+        let max = coefficients::max_evaluations();
+        let mut order = MIN_ORDER.min(max);
+        let mut result = self.invert_with_order(&mut shifted, t, order)?;
+        while order < max {
+            order = (2 * order).min(max);
+            let next = self.invert_with_order(&mut shifted, t, order)?;
+            let converged = (next - result).abs() <= tolerance;
+            result = next;
+            if converged {
+                break;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Calculate the Laplace inversion of the shifted function with a fixed order.
+    fn invert_with_order(
+        &self,
+        shifted: impl FnMut(Complex<f64>) -> Complex<f64>,
+        t: f64,
+        order: usize,
+    ) -> Result<f64, Error> {
+        let result = match self.method {
+            Method::Cme => CmeInverter::new(order)?
+                .with_summation(self.summation)
+                .invert(shifted, t)?,
+            Method::Euler => BromwichInverter::new(DEFAULT_EULER_ABSCISSA, order)
+                .with_acceleration(Acceleration::Euler(order / 2))
+                .invert(shifted, t)?,
+            Method::Hybrid => HybridInverter::new(order)?.invert(shifted, t)?,
+        };
+
+        Ok(if self.sigma == 0.0 {
+            result
+        } else {
+            (self.sigma * t).exp() * result
+        })
+    }
+}
+
+/// Builder of an [`Inversion`], see [`Inversion::builder`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InversionBuilder {
+    /// Method used to invert the Laplace transform.
+    method: Method,
+    /// Maximum number of function evaluations, or number of terms of the Euler algorithm.
+    order: Order,
+    /// Shift of the transform to the right.
+    sigma: f64,
+    /// How the terms of the CME sum are added.
+    summation: Summation,
+}
+
+impl Default for InversionBuilder {
+    fn default() -> Self {
+        Self {
+            method: Method::default(),
+            order: Order::default(),
+            sigma: 0.0,
+            summation: Summation::default(),
+        }
+    }
+}
+
+impl InversionBuilder {
+    /// Set the method used to invert the Laplace transform.
+    pub fn method(mut self, method: Method) -> Self {
+        self.method = method;
+
+        self
+    }
+
+    /// Set the maximum number of function evaluations of the CME and hybrid methods, or the number of terms of the Euler algorithm, see [`Order`].
+    ///
+    /// The Euler algorithm averages the partial sums of the second half of the terms.
+    pub fn order(mut self, order: impl Into<Order>) -> Self {
+        self.order = order.into();
+        if let Order::Tolerance(tolerance) = self.order {
+            assert!(tolerance > 0.0, "Tolerance must be positive");
+        }
+
+        self
+    }
+
+    /// Shift the transform to the right, inverting $F(s + \sigma)$ and multiplying the result by $e^{\sigma t}$.
+    ///
+    /// The methods need the singularities of the transform left of their nodes, a shift beyond the rightmost singularity allows inverses that grow exponentially.
+    /// The absolute error of the shifted inversion is amplified by $e^{\sigma t}$, so the shift should only just pass the singularity.
+    pub fn sigma(mut self, sigma: f64) -> Self {
+        self.sigma = sigma;
+
+        self
+    }
+
+    /// Set how the terms of the CME sum are added, the Euler algorithm and the hybrid method add them in order.
+    pub fn summation(mut self, summation: Summation) -> Self {
+        self.summation = summation;
+
+        self
+    }
+
+    /// Validate the configuration.
+    ///
+    /// Returns [`Error::TooManyEvaluations`] when the CME or hybrid method gets a fixed order that's too high.
+    pub fn build(self) -> Result<Inversion, Error> {
+        if let (Method::Cme | Method::Hybrid, Order::Fixed(order)) = (self.method, self.order) {
+            CmeInverter::new(order)?;
+        }
+
+        Ok(Inversion {
+            method: self.method,
+            order: self.order,
+            sigma: self.sigma,
+            summation: self.summation,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder() {
+        let inversion = Inversion::builder().build().unwrap();
+        assert_eq!(inversion.method(), Method::Cme);
+        assert_eq!(inversion.order(), Order::Fixed(50));
+        let sine = |s: Complex<f64>| 1.0 / (s * s + 1.0);
+        assert_eq!(
+            inversion.invert(sine, 1.0).unwrap(),
+            crate::laplace_inversion(sine, 1.0, 50)
+        );
+
+        // Every method inverts a fast oscillation with enough effort
+        let fast = |s: Complex<f64>| 10.0 / (s * s + 100.0);
+        for (method, order) in [
+            (Method::Cme, 500),
+            (Method::Euler, 60),
+            (Method::Hybrid, 50),
+        ] {
+            let inversion = Inversion::builder()
+                .method(method)
+                .order(order)
+                .summation(Summation::Kahan)
+                .build()
+                .unwrap();
+            let result = inversion.invert(fast, 1.5).unwrap();
+            assert!(
+                approx::relative_eq!(result, 15.0_f64.sin(), epsilon = 1e-3),
+                "{method:?} result {result} differs"
+            );
+        }
+
+        // Growing inverse needs the shift
+        let growing = |s: Complex<f64>| 1.0 / (s - 2.0);
+        let unshifted = Inversion::builder().order(100).build().unwrap();
+        let shifted = Inversion::builder().order(100).sigma(2.5).build().unwrap();
+        let exact = 20.0_f64.exp();
+        assert!((unshifted.invert(growing, 10.0).unwrap() / exact - 1.0).abs() > 0.1);
+        assert!(approx::relative_eq!(
+            shifted.invert(growing, 10.0).unwrap(),
+            exact,
+            max_relative = 1e-3
+        ));
+
+        // Compensated summation only changes the rounding of the sum
+        let exponential = |s: Complex<f64>| 1.0 / (s + 1.0);
+        let naive = Inversion::builder().order(500).build().unwrap();
+        let kahan = Inversion::builder()
+            .order(500)
+            .summation(Summation::Kahan)
+            .build()
+            .unwrap();
+        let result = kahan.invert(exponential, 1.0).unwrap();
+        assert_ne!(result, naive.invert(exponential, 1.0).unwrap());
+        assert!(approx::relative_eq!(
+            result,
+            naive.invert(exponential, 1.0).unwrap(),
+            epsilon = 1e-10
+        ));
+
+        assert!(matches!(
+            Inversion::builder().order(100_000).build(),
+            Err(Error::TooManyEvaluations { .. })
+        ));
+        assert!(Inversion::builder()
+            .method(Method::Euler)
+            .order(100_000)
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn orders() {
+        let sine = |s: Complex<f64>| 1.0 / (s * s + 1.0);
+        let invert = |order: Order| {
+            let mut evaluations = 0;
+            let result = Inversion::builder()
+                .order(order)
+                .build()
+                .unwrap()
+                .invert(
+                    |s| {
+                        evaluations += 1;
+                        sine(s)
+                    },
+                    1.0,
+                )
+                .unwrap();
+            (result, evaluations)
+        };
+
+        let (result, _) = invert(Order::Auto);
+        assert!(approx::relative_eq!(result, 1.0_f64.sin(), epsilon = 1e-5));
+
+        // A loose tolerance stops early
+        let (result, evaluations) = invert(Order::Tolerance(1e-3));
+        assert!(approx::relative_eq!(result, 1.0_f64.sin(), epsilon = 1e-3));
+        assert!(evaluations < 100);
+
+        assert_eq!(invert(Order::Fixed(80)), invert(80.into()));
+        assert!(invert(Order::Fixed(80)).1 <= 80);
+    }
+}
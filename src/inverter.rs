@@ -0,0 +1,1413 @@
+//! Configurable Laplace inversion using the CME method.
+
+use std::f64::consts::PI;
+
+use nalgebra::{Complex, DMatrix, DVector};
+
+use crate::{coefficients, double_double::DoubleDouble, special, sweep::Sweep, Diagnostics, Error};
+
+/// Number of terms of the small-time expansion, see [`CmeInverter::with_small_time_threshold`].
+const SMALL_TIME_TERMS: usize = 4;
+
+/// Ratio between the successive points of the large-time asymptote, see [`CmeInverter::with_large_time_threshold`].
+const LARGE_TIME_RATIO: f64 = 0.25;
+
+/// Shape of the gamma window of the smoothing, see [`CmeInverter::with_smoothing`].
+const SMOOTHING_SHAPE: i32 = 16;
+
+/// How to handle an inversion requested at a time that's zero or negative.
+///
+/// The CME method divides by the time, so it's undefined at zero and meaningless for negative times.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TimePolicy {
+    /// Return [`Error::NonPositiveTime`].
+    #[default]
+    Error,
+    /// Return [`f64::NAN`].
+    Nan,
+    /// Approximate the limit $t \to 0^+$ with the initial value theorem $f(0^+) = \lim_{s \to \infty} s F(s)$.
+    ///
+    /// Negative times return zero since the inverse of a Laplace transform is causal.
+    InitialValue,
+}
+
+/// Damping of the high-frequency nodes of the CME sum.
+///
+/// The weight of the node $\beta_k = \mu_1 + i k \omega$ is multiplied by $\sigma(\frac{k}{n + 1})$ and the weights are rescaled so a constant is still inverted exactly.
+/// This smooths the kernel of the method, so the result is less sensitive to errors in the transform at large $|s|$ and to oscillating components that the order can't resolve.
+///
+/// The kernel of the CME method is already a nonnegative density, so an exact jump of the inverse doesn't cause Gibbs oscillations without a filter.
+/// Filtering breaks that, and the concentration of the kernel relies on the high-frequency nodes, so every filter costs accuracy where the inverse is smooth.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SpectralFilter {
+    /// Use the weights unchanged.
+    #[default]
+    None,
+    /// Lanczos sigma factors $\sigma(x) = \frac{\sin \pi x}{\pi x}$, the mildest filter.
+    Lanczos,
+    /// Exponential filter $\sigma(x) = e^{-\alpha x^{2p}}$ with strength $\alpha$ and order $p$.
+    ///
+    /// A strength of about 36 damps the highest node to machine precision, which is only sensible at high orders.
+    Exponential {
+        /// Strength $\alpha$, the damping of the highest node is $e^{-\alpha}$.
+        strength: f64,
+        /// Order $p$, higher orders leave more of the low-frequency nodes untouched.
+        order: u32,
+    },
+}
+
+impl SpectralFilter {
+    /// Factor $\sigma(\frac{k}{n + 1})$ of the weight of node `k` for the distribution of order `n`.
+    pub fn factor(&self, k: usize, n: usize) -> f64 {
+        let x = k as f64 / (n + 1) as f64;
+        match *self {
+            Self::None => 1.0,
+            Self::Lanczos if k == 0 => 1.0,
+            Self::Lanczos => (PI * x).sin() / (PI * x),
+            Self::Exponential { strength, order } => (-strength * x.powi(2 * order as i32)).exp(),
+        }
+    }
+}
+
+/// How the weighted terms of the CME sum are added.
+///
+/// The terms of high orders are large and alternate, so most of the digits cancel in the sum.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Summation {
+    /// Add the terms in order.
+    #[default]
+    Naive,
+    /// Kahan's compensated summation, which carries the rounding error of each addition to the next one.
+    Kahan,
+    /// Pairwise summation, which adds the sums of both halves of the terms recursively.
+    ///
+    /// The tree of additions only depends on the number of terms, so `par_sum` with the `parallel` feature gives bit-identical results for any number of threads.
+    Pairwise,
+    /// Double-double arithmetic with about 32 significant digits, for the products of the weights and the values of the transform as well as their sum.
+    ///
+    /// Recovers the digits that cancel in the sums of high orders for a few times the cost of adding floats, the rounding of the weights and of the values of the transform remains.
+    DoubleDouble,
+}
+
+impl Summation {
+    /// Add the terms.
+    pub fn sum(&self, terms: impl IntoIterator<Item = Complex<f64>>) -> Complex<f64> {
+        match self {
+            Self::Naive => terms.into_iter().sum(),
+            Self::Kahan => {
+                let (mut sum, mut compensation) = (Complex::from(0.0), Complex::from(0.0));
+                for term in terms {
+                    let corrected = term - compensation;
+                    let next = sum + corrected;
+                    compensation = (next - sum) - corrected;
+                    sum = next;
+                }
+
+                sum
+            }
+            Self::Pairwise => pairwise_sum(&terms.into_iter().collect::<Vec<_>>()),
+            Self::DoubleDouble => {
+                let (mut re, mut im) = (DoubleDouble::default(), DoubleDouble::default());
+                for term in terms {
+                    re = re + DoubleDouble::from(term.re);
+                    im = im + DoubleDouble::from(term.im);
+                }
+
+                Complex::new(re.to_f64(), im.to_f64())
+            }
+        }
+    }
+
+    /// Add the products of the weights and the values, without rounding the products for [`Summation::DoubleDouble`].
+    pub(crate) fn weighted(
+        &self,
+        terms: impl IntoIterator<Item = (Complex<f64>, Complex<f64>)>,
+    ) -> Complex<f64> {
+        if *self != Self::DoubleDouble {
+            return self.sum(terms.into_iter().map(|(weight, value)| weight * value));
+        }
+
+        let (mut re, mut im) = (DoubleDouble::default(), DoubleDouble::default());
+        for (weight, value) in terms {
+            re = re + DoubleDouble::product(weight.re, value.re)
+                - DoubleDouble::product(weight.im, value.im);
+            im = im
+                + DoubleDouble::product(weight.re, value.im)
+                + DoubleDouble::product(weight.im, value.re);
+        }
+
+        Complex::new(re.to_f64(), im.to_f64())
+    }
+
+    /// Add the terms, the halves of the pairwise summation on multiple threads.
+    ///
+    /// The other summations add the terms in order on the current thread, all summations give the same result as [`Summation::sum`].
+    #[cfg(feature = "parallel")]
+    pub fn par_sum(&self, terms: &[Complex<f64>]) -> Complex<f64> {
+        match self {
+            Self::Pairwise => par_pairwise_sum(terms),
+            _ => self.sum(terms.iter().copied()),
+        }
+    }
+}
+
+/// Largest number of terms of the pairwise summation that are added in order.
+const PAIRWISE_BLOCK: usize = 8;
+
+/// Add the terms in order when there are few, otherwise add the pairwise sums of both halves.
+fn pairwise_sum(terms: &[Complex<f64>]) -> Complex<f64> {
+    if terms.len() <= PAIRWISE_BLOCK {
+        return terms.iter().sum();
+    }
+
+    let (left, right) = terms.split_at(terms.len() / 2);
+    pairwise_sum(left) + pairwise_sum(right)
+}
+
+/// Pairwise summation with the same tree as [`pairwise_sum`], adding both halves on multiple threads.
+#[cfg(feature = "parallel")]
+fn par_pairwise_sum(terms: &[Complex<f64>]) -> Complex<f64> {
+    if terms.len() <= PAIRWISE_BLOCK {
+        return terms.iter().sum();
+    }
+
+    let (left, right) = terms.split_at(terms.len() / 2);
+    let (left, right) = rayon::join(|| par_pairwise_sum(left), || par_pairwise_sum(right));
+    left + right
+}
+
+/// Laplace inverter using the CME method with a validated configuration.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), iltcme::Error> {
+/// use iltcme::{CmeInverter, TimePolicy};
+///
+/// let inverter = CmeInverter::new(50)?.with_time_policy(TimePolicy::InitialValue);
+///
+/// // The cosine starts at one
+/// let result = inverter.invert(|s| s / (s.powi(2) + 1.0), 0.0)?;
+/// approx::assert_relative_eq!(result, 1.0, epsilon = 0.001);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedCmeInverter"))]
+pub struct CmeInverter {
+    /// Maximum number of times the Laplace transform function is evaluated.
+    max_function_evals: usize,
+    /// What to do with times that are zero or negative.
+    time_policy: TimePolicy,
+    /// Positive times below this use the small-time expansion instead of the CME sum.
+    small_time_threshold: f64,
+    /// Times above this use the large-time asymptote instead of the CME sum.
+    large_time_threshold: Option<f64>,
+    /// Damping of the high-frequency nodes.
+    spectral_filter: SpectralFilter,
+    /// Standard deviation of the window the inverse is averaged over.
+    smoothing: f64,
+    /// How the terms of the CME sum are added.
+    summation: Summation,
+}
+
+impl CmeInverter {
+    /// Create an inverter evaluating the Laplace transform function at most `max_function_evals` times.
+    ///
+    /// Maximum number of evaluations is given by [`coefficients::max_evaluations`].
+    pub fn new(max_function_evals: usize) -> Result<Self, Error> {
+        let max = coefficients::max_evaluations();
+        if max_function_evals > max {
+            return Err(Error::TooManyEvaluations {
+                requested: max_function_evals,
+                max,
+            });
+        }
+
+        Ok(Self {
+            max_function_evals,
+            time_policy: TimePolicy::default(),
+            small_time_threshold: 0.0,
+            large_time_threshold: None,
+            spectral_filter: SpectralFilter::default(),
+            smoothing: 0.0,
+            summation: Summation::default(),
+        })
+    }
+
+    /// Set how times that are zero or negative are handled.
+    pub fn with_time_policy(mut self, time_policy: TimePolicy) -> Self {
+        self.time_policy = time_policy;
+
+        self
+    }
+
+    /// Invert positive times below `threshold` with an expansion from the behavior of the transform for large $s$, instead of the CME sum.
+    ///
+    /// When $s F(s) = \sum_k a_k s^{-k}$ for large $s$ the inverse starts as $f(t) = \sum_k a_k \frac{t^k}{k!}$, which generalizes the initial value theorem.
+    /// The first four coefficients are fitted to $s F(s)$ at real $s$ between $1 / t$ and $4 / t$, so only four evaluations are needed and the result has no Gibbs oscillations from the jump of the inverse at zero.
+    /// This only applies to inverses that are smooth at $0^+$, a singularity like $t^{-1/2}$ has no such expansion.
+    ///
+    /// The default threshold of zero never uses the expansion.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), iltcme::Error> {
+    /// use iltcme::CmeInverter;
+    ///
+    /// let inverter = CmeInverter::new(50)?.with_small_time_threshold(1e-6);
+    /// let result = inverter.invert(|s| 1.0 / (s + 1.0), 1e-8)?;
+    /// approx::assert_relative_eq!(result, (-1e-8_f64).exp(), epsilon = 1e-14);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_small_time_threshold(mut self, threshold: f64) -> Self {
+        self.small_time_threshold = threshold;
+
+        self
+    }
+
+    /// Invert times above `threshold` with the final value and its leading correction, instead of the CME sum.
+    ///
+    /// Far beyond the time constants of the transform only its behavior for small $s$ matters.
+    /// When $s F(s) = c_0 + b s^\alpha + \ldots$ there, the inverse approaches $f(t) = c_0 + \frac{b t^{-\alpha}}{\Gamma(1 - \alpha)}$, the correction vanishes for a transform that's smooth at zero.
+    /// The final value $c_0$, $b$ and $\alpha$ are fitted to $s F(s)$ at the real points $\frac{1}{t}$, $\frac{1}{4t}$ and $\frac{1}{16t}$, so the result costs three evaluations instead of a full CME sum.
+    ///
+    /// The threshold must be well beyond the slowest time constant, including the periods of undamped oscillations that have no final value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), iltcme::Error> {
+    /// use iltcme::CmeInverter;
+    ///
+    /// // Step response of a first-order system settles at one
+    /// let inverter = CmeInverter::new(500)?.with_large_time_threshold(100.0);
+    /// let result = inverter.invert(|s| 1.0 / (s * (s + 1.0)), 1e3)?;
+    /// approx::assert_relative_eq!(result, 1.0, epsilon = 1e-6);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_large_time_threshold(mut self, threshold: f64) -> Self {
+        self.large_time_threshold = Some(threshold);
+
+        self
+    }
+
+    /// Set the damping of the high-frequency nodes of the CME sum, see [`SpectralFilter`].
+    ///
+    /// The correction by the filter is reported in [`Diagnostics::filter_correction`].
+    pub fn with_spectral_filter(mut self, spectral_filter: SpectralFilter) -> Self {
+        self.spectral_filter = spectral_filter;
+
+        self
+    }
+
+    /// Smooth the inverse by averaging it over a window with standard deviation `width` around each time.
+    ///
+    /// The window is a gamma density with shape 16 and scale $\theta = \frac{w}{4}$, shifted back by its mean so the average isn't delayed.
+    /// The CME sum inverts $\frac{F(s)}{(1 + \theta s)^{16}}$ at $t + 16 \theta$ instead of $F(s)$ at $t$, the factor damps the high-frequency nodes like a low-pass filter.
+    /// Jumps become smooth ramps over a few widths and oscillations faster than $\frac{1}{w}$ are removed, which is useful when the result feeds an optimizer or a derivative.
+    /// Constants and linear functions are preserved once the window is past zero, which takes a few widths because of its long right tail.
+    ///
+    /// Unlike a Gaussian window, whose factor $e^{\frac{1}{2} w^2 s^2}$ spreads the inverse over negative times where the CME kernel grows exponentially, the window is zero before the start of the inverse.
+    /// The default width of zero doesn't smooth, the expansions for small and large times never do.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), iltcme::Error> {
+    /// use iltcme::CmeInverter;
+    ///
+    /// // Fast oscillation is removed, the slow one is barely damped
+    /// let inverter = CmeInverter::new(100)?.with_smoothing(0.1);
+    /// let result = inverter.invert(|s| 1.0 / (s * s + 1.0) + 1.0 / (s * s + 1e4), 2.0)?;
+    /// approx::assert_relative_eq!(result, 2.0_f64.sin(), epsilon = 0.01);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_smoothing(mut self, width: f64) -> Self {
+        assert!(width >= 0.0, "Smoothing width must not be negative");
+        self.smoothing = width;
+
+        self
+    }
+
+    /// Set how the terms of the CME sum are added, see [`Summation`].
+    pub fn with_summation(mut self, summation: Summation) -> Self {
+        self.summation = summation;
+
+        self
+    }
+
+    /// Maximum number of times the Laplace transform function is evaluated.
+    pub fn max_function_evals(&self) -> usize {
+        self.max_function_evals
+    }
+
+    /// How times that are zero or negative are handled.
+    pub fn time_policy(&self) -> TimePolicy {
+        self.time_policy
+    }
+
+    /// Positive times below this threshold use the small-time expansion.
+    pub fn small_time_threshold(&self) -> f64 {
+        self.small_time_threshold
+    }
+
+    /// Times above this threshold use the large-time asymptote, if any.
+    pub fn large_time_threshold(&self) -> Option<f64> {
+        self.large_time_threshold
+    }
+
+    /// Damping of the high-frequency nodes of the CME sum.
+    pub fn spectral_filter(&self) -> SpectralFilter {
+        self.spectral_filter
+    }
+
+    /// Standard deviation of the window the inverse is averaged over.
+    pub fn smoothing(&self) -> f64 {
+        self.smoothing
+    }
+
+    /// How the terms of the CME sum are added.
+    pub fn summation(&self) -> Summation {
+        self.summation
+    }
+
+    /// Encode the configuration in a compact binary format, to cache it on disk or send it to other processes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use iltcme::{CmeInverter, TimePolicy};
+    ///
+    /// let inverter = CmeInverter::new(100)?.with_time_policy(TimePolicy::Nan);
+    /// let bytes = inverter.to_bytes();
+    /// assert_eq!(CmeInverter::from_bytes(&bytes)?, inverter);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        postcard::to_stdvec(self).expect("Inverter can always be encoded")
+    }
+
+    /// Decode a configuration encoded with [`CmeInverter::to_bytes`].
+    ///
+    /// The maximum number of evaluations is validated like in [`CmeInverter::new`].
+    #[cfg(feature = "serde")]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, postcard::Error> {
+        postcard::from_bytes(bytes)
+    }
+
+    /// Calculate the Laplace inversion for a function at every time in an Arrow array.
+    ///
+    /// Null times and times where the inversion fails, see [`CmeInverter::invert`], are null in the result.
+    /// This is a plain function on Arrow arrays for Rust pipelines, it isn't registered as an Arrow compute kernel or a Polars expression plugin.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), iltcme::Error> {
+    /// use arrow_array::{Array, Float64Array};
+    /// use iltcme::CmeInverter;
+    ///
+    /// let times = Float64Array::from(vec![Some(1.0), None, Some(0.0)]);
+    /// let result = CmeInverter::new(50)?.invert_array(|s| 1.0 / (s.powi(2) + 1.0), &times);
+    /// approx::assert_relative_eq!(result.value(0), 1.0_f64.sin(), epsilon = 0.001);
+    /// assert!(result.is_null(1) && result.is_null(2));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "arrow")]
+    pub fn invert_array(
+        &self,
+        mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+        times: &arrow_array::Float64Array,
+    ) -> arrow_array::Float64Array {
+        times
+            .iter()
+            .map(|t| t.and_then(|t| self.invert(&mut laplace_func, t).ok()))
+            .collect()
+    }
+
+    /// Calculate the Laplace inversion for a function at time `t`.
+    pub fn invert(
+        &self,
+        laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+        t: f64,
+    ) -> Result<f64, Error> {
+        self.invert_with_diagnostics(laplace_func, t)
+            .map(|(result, _)| result)
+    }
+
+    /// Calculate the Laplace inversion for a function at the times of each window, with a separate order per window.
+    ///
+    /// Small and large times of the same transform often need very different orders.
+    /// For every window the order is doubled, starting at 10 evaluations, until the results at the first, middle and last time of the window differ less than `tolerance` from the results of the next order.
+    /// The maximum number of evaluations of the inverter bounds the order, it's used when the results never agree.
+    ///
+    /// Returns the results of all windows stitched together and the maximum number of evaluations chosen for each window.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), iltcme::Error> {
+    /// use iltcme::CmeInverter;
+    ///
+    /// let inverter = CmeInverter::new(500)?;
+    /// let windows: [&[f64]; 2] = [&[0.1, 0.2, 0.5], &[10.0, 15.0, 20.0]];
+    /// let (result, orders) = inverter.invert_windowed(|s| 1.0 / (s * s + 1.0), &windows, 1e-4)?;
+    /// assert_eq!(result.len(), 6);
+    /// approx::assert_relative_eq!(result[4], 15.0_f64.sin(), epsilon = 1e-3);
+    /// // Later times of the sine need more evaluations
+    /// assert!(orders[0] < orders[1]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn invert_windowed(
+        &self,
+        laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+        windows: &[&[f64]],
+        tolerance: f64,
+    ) -> Result<(Vec<f64>, Vec<usize>), Error> {
+        Sweep::new(*self).invert_windowed(laplace_func, windows, tolerance)
+    }
+
+    /// Calculate the Laplace inversion for a function on a grid over `[start, end]` that's refined until the estimated error is below `tolerance` everywhere.
+    ///
+    /// The order is chosen such that the results on an initial uniform grid of 17 times agree with the next order, bounded by the maximum number of evaluations of the inverter.
+    /// Afterwards every interval is halved as long as the result in its middle differs more than `tolerance` from the linear interpolation, at most 12 times.
+    /// Linear interpolation of the returned times and results is then accurate everywhere in the interval, which is what plots need.
+    /// A [`Sweep`] computes the same grid and can be cancelled.
+    ///
+    /// Returns the sorted times and the results at those times.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), iltcme::Error> {
+    /// use iltcme::CmeInverter;
+    ///
+    /// let (times, result) =
+    ///     CmeInverter::new(500)?.invert_uniform(|s| 1.0 / (s * s + 1.0), [0.1, 10.0], 1e-3)?;
+    /// assert_eq!((times[0], times[times.len() - 1]), (0.1, 10.0));
+    /// approx::assert_relative_eq!(result[1], times[1].sin(), epsilon = 1e-3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn invert_uniform(
+        &self,
+        laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+        range: [f64; 2],
+        tolerance: f64,
+    ) -> Result<(Vec<f64>, Vec<f64>), Error> {
+        Sweep::new(*self).invert_uniform(laplace_func, range, tolerance)
+    }
+
+    /// Locate jumps of the inverse on a sorted grid of times with a heuristic.
+    ///
+    /// Away from jumps the results of the full order and of half the order agree, around a jump the full order rises or falls earlier and later on the respective sides.
+    /// A jump is reported where their difference changes sign between consecutive times while it exceeds `tolerance` at one of them, at the zero of the linear interpolation.
+    /// The location is accurate to about the spacing of the grid.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), iltcme::Error> {
+    /// use iltcme::CmeInverter;
+    ///
+    /// // Unit step at one
+    /// let times = (1..40).map(|i| f64::from(i) * 0.05).collect::<Vec<_>>();
+    /// let jumps = CmeInverter::new(100)?.detect_jumps(|s| (-s).exp() / s, &times, 1e-4)?;
+    /// assert_eq!(jumps.len(), 1);
+    /// approx::assert_relative_eq!(jumps[0], 1.0, epsilon = 0.05);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn detect_jumps(
+        &self,
+        mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+        times: &[f64],
+        tolerance: f64,
+    ) -> Result<Vec<f64>, Error> {
+        let half = self.with_max_function_evals(self.max_function_evals / 2);
+        let differences = times
+            .iter()
+            .map(|&t| Ok(self.invert(&mut laplace_func, t)? - half.invert(&mut laplace_func, t)?))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(times
+            .windows(2)
+            .zip(differences.windows(2))
+            .filter(|(_, d)| {
+                d[0].signum() != d[1].signum() && d[0].abs().max(d[1].abs()) > tolerance
+            })
+            .map(|(t, d)| t[0] + (t[1] - t[0]) * d[0] / (d[0] - d[1]))
+            .collect())
+    }
+
+    /// Calculate the Laplace inversion for a function at time `t`, passing all nodes to the function at once.
+    ///
+    /// The function must return one value for each node in the same order, this allows vectorized evaluation of the transform, for example with BLAS, on a GPU or by an external service.
+    /// The result is bit-identical to [`CmeInverter::invert`].
+    /// When the time policy handles a time that's zero or negative, or an expansion for small or large times applies, the function is called with a single node at a time.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), iltcme::Error> {
+    /// use iltcme::CmeInverter;
+    ///
+    /// let inverter = CmeInverter::new(100)?;
+    /// let result = inverter.invert_batch(
+    ///     |nodes| nodes.iter().map(|s| 1.0 / (s * s + 1.0)).collect(),
+    ///     1.0,
+    /// )?;
+    /// assert_eq!(result, inverter.invert(|s| 1.0 / (s * s + 1.0), 1.0)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn invert_batch(
+        &self,
+        mut laplace_func: impl FnMut(&[Complex<f64>]) -> Vec<Complex<f64>>,
+        t: f64,
+    ) -> Result<f64, Error> {
+        let mut evaluate = |nodes: &[Complex<f64>]| {
+            let values = laplace_func(nodes);
+            assert_eq!(
+                values.len(),
+                nodes.len(),
+                "Laplace transform function must return a value for each node"
+            );
+
+            values
+        };
+
+        // The time policy and the expansions evaluate at other points
+        if !self.uses_cme(t) {
+            return self.invert(|s| evaluate(&[s])[0], t);
+        }
+
+        let nodes = coefficients::order(self.max_function_evals)
+            .nodes()
+            .map(|(_, beta)| beta / self.smoothed_time(t).1)
+            .collect::<Vec<_>>();
+        let mut values = evaluate(&nodes).into_iter();
+
+        self.invert(|_| values.next().expect("Evaluated at every node"), t)
+    }
+
+    /// Calculate the Laplace inversion for a function at time `t`, evaluating the function at the nodes on multiple threads.
+    ///
+    /// Meant for expensive transforms, for example when each evaluation is a PDE solve.
+    /// The weighted sum is reduced in the same order as [`CmeInverter::invert`] once all evaluations finished, so the result is bit-identical to it regardless of the number of threads.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), iltcme::Error> {
+    /// use iltcme::CmeInverter;
+    ///
+    /// let inverter = CmeInverter::new(100)?;
+    /// let transform = |s| 1.0 / (s * s + 1.0);
+    /// assert_eq!(inverter.par_invert(transform, 1.0)?, inverter.invert(transform, 1.0)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "parallel")]
+    pub fn par_invert(
+        &self,
+        laplace_func: impl Fn(Complex<f64>) -> Complex<f64> + Sync,
+        t: f64,
+    ) -> Result<f64, Error> {
+        use rayon::prelude::*;
+
+        // The time policy and the expansions evaluate at other points
+        if !self.uses_cme(t) {
+            return self.invert(laplace_func, t);
+        }
+
+        let cme_time = self.smoothed_time(t).1;
+        let values = coefficients::order(self.max_function_evals)
+            .nodes()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|(_, beta)| laplace_func(beta / cme_time))
+            .collect::<Vec<_>>();
+        let mut values = values.into_iter();
+
+        self.invert(|_| values.next().expect("Evaluated at every node"), t)
+    }
+
+    /// Calculate the Laplace inversion for a function at time `t` and report how it was computed.
+    ///
+    /// When the time policy handled a time that's zero or negative, or an expansion for small or large times was used, the diagnostics only report the function evaluations.
+    ///
+    /// Returns [`Error::NonFiniteTransform`] for the first node where the Laplace transform function returned NaN or infinity.
+    pub fn invert_with_diagnostics(
+        &self,
+        mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+        t: f64,
+    ) -> Result<(f64, Diagnostics), Error> {
+        if self.uses_cme(t) {
+            let (scale, t) = self.smoothed_time(t);
+            // Remember the first node that isn't finite
+            let mut node = 0;
+            let mut non_finite = None;
+            let result = crate::cme_sum(
+                |s| {
+                    let value = laplace_func(s);
+                    if non_finite.is_none() && !(value.re.is_finite() && value.im.is_finite()) {
+                        non_finite = Some(Error::NonFiniteTransform {
+                            node,
+                            beta: s * t,
+                            s,
+                            value,
+                        });
+                    }
+                    node += 1;
+
+                    if scale > 0.0 {
+                        value / (1.0 + scale * s).powi(SMOOTHING_SHAPE)
+                    } else {
+                        value
+                    }
+                },
+                t,
+                self.max_function_evals,
+                self.spectral_filter,
+                self.summation,
+            );
+
+            return match non_finite {
+                Some(err) => Err(err),
+                None => Ok(result),
+            };
+        }
+
+        let mut diagnostics = Diagnostics {
+            order_used: self.max_function_evals,
+            n: 0,
+            cv2: f64::NAN,
+            imag_residual: 0.0,
+            evals: 0,
+            filter_correction: 0.0,
+        };
+        if t > 0.0 && t >= self.small_time_threshold {
+            let result = large_time_asymptote(&mut laplace_func, t, &mut diagnostics.evals)?;
+            return Ok((result, diagnostics));
+        }
+        if t > 0.0 {
+            let result = small_time_expansion(&mut laplace_func, t, &mut diagnostics.evals)?;
+            return Ok((result, diagnostics));
+        }
+
+        let result = match self.time_policy {
+            TimePolicy::Error => return Err(Error::NonPositiveTime(t)),
+            // Also catches NaN times
+            TimePolicy::Nan => f64::NAN,
+            TimePolicy::InitialValue if t == 0.0 => {
+                initial_value(&mut laplace_func, &mut diagnostics.evals)
+            }
+            TimePolicy::InitialValue if t < 0.0 => 0.0,
+            TimePolicy::InitialValue => f64::NAN,
+        };
+
+        Ok((result, diagnostics))
+    }
+}
+
+impl CmeInverter {
+    /// Whether the time is inverted with the CME sum, rather than by the time policy or an expansion.
+    fn uses_cme(&self, t: f64) -> bool {
+        t > 0.0
+            && t >= self.small_time_threshold
+            && self
+                .large_time_threshold
+                .is_none_or(|threshold| t <= threshold)
+    }
+
+    /// Scale of the smoothing window and the time where the CME sum is evaluated instead of `t`.
+    fn smoothed_time(&self, t: f64) -> (f64, f64) {
+        if self.smoothing > 0.0 {
+            let shape = f64::from(SMOOTHING_SHAPE);
+            let scale = self.smoothing / shape.sqrt();
+            (scale, t + shape * scale)
+        } else {
+            (0.0, t)
+        }
+    }
+
+    /// Copy with another maximum number of evaluations that's known to be valid.
+    pub(crate) fn with_max_function_evals(&self, max_function_evals: usize) -> Self {
+        Self {
+            max_function_evals,
+            ..*self
+        }
+    }
+}
+
+/// Deserialized fields of [`CmeInverter`] before the maximum number of evaluations is validated.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedCmeInverter {
+    max_function_evals: usize,
+    #[serde(default)]
+    time_policy: TimePolicy,
+    #[serde(default)]
+    small_time_threshold: f64,
+    #[serde(default)]
+    large_time_threshold: Option<f64>,
+    #[serde(default)]
+    spectral_filter: SpectralFilter,
+    #[serde(default)]
+    smoothing: f64,
+    #[serde(default)]
+    summation: Summation,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedCmeInverter> for CmeInverter {
+    type Error = Error;
+
+    fn try_from(unchecked: UncheckedCmeInverter) -> Result<Self, Self::Error> {
+        // Checked like `with_smoothing`, which panics instead
+        if unchecked.smoothing.is_nan() || unchecked.smoothing < 0.0 {
+            return Err(Error::InvalidSmoothing(unchecked.smoothing));
+        }
+
+        Ok(Self {
+            large_time_threshold: unchecked.large_time_threshold,
+            smoothing: unchecked.smoothing,
+            ..Self::new(unchecked.max_function_evals)?
+                .with_time_policy(unchecked.time_policy)
+                .with_small_time_threshold(unchecked.small_time_threshold)
+                .with_spectral_filter(unchecked.spectral_filter)
+                .with_summation(unchecked.summation)
+        })
+    }
+}
+
+/// Approximate $\lim_{s \to \infty} s F(s)$ by evaluating at increasingly large real values until it converges.
+fn initial_value(
+    mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+    evals: &mut usize,
+) -> f64 {
+    let mut previous = f64::NAN;
+    for exponent in 4..=16 {
+        let s = 10.0_f64.powi(exponent);
+        let value = (laplace_func(s.into()) * s).re;
+        *evals += 1;
+
+        if (value - previous).abs() <= 1e-10 * value.abs().max(1.0) {
+            return value;
+        }
+        previous = value;
+    }
+
+    previous
+}
+
+/// Sum the expansion $f(t) = \sum_k a_k \frac{t^k}{k!}$ with the coefficients of $s F(s) = \sum_k a_k s^{-k}$ fitted at real $s$ of order $1 / t$.
+fn small_time_expansion(
+    mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+    t: f64,
+    evals: &mut usize,
+) -> Result<f64, Error> {
+    // Interpolating in `x = 1 / (s t)` gives the scaled coefficients `a_k t^k` directly
+    let points = (1..=SMALL_TIME_TERMS)
+        .map(|j| j as f64 / SMALL_TIME_TERMS as f64)
+        .collect::<Vec<_>>();
+    let mut values = DVector::zeros(SMALL_TIME_TERMS);
+    for (node, x) in points.iter().enumerate() {
+        let s = Complex::from(1.0 / (x * t));
+        let value = laplace_func(s);
+        *evals += 1;
+        if !(value.re.is_finite() && value.im.is_finite()) {
+            return Err(Error::NonFiniteTransform {
+                node,
+                beta: s * t,
+                s,
+                value,
+            });
+        }
+        values[node] = (value * s).re;
+    }
+
+    let vandermonde = DMatrix::from_fn(SMALL_TIME_TERMS, SMALL_TIME_TERMS, |j, k| {
+        points[j].powi(k as i32)
+    });
+    let coefficients = vandermonde
+        .lu()
+        .solve(&values)
+        .expect("Vandermonde matrix of distinct points is invertible");
+
+    let mut factorial = 1.0;
+    Ok(coefficients
+        .iter()
+        .enumerate()
+        .map(|(k, coefficient)| {
+            factorial *= k.max(1) as f64;
+            coefficient / factorial
+        })
+        .sum())
+}
+
+/// Final value $c_0$ with the correction $\frac{b t^{-\alpha}}{\Gamma(1 - \alpha)}$ for $s F(s) = c_0 + b s^\alpha$ fitted at three real points from $1 / t$ down.
+fn large_time_asymptote(
+    mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+    t: f64,
+    evals: &mut usize,
+) -> Result<f64, Error> {
+    let mut values = [0.0; 3];
+    let mut s = Complex::from(1.0 / t);
+    for (node, value) in values.iter_mut().enumerate() {
+        let transform = laplace_func(s);
+        *evals += 1;
+        if !(transform.re.is_finite() && transform.im.is_finite()) {
+            return Err(Error::NonFiniteTransform {
+                node,
+                beta: s * t,
+                s,
+                value: transform,
+            });
+        }
+        *value = (transform * s).re;
+        s *= LARGE_TIME_RATIO;
+    }
+
+    // Successive differences shrink by `ratio^alpha`, the rest of the geometric series is the distance to the final value
+    let (first, second) = (values[0] - values[1], values[1] - values[2]);
+    let shrink = second / first;
+    if first == 0.0 || !(0.0..1.0).contains(&shrink) {
+        return Ok(values[2]);
+    }
+    let final_value = values[2] - second * shrink / (1.0 - shrink);
+
+    // Smooth transforms have `alpha` of one or more, the correction is then concentrated at zero
+    let alpha = shrink.ln() / LARGE_TIME_RATIO.ln();
+    let correction = if alpha < 1.0 {
+        first / (1.0 - shrink) / special::gamma(1.0 - alpha)
+    } else {
+        0.0
+    };
+
+    Ok(final_value + correction)
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::ComplexField;
+
+    use super::*;
+
+    #[test]
+    fn too_many_evaluations() {
+        assert_eq!(
+            CmeInverter::new(coefficients::max_evaluations() + 1),
+            Err(Error::TooManyEvaluations {
+                requested: coefficients::max_evaluations() + 1,
+                max: coefficients::max_evaluations()
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let inverter = CmeInverter::new(50)
+            .unwrap()
+            .with_time_policy(TimePolicy::InitialValue);
+        let json = serde_json::to_string(&inverter).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"max_function_evals":50,"time_policy":"InitialValue","small_time_threshold":0.0,"large_time_threshold":null,"spectral_filter":"None","smoothing":0.0,"summation":"Naive"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<CmeInverter>(&json).unwrap(),
+            inverter
+        );
+        assert_eq!(
+            serde_json::from_str::<CmeInverter>(r#"{"max_function_evals":50}"#).unwrap(),
+            CmeInverter::new(50).unwrap()
+        );
+        // Validated like the constructor
+        assert!(serde_json::from_str::<CmeInverter>(r#"{"max_function_evals":100000}"#).is_err());
+        assert!(serde_json::from_str::<CmeInverter>(
+            r#"{"max_function_evals":50,"smoothing":-1.0}"#
+        )
+        .is_err());
+        let nan = UncheckedCmeInverter {
+            smoothing: f64::NAN,
+            ..serde_json::from_str(r#"{"max_function_evals":50}"#).unwrap()
+        };
+        assert!(matches!(
+            CmeInverter::try_from(nan),
+            Err(Error::InvalidSmoothing(width)) if width.is_nan()
+        ));
+
+        let bytes = inverter.to_bytes();
+        assert_eq!(CmeInverter::from_bytes(&bytes), Ok(inverter));
+        assert!(CmeInverter::from_bytes(&bytes[..1]).is_err());
+        let too_many = postcard::to_stdvec(&(100_000_usize, TimePolicy::Error)).unwrap();
+        assert!(CmeInverter::from_bytes(&too_many).is_err());
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn invert_array() {
+        use arrow_array::{Array, Float64Array};
+
+        let exponential = |s: Complex<f64>| (1.0 + s).recip();
+        let times =
+            Float64Array::from_iter((0..1000).map(|i| (i % 10 != 0).then_some(i as f64 / 100.0)));
+        let inverter = CmeInverter::new(50).unwrap();
+
+        let result = inverter.invert_array(exponential, &times);
+        assert_eq!(result.len(), times.len());
+        // Every tenth time is null
+        assert_eq!(result.null_count(), 100);
+        for (t, value) in times.iter().zip(&result) {
+            if let Some(t) = t {
+                assert_eq!(value, inverter.invert(exponential, t).ok());
+            }
+        }
+
+        // Times at zero aren't null when the time policy handles them
+        let result = inverter
+            .with_time_policy(TimePolicy::InitialValue)
+            .invert_array(exponential, &Float64Array::from(vec![0.0, 1.0]));
+        assert_eq!(result.null_count(), 0);
+    }
+
+    #[test]
+    fn invert_windowed() {
+        let sine = |s: Complex<f64>| (s.powi(2) + 1.0).recip();
+        let inverter = CmeInverter::new(500).unwrap();
+        let windows: [&[f64]; 3] = [&[0.1, 0.2, 0.5], &[1.0, 2.0, 3.0], &[10.0, 15.0, 20.0]];
+
+        let (results, orders) = inverter.invert_windowed(sine, &windows, 1e-4).unwrap();
+        assert_eq!(results.len(), 9);
+        assert!(orders.windows(2).all(|orders| orders[0] < orders[1]));
+        for ((times, order), results) in windows.iter().zip(&orders).zip(results.chunks(3)) {
+            let window_inverter = CmeInverter::new(*order).unwrap();
+            for (t, result) in times.iter().zip(results) {
+                assert_eq!(*result, window_inverter.invert(sine, *t).unwrap());
+                assert!(approx::relative_eq!(*result, t.sin(), epsilon = 1e-3));
+            }
+        }
+
+        // Bounded by the maximum number of evaluations
+        let (_, orders) = CmeInverter::new(50)
+            .unwrap()
+            .invert_windowed(sine, &windows, 1e-12)
+            .unwrap();
+        assert_eq!(orders, [50; 3]);
+        assert_eq!(
+            inverter.invert_windowed(sine, &[&[1.0, 0.0]], 1e-4),
+            Err(Error::NonPositiveTime(0.0))
+        );
+    }
+
+    #[test]
+    fn invert_uniform() {
+        let inverter = CmeInverter::new(500).unwrap();
+
+        for (transform, inverse, tolerance) in [
+            (
+                (|s| (s.powi(2) + 1.0).recip()) as fn(Complex<f64>) -> Complex<f64>,
+                f64::sin as fn(f64) -> f64,
+                1e-3,
+            ),
+            (|s| (s + 10.0).recip(), |t| (-10.0 * t).exp(), 1e-4),
+        ] {
+            let (times, results) = inverter
+                .invert_uniform(transform, [0.05, 5.0], tolerance)
+                .unwrap();
+            assert_eq!((times[0], times[times.len() - 1]), (0.05, 5.0));
+            assert!(times.windows(2).all(|times| times[0] < times[1]));
+
+            // Linear interpolation is accurate between the times
+            for (times, results) in times.windows(2).zip(results.windows(2)) {
+                for fraction in [0.0, 0.25, 0.5, 0.75] {
+                    let t = times[0] + fraction * (times[1] - times[0]);
+                    let interpolated = results[0] + fraction * (results[1] - results[0]);
+                    assert!((interpolated - inverse(t)).abs() < 4.0 * tolerance);
+                }
+            }
+        }
+
+        // The decay is resolved with more points close to zero
+        let (times, _) = inverter
+            .invert_uniform(|s| (s + 10.0).recip(), [0.05, 5.0], 1e-4)
+            .unwrap();
+        let early = times.iter().filter(|t| **t < 0.5).count();
+        assert!(early > times.len() / 2);
+    }
+
+    #[test]
+    fn invert_batch() {
+        let staircase = |s: Complex<f64>| s.recip() / (s.exp() - 1.0);
+        let inverter = CmeInverter::new(200).unwrap();
+
+        let mut batches = 0;
+        for t in [0.5, 1.5, 2.5, 10.0] {
+            assert_eq!(
+                inverter.invert_batch(
+                    |nodes| {
+                        batches += 1;
+                        assert_eq!(nodes.len(), coefficients::order(200).evaluations());
+                        nodes.iter().copied().map(staircase).collect()
+                    },
+                    t
+                ),
+                inverter.invert(staircase, t)
+            );
+        }
+        assert_eq!(batches, 4);
+
+        // Errors and time policies behave the same
+        assert!(matches!(
+            inverter.invert_batch(|nodes| vec![Complex::new(f64::NAN, 0.0); nodes.len()], 1.0),
+            Err(Error::NonFiniteTransform { node: 0, .. })
+        ));
+        assert_eq!(
+            inverter.invert_batch(|nodes| nodes.to_vec(), 0.0),
+            Err(Error::NonPositiveTime(0.0))
+        );
+        assert!(matches!(
+            inverter
+                .with_time_policy(TimePolicy::InitialValue)
+                .invert_batch(|nodes| nodes.iter().map(|s| (s + 1.0).recip()).collect(), 0.0),
+            Ok(result) if approx::relative_eq!(result, 1.0, epsilon = 1e-6)
+        ));
+    }
+
+    #[test]
+    fn double_double_summation() {
+        // Cancellation that loses everything in floats
+        let terms = [1e17, 1.0, -1e17].map(Complex::from);
+        assert_eq!(Summation::Naive.sum(terms), Complex::from(0.0));
+        assert_eq!(Summation::DoubleDouble.sum(terms), Complex::from(1.0));
+
+        // Products aren't rounded, like a fused multiply-add
+        let terms = [
+            (Complex::new(0.1, 0.0), Complex::new(0.1, 0.0)),
+            (Complex::new(0.0, 0.01), Complex::new(0.0, 1.0)),
+        ];
+        assert_ne!(
+            Summation::Naive.weighted(terms).re,
+            0.1_f64.mul_add(0.1, -0.01)
+        );
+        assert_eq!(
+            Summation::DoubleDouble.weighted(terms),
+            Complex::from(0.1_f64.mul_add(0.1, -0.01))
+        );
+
+        let inverter = CmeInverter::new(500)
+            .unwrap()
+            .with_summation(Summation::DoubleDouble);
+        assert!(approx::relative_eq!(
+            inverter.invert(|s| (s + 1.0).recip(), 1.0).unwrap(),
+            (-1.0_f64).exp(),
+            epsilon = 1e-6
+        ));
+    }
+
+    #[test]
+    fn pairwise_summation() {
+        let terms = coefficients::order(500)
+            .nodes()
+            .map(|(eta, beta)| eta / (beta + 1.0))
+            .collect::<Vec<_>>();
+        let pairwise = Summation::Pairwise.sum(terms.iter().copied());
+        assert!((pairwise - Summation::Naive.sum(terms.iter().copied())).norm() < 1e-8);
+        assert_eq!(pairwise_sum(&terms[..3]), terms[0] + terms[1] + terms[2]);
+
+        let inverter = CmeInverter::new(500)
+            .unwrap()
+            .with_summation(Summation::Pairwise);
+        assert!(approx::relative_eq!(
+            inverter.invert(|s| (s + 1.0).recip(), 1.0).unwrap(),
+            (-1.0_f64).exp(),
+            epsilon = 1e-6
+        ));
+
+        // Same tree for any number of threads
+        #[cfg(feature = "parallel")]
+        for threads in [1, 2, 7] {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            for summation in [Summation::Naive, Summation::Kahan, Summation::Pairwise] {
+                assert_eq!(
+                    pool.install(|| summation.par_sum(&terms)),
+                    summation.sum(terms.iter().copied())
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn par_invert() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let evaluations = AtomicUsize::new(0);
+        let staircase = |s: Complex<f64>| {
+            evaluations.fetch_add(1, Ordering::Relaxed);
+            s.recip() / (s.exp() - 1.0)
+        };
+        let inverter = CmeInverter::new(200).unwrap();
+
+        for t in [0.5, 1.5, 2.5, 10.0] {
+            assert_eq!(
+                inverter.par_invert(staircase, t),
+                inverter.invert(staircase, t)
+            );
+        }
+        assert_eq!(
+            evaluations.load(Ordering::Relaxed),
+            8 * coefficients::order(200).evaluations()
+        );
+
+        // Errors and time policies behave the same
+        assert!(matches!(
+            inverter.par_invert(|s| s.recip(), 1.0),
+            Ok(result) if approx::relative_eq!(result, 1.0, epsilon = 1e-3)
+        ));
+        assert!(matches!(
+            inverter.par_invert(|_| Complex::new(f64::NAN, 0.0), 1.0),
+            Err(Error::NonFiniteTransform { node: 0, .. })
+        ));
+        assert_eq!(
+            inverter.par_invert(staircase, 0.0),
+            Err(Error::NonPositiveTime(0.0))
+        );
+    }
+
+    #[test]
+    fn time_policies() {
+        let exponential = |s: Complex<f64>| (1.0 + s).recip();
+        let inverter = CmeInverter::new(50).unwrap();
+
+        assert_eq!(
+            inverter.invert(exponential, 0.0),
+            Err(Error::NonPositiveTime(0.0))
+        );
+        assert_eq!(
+            inverter.invert(exponential, -1.0),
+            Err(Error::NonPositiveTime(-1.0))
+        );
+        assert!(inverter
+            .with_time_policy(TimePolicy::Nan)
+            .invert(exponential, 0.0)
+            .unwrap()
+            .is_nan());
+
+        let inverter = inverter.with_time_policy(TimePolicy::InitialValue);
+        assert!(approx::relative_eq!(
+            inverter.invert(exponential, 0.0).unwrap(),
+            1.0,
+            epsilon = 1e-8
+        ));
+        assert_eq!(inverter.invert(exponential, -1.0), Ok(0.0));
+        assert!(approx::relative_eq!(
+            inverter.invert(exponential, 1.0).unwrap(),
+            (-1.0_f64).exp(),
+            epsilon = 0.01
+        ));
+    }
+
+    #[test]
+    fn small_time_expansion() {
+        // Damped cosine jumps at zero, which the CME sum smears out
+        let transform = |s: Complex<f64>| (s + 1.0) / ((s + 1.0).powi(2) + 4.0);
+        let exact = |t: f64| (-t).exp() * (2.0 * t).cos();
+        let inverter = CmeInverter::new(50).unwrap();
+        let expansion = inverter.with_small_time_threshold(1e-3);
+        for t in [1e-12, 1e-6, 1e-4] {
+            let (result, diagnostics) = expansion.invert_with_diagnostics(transform, t).unwrap();
+            let error = (result - exact(t)).abs();
+            assert!(error < 1e-15);
+            assert!(error < (inverter.invert(transform, t).unwrap() - exact(t)).abs());
+            assert_eq!(diagnostics.evals, SMALL_TIME_TERMS);
+            assert_eq!(
+                expansion.invert_batch(|nodes| nodes.iter().copied().map(transform).collect(), t),
+                Ok(result)
+            );
+        }
+
+        // Larger times and the time policy are unaffected
+        assert_eq!(
+            expansion.invert(transform, 1e-2),
+            inverter.invert(transform, 1e-2)
+        );
+        assert_eq!(
+            expansion.invert(transform, 0.0),
+            Err(Error::NonPositiveTime(0.0))
+        );
+    }
+
+    #[test]
+    fn large_time_asymptote() {
+        let inverter = CmeInverter::new(100).unwrap();
+        let asymptote = inverter.with_large_time_threshold(1e2);
+        // Step response of a first-order system, and an algebraic tail `1 - e^t erfc(sqrt(t))`
+        let settling = |s: Complex<f64>| (s * (s + 1.0)).recip();
+        let tail = |s: Complex<f64>| (s * (1.0 + s.sqrt())).recip();
+        for (t, tolerance) in [(1e3, 1e-4), (1e5, 1e-6)] {
+            let (result, diagnostics) = asymptote.invert_with_diagnostics(settling, t).unwrap();
+            assert!(approx::relative_eq!(result, 1.0, epsilon = tolerance));
+            assert_eq!(diagnostics.evals, 3);
+
+            // The next term of the tail is `t^(-3/2)`
+            let exact = 1.0 - (1.0 - 1.0 / (2.0 * t)) / (PI * t).sqrt();
+            let result = asymptote.invert(tail, t).unwrap();
+            assert!(approx::relative_eq!(result, exact, epsilon = tolerance));
+        }
+
+        // Times up to the threshold use the CME sum
+        assert_eq!(asymptote.invert(tail, 1e2), inverter.invert(tail, 1e2));
+        assert_eq!(asymptote.large_time_threshold(), Some(1e2));
+    }
+
+    #[test]
+    fn jumps() {
+        let staircase = |s: Complex<f64>| s.recip() / (s.exp() - 1.0);
+        let inverter = CmeInverter::new(100).unwrap();
+
+        // Jumps at one and two, the one at three is beyond the grid
+        let times = (1..60).map(|i| f64::from(i) * 0.05).collect::<Vec<_>>();
+        let jumps = inverter.detect_jumps(staircase, &times, 1e-4).unwrap();
+        assert_eq!(jumps.len(), 2);
+        assert!((jumps[0] - 1.0).abs() < 0.05 && (jumps[1] - 2.0).abs() < 0.05);
+        let sine = |s: Complex<f64>| (s.powi(2) + 1.0).recip();
+        assert!(inverter
+            .detect_jumps(sine, &times, 1e-4)
+            .unwrap()
+            .is_empty());
+
+        // Filters still invert a constant exactly, and report what they changed
+        for filter in [
+            SpectralFilter::Lanczos,
+            SpectralFilter::Exponential {
+                strength: 1.0,
+                order: 1,
+            },
+        ] {
+            let filtered = inverter.with_spectral_filter(filter);
+            let (result, diagnostics) = filtered
+                .invert_with_diagnostics(|s| s.recip(), 2.0)
+                .unwrap();
+            assert!(approx::relative_eq!(result, 1.0, epsilon = 1e-10));
+            assert!(diagnostics.filter_correction.abs() < 1e-10);
+
+            let (result, diagnostics) = filtered.invert_with_diagnostics(staircase, 1.5).unwrap();
+            let (unfiltered, _) = inverter.invert_with_diagnostics(staircase, 1.5).unwrap();
+            assert!(approx::relative_eq!(
+                diagnostics.filter_correction,
+                result - unfiltered,
+                epsilon = 1e-12
+            ));
+            assert!(approx::relative_eq!(result, 1.0, epsilon = 1e-3));
+        }
+        assert_eq!(SpectralFilter::Lanczos.factor(0, 10), 1.0);
+        assert_eq!(
+            inverter
+                .invert_with_diagnostics(staircase, 1.5)
+                .unwrap()
+                .1
+                .filter_correction,
+            0.0
+        );
+    }
+
+    #[test]
+    fn smoothing() {
+        let inverter = CmeInverter::new(100).unwrap();
+        let width = 0.5;
+        let smoothed = inverter.with_smoothing(width);
+        assert_eq!(smoothed.smoothing(), width);
+
+        // Constants and linear functions are preserved once the window is past zero
+        for t in [3.0, 10.0] {
+            assert!(approx::relative_eq!(
+                smoothed.invert(|s| s.recip(), t).unwrap(),
+                1.0,
+                epsilon = 1e-5
+            ));
+            assert!(approx::relative_eq!(
+                smoothed.invert(|s| s.powi(-2), t).unwrap(),
+                t,
+                epsilon = 1e-5
+            ));
+        }
+        assert!(smoothed.invert(|s| s.recip(), 0.1).unwrap() < 0.9);
+
+        // Sine is damped and shifted by the characteristic function of the centered window
+        let shape = f64::from(SMOOTHING_SHAPE);
+        let scale = width / shape.sqrt();
+        let window = Complex::new(0.0, shape * scale).exp() / Complex::new(1.0, scale).powi(16);
+        let sine = |s: Complex<f64>| (s * s + 1.0).recip();
+        for t in [3.0, 5.0] {
+            let expected = (Complex::new(0.0, t).exp() * window).im;
+            assert!(approx::relative_eq!(
+                smoothed.invert(sine, t).unwrap(),
+                expected,
+                epsilon = 5e-3
+            ));
+        }
+
+        // Fast oscillation is removed
+        let fast = |s: Complex<f64>| 10.0 * (s * s + 100.0).recip();
+        assert!(smoothed.invert(fast, 5.0).unwrap().abs() < 1e-3);
+        assert!(inverter.invert(fast, 5.0).unwrap().abs() > 0.1);
+
+        // Nodes of batched inversions are moved to the later time as well
+        assert_eq!(
+            smoothed
+                .invert_batch(|nodes| nodes.iter().map(|s| sine(*s)).collect(), 5.0)
+                .unwrap(),
+            smoothed.invert(sine, 5.0).unwrap()
+        );
+    }
+
+    #[test]
+    fn non_finite_transform() {
+        let inverter = CmeInverter::new(50).unwrap();
+
+        // Only the first node is evaluated on the real axis
+        let err = inverter
+            .invert(|s| if s.im == 0.0 { f64::NAN.into() } else { s }, 2.0)
+            .unwrap_err();
+        let Error::NonFiniteTransform {
+            node,
+            beta,
+            s,
+            value,
+        } = err
+        else {
+            panic!("Unexpected error {err:?}");
+        };
+        assert_eq!(node, 0);
+        assert_eq!(beta.im, 0.0);
+        assert_eq!(s, beta / 2.0);
+        assert!(value.re.is_nan());
+
+        let err = inverter
+            .invert(|s| if s.im > 1.0 { (s - s).recip() } else { s }, 1.0)
+            .unwrap_err();
+        assert!(matches!(err, Error::NonFiniteTransform { node, s, .. } if node > 0 && s.im > 1.0));
+    }
+}
@@ -1,15 +1,91 @@
 #![doc = include_str!("../README.md")]
 
-#[rustfmt::skip]
-mod coefficients;
+pub mod acceleration;
+pub mod bromwich;
+pub mod coefficients;
+pub mod combinators;
+#[cfg(feature = "consolidation")]
+pub mod consolidation;
+pub mod contour;
+pub mod control;
+pub mod convolution;
+pub mod delay;
+pub mod distribution;
+mod double_double;
+pub mod drt;
+mod error;
+pub mod exponentials;
+#[cfg(feature = "expression")]
+pub mod expression;
+#[cfg(feature = "finance")]
+pub mod finance;
+pub mod first_passage;
+pub mod fourier;
+pub mod fractional;
+pub mod gaver;
+pub mod heat;
+pub mod hybrid;
+pub mod interval;
+mod inversion;
+mod inverter;
+pub mod mittag_leffler;
+#[cfg(feature = "parallel")]
+pub mod offload;
+pub mod periodic;
+pub mod phase_type;
+pub mod piecewise;
+pub mod poles;
+pub mod queueing;
+pub mod rational;
+pub mod reference;
+pub mod regularization;
+pub mod ruin;
+pub mod special;
+pub mod state_space;
+pub mod sweep;
+pub mod transforms;
+pub mod transport;
+#[cfg(feature = "uom")]
+pub mod units;
+pub mod viscoelastic;
+pub mod wells;
 
-use nalgebra::Complex;
+use double_double::DoubleDouble;
+pub use error::Error;
+/// # Example
+///
+/// ```rust
+/// use iltcme::laplace;
+///
+/// let result = iltcme::laplace_inversion(laplace!(1 / (s^2 + 1)), 1.0, 50);
+/// approx::assert_relative_eq!(result, 1.0_f64.sin(), epsilon = 0.001);
+///
+/// // Damped oscillation with captured parameters
+/// let (zeta, omega) = (0.1, 2.0);
+/// let transform = laplace!(omega^2 / (s^2 + 2 * zeta * omega * s + omega^2));
+/// ```
+#[cfg(feature = "macros")]
+pub use iltcme_macros::laplace;
+pub use inversion::{Inversion, InversionBuilder, Method, Order};
+pub use inverter::{CmeInverter, SpectralFilter, Summation, TimePolicy};
+use nalgebra::{Complex, DVector};
+use num_traits::Num;
+
+/// Items used by the expansion of [`laplace!`].
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub mod __private {
+    pub use nalgebra::Complex;
+}
+
+/// Laplace transform function stored on the heap.
+pub(crate) type BoxedLaplaceFunc<'a> = Box<dyn Fn(Complex<f64>) -> Complex<f64> + 'a>;
 
 /// Calculate the Laplace inversion for a function using the CME method.
 ///
 /// Evaluates the Laplace transform expression at certain points to approximate the inverse of the Laplace transform at a given point.
 ///
-/// Maximum number of evaluations is 500 due to filesize limitations for crates.
+/// Maximum number of evaluations is given by [`coefficients::max_evaluations`].
 ///
 /// # Example
 ///
@@ -18,56 +94,411 @@ use nalgebra::Complex;
 /// // Approximate a sine function where `x = 1`
 /// // The Laplace transform of sine is `h*(s) = 1 / (s^2 + 1)`
 /// let result = iltcme::laplace_inversion(|s| 1.0 / (s.powi(2) + 1.0), 1.0, 50);
-/// approx::relative_eq!(result, 1.0_f64.sin(), epsilon = 0.001);
+/// approx::assert_relative_eq!(result, 1.0_f64.sin(), epsilon = 0.001);
 /// # }
 /// ```
+#[inline]
 pub fn laplace_inversion(
     laplace_func: impl Fn(Complex<f64>) -> Complex<f64>,
-    s: f64,
+    t: f64,
     max_function_evals: usize,
 ) -> f64 {
-    assert!(
-        max_function_evals <= coefficients::MAX_EVALUATIONS,
-        "Laplace maximum function evaluations must be less or equal to {}",
-        coefficients::MAX_EVALUATIONS
-    );
+    laplace_inversion_dyn(&laplace_func, t, max_function_evals)
+}
 
-    // Compute inverse Laplace
-    let (mu1, eta_betas, first_eta) = coefficients::ETA_BETA_PAIRS[max_function_evals];
-    std::iter::once((first_eta.into(), mu1.into()))
-        .chain(eta_betas.iter().map(|(eta_re, eta_im, beta)| {
-            (Complex::new(*eta_re, *eta_im), Complex::new(mu1, *beta))
-        }))
-        .map(|(eta, beta)| (eta * laplace_func(beta / s)).re)
-        .sum::<f64>()
-        / s
+/// Calculate the Laplace inversion for a function behind a trait object using the CME method.
+///
+/// Behaves the same as [`laplace_inversion`], which forwards to this function.
+/// The inversion is compiled once for all transforms instead of once for each closure type, at the cost of a dynamic call per node.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use nalgebra::Complex;
+///
+/// let transforms: [&dyn Fn(Complex<f64>) -> Complex<f64>; 2] =
+///     [&|s| 1.0 / (s + 1.0), &|s| 1.0 / (s.powi(2) + 1.0)];
+/// for transform in transforms {
+///     let result = iltcme::laplace_inversion_dyn(transform, 1.0, 50);
+///     assert!(result > 0.3);
+/// }
+/// # }
+/// ```
+pub fn laplace_inversion_dyn(
+    laplace_func: &dyn Fn(Complex<f64>) -> Complex<f64>,
+    t: f64,
+    max_function_evals: usize,
+) -> f64 {
+    laplace_inversion_mut(laplace_func, t, max_function_evals)
 }
 
 /// Calculate the Laplace inversion for a mutable function using the CME method.
 ///
 /// Evaluates the Laplace transform expression at certain points to approximate the inverse of the Laplace transform at a given point.
 ///
-/// Maximum number of evaluations is 500 due to filesize limitations for crates.
+/// Maximum number of evaluations is given by [`coefficients::max_evaluations`].
 pub fn laplace_inversion_mut(
-    mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
-    s: f64,
+    laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+    t: f64,
+    max_function_evals: usize,
+) -> f64 {
+    laplace_inversion_with_diagnostics(laplace_func, t, max_function_evals).0
+}
+
+/// Calculate the Laplace inversion using the CME method without validating `max_function_evals` in release builds.
+///
+/// For inner loops that invert millions of times with an order that's known to be valid, this skips the bounds check and the [`Diagnostics`] of [`laplace_inversion_with_diagnostics`].
+/// Exceeding the maximum number of evaluations panics in debug builds and uses the highest order in release builds.
+/// Looking up the coefficients once with [`coefficients::order`] and inverting with [`CmeOrder::invert`](coefficients::CmeOrder::invert) in the loop skips the lookup as well.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// let sine = |s: nalgebra::Complex<f64>| 1.0 / (s.powi(2) + 1.0);
+/// let result = iltcme::laplace_inversion_unchecked(sine, 1.0, 50);
+/// assert_eq!(result, iltcme::laplace_inversion(sine, 1.0, 50));
+/// # }
+/// ```
+#[inline]
+pub fn laplace_inversion_unchecked(
+    laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+    t: f64,
     max_function_evals: usize,
 ) -> f64 {
-    assert!(
-        max_function_evals <= coefficients::MAX_EVALUATIONS,
+    debug_assert!(
+        max_function_evals <= coefficients::max_evaluations(),
         "Laplace maximum function evaluations must be less or equal to {}",
-        coefficients::MAX_EVALUATIONS
+        coefficients::max_evaluations()
     );
 
+    coefficients::order_unchecked(max_function_evals)
+        .weighted_sum(laplace_func, t, Summation::Naive)
+        .re
+}
+
+/// Calculate the Laplace inversion using the CME method and report how it was computed.
+///
+/// Behaves the same as [`laplace_inversion_mut`] but also returns the [`Diagnostics`] describing the concentrated matrix-exponential distribution that got chosen for `max_function_evals`.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// let (result, diagnostics) =
+///     iltcme::laplace_inversion_with_diagnostics(|s| 1.0 / (s.powi(2) + 1.0), 1.0, 50);
+/// approx::assert_relative_eq!(result, 1.0_f64.sin(), epsilon = 0.001);
+/// assert_eq!(diagnostics.evals, diagnostics.n + 1);
+/// assert!(diagnostics.evals <= 50);
+/// # }
+/// ```
+pub fn laplace_inversion_with_diagnostics(
+    laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+    t: f64,
+    max_function_evals: usize,
+) -> (f64, Diagnostics) {
+    cme_sum(
+        laplace_func,
+        t,
+        max_function_evals,
+        SpectralFilter::None,
+        Summation::Naive,
+    )
+}
+
+/// Compute the weighted CME sum, with the weights damped by the filter and the terms added with the summation.
+pub(crate) fn cme_sum(
+    laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+    t: f64,
+    max_function_evals: usize,
+    filter: SpectralFilter,
+    summation: Summation,
+) -> (f64, Diagnostics) {
     // Compute inverse Laplace
-    let (mu1, eta_betas, first_eta) = coefficients::ETA_BETA_PAIRS[max_function_evals];
-    std::iter::once((first_eta.into(), mu1.into()))
-        .chain(eta_betas.iter().map(|(eta_re, eta_im, beta)| {
-            (Complex::new(*eta_re, *eta_im), Complex::new(mu1, *beta))
-        }))
-        .map(|(eta, beta)| (eta * laplace_func(beta / s)).re)
-        .sum::<f64>()
-        / s
+    let order = coefficients::order(max_function_evals);
+    let (sum, filter_correction) = if filter == SpectralFilter::None {
+        (order.weighted_sum(laplace_func, t, summation), 0.0)
+    } else {
+        let (filtered, unfiltered) = order.filtered_sum(laplace_func, t, filter, summation);
+        (filtered, filtered.re - unfiltered.re)
+    };
+
+    let diagnostics = Diagnostics {
+        order_used: max_function_evals,
+        n: order.n(),
+        cv2: order.cv2(),
+        imag_residual: sum.im,
+        evals: order.evaluations(),
+        filter_correction,
+    };
+
+    (sum.re, diagnostics)
+}
+
+/// Recompute the Laplace inversion in double-double precision and report how many digits the `f64` result has right.
+///
+/// The transform is evaluated once at each node in `f64`, then the weighted sum is computed both like [`laplace_inversion`] and with exact products accumulated in double-double precision.
+/// Both use the same weights and nodes, so this audits the rounding errors of the inversion itself, which grow with the order and with cancellation between large terms, not the approximation error of the method.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// let precision = iltcme::verify_precision(|s| 1.0 / (s.powi(2) + 1.0), 1.0, 50);
+/// assert_eq!(precision.result, iltcme::laplace_inversion(|s| 1.0 / (s.powi(2) + 1.0), 1.0, 50));
+/// assert!(precision.digits > 10.0);
+/// # }
+/// ```
+pub fn verify_precision(
+    mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+    t: f64,
+    max_function_evals: usize,
+) -> Precision {
+    let terms = coefficients::order(max_function_evals)
+        .nodes()
+        .map(|(eta, beta)| (eta, laplace_func(beta / t)))
+        .collect::<Vec<_>>();
+
+    let result = Summation::Naive.weighted(terms.iter().copied()).re / t;
+    let extended = terms
+        .iter()
+        .fold(DoubleDouble::default(), |sum, (eta, value)| {
+            sum + DoubleDouble::product(eta.re, value.re) - DoubleDouble::product(eta.im, value.im)
+        })
+        / DoubleDouble::from(t);
+    let extended = extended.to_f64();
+
+    // Relative to the extended result, absolute when it's zero
+    let error = (result - extended).abs() / extended.abs().max(f64::MIN_POSITIVE);
+    let max_digits = -(f64::EPSILON / 2.0).log10();
+
+    Precision {
+        result,
+        extended,
+        digits: if error.is_nan() {
+            0.0
+        } else {
+            (-error.log10()).clamp(0.0, max_digits)
+        },
+    }
+}
+
+/// Calculate the Laplace inversion for a vector valued function using the CME method.
+///
+/// Each element of the vector is inverted, the result vectors are accumulated so each node is only evaluated once for all elements.
+///
+/// Maximum number of evaluations is given by [`coefficients::max_evaluations`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use nalgebra::DVector;
+///
+/// // Invert both the sine and cosine at once
+/// let result = iltcme::laplace_inversion_vector(
+///     |s| DVector::from_vec(vec![1.0 / (s.powi(2) + 1.0), s / (s.powi(2) + 1.0)]),
+///     1.0,
+///     50,
+/// );
+/// approx::assert_relative_eq!(result[0], 1.0_f64.sin(), epsilon = 0.001);
+/// approx::assert_relative_eq!(result[1], 1.0_f64.cos(), epsilon = 0.001);
+/// # }
+/// ```
+pub fn laplace_inversion_vector(
+    mut laplace_func: impl FnMut(Complex<f64>) -> DVector<Complex<f64>>,
+    t: f64,
+    max_function_evals: usize,
+) -> DVector<f64> {
+    let mut nodes = coefficients::order(max_function_evals).nodes();
+    // The first node determines the size of the vector
+    let (eta, beta) = nodes.next().unwrap();
+    let first = laplace_func(beta / t) * eta;
+
+    nodes
+        .fold(first, |sum, (eta, beta)| sum + laplace_func(beta / t) * eta)
+        .map(|value| value.re / t)
+}
+
+/// Calculate the Laplace inversion for a function of a generic real type using the CME method.
+///
+/// The nodes are passed to the transform as `Complex<T>`, so dual numbers such as those of the [`num-dual`](https://docs.rs/num-dual) crate can flow through it.
+/// With the parameters of the transform as dual numbers the derivatives of the inverse with respect to those parameters come out of the same call, which is what fitting Laplace domain models to data needs.
+///
+/// Maximum number of evaluations is given by [`coefficients::max_evaluations`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use nalgebra::Complex;
+/// use num_dual::{Dual64, DualNum};
+///
+/// // Derivative of the decaying exponential `e^(-rate t)` with respect to the rate
+/// let rate = Dual64::from(2.0).derivative();
+/// let t = 1.0;
+/// let result = iltcme::laplace_inversion_generic(
+///     |s: Complex<Dual64>| Complex::from(Dual64::from(1.0)) / (s + rate),
+///     t,
+///     50,
+/// );
+/// approx::assert_relative_eq!(result.re(), (-2.0_f64).exp(), epsilon = 0.001);
+/// approx::assert_relative_eq!(result.eps, -t * (-2.0_f64).exp(), epsilon = 0.001);
+/// # }
+/// ```
+pub fn laplace_inversion_generic<T>(
+    mut laplace_func: impl FnMut(Complex<T>) -> Complex<T>,
+    t: f64,
+    max_function_evals: usize,
+) -> T
+where
+    T: Clone + Num + From<f64>,
+{
+    // Lift the weights and nodes into the generic type
+    let lift = |value: Complex<f64>| Complex::new(T::from(value.re), T::from(value.im));
+
+    coefficients::order(max_function_evals)
+        .nodes()
+        .fold(Complex::new(T::zero(), T::zero()), |sum, (eta, beta)| {
+            sum + lift(eta) * laplace_func(lift(beta / t))
+        })
+        .re
+        / T::from(t)
+}
+
+/// Calculate the Laplace inversion of a function with a parameter $\theta$ and its derivative with respect to that parameter.
+///
+/// The derivative is approximated with central differences of the transform at $\theta \pm d\theta$, evaluated at the same nodes as the inverse itself.
+/// The complex-step method can't be used since the transform is already complex.
+/// For exact derivatives see [`laplace_inversion_generic`].
+///
+/// Returns the inverse $f(t; \theta)$ and $\partial f / \partial \theta$, evaluating the transform three times for each node.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// // Sensitivity of the decaying exponential `e^(-rate t)` to the rate
+/// let t = 1.0;
+/// let (result, derivative) =
+///     iltcme::laplace_sensitivity(|s, rate| 1.0 / (s + rate), t, 2.0, 1e-5, 50);
+/// approx::assert_relative_eq!(result, (-2.0_f64).exp(), epsilon = 0.001);
+/// approx::assert_relative_eq!(derivative, -t * (-2.0_f64).exp(), epsilon = 0.001);
+/// # }
+/// ```
+pub fn laplace_sensitivity(
+    mut laplace_func: impl FnMut(Complex<f64>, f64) -> Complex<f64>,
+    t: f64,
+    theta: f64,
+    d_theta: f64,
+    max_function_evals: usize,
+) -> (f64, f64) {
+    let (sum, derivative_sum) = coefficients::order(max_function_evals).nodes().fold(
+        (Complex::new(0.0, 0.0), Complex::new(0.0, 0.0)),
+        |(sum, derivative_sum), (eta, beta)| {
+            let s = beta / t;
+            let difference = laplace_func(s, theta + d_theta) - laplace_func(s, theta - d_theta);
+
+            (
+                sum + eta * laplace_func(s, theta),
+                derivative_sum + eta * difference / (2.0 * d_theta),
+            )
+        },
+    );
+
+    (sum.re / t, derivative_sum.re / t)
+}
+
+/// Laplace transform function marked as only accepting real arguments, such as the output of a solver that can't be run with complex numbers.
+///
+/// [`laplace_inversion_of`] inverts it with the Gaver-Wynn-Rho method, see [`gaver::gwr`], instead of the CME method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RealOnly<F>(pub F);
+
+/// Laplace transform that knows which inversion method it can be evaluated for.
+///
+/// Implemented for functions of complex arguments, which use the CME method, and for [`RealOnly`] functions, which use evaluations on the real axis only.
+pub trait LaplaceTransform {
+    /// Calculate the Laplace inversion at time `t` evaluating the function at most `max_function_evals` times.
+    fn invert(&mut self, t: f64, max_function_evals: usize) -> f64;
+}
+
+impl<F> LaplaceTransform for F
+where
+    F: FnMut(Complex<f64>) -> Complex<f64>,
+{
+    fn invert(&mut self, t: f64, max_function_evals: usize) -> f64 {
+        laplace_inversion_mut(self, t, max_function_evals)
+    }
+}
+
+impl<F> LaplaceTransform for RealOnly<F>
+where
+    F: FnMut(f64) -> f64,
+{
+    /// Uses at most 10 Gaver functionals, more don't improve the result in `f64`.
+    fn invert(&mut self, t: f64, max_function_evals: usize) -> f64 {
+        gaver::gwr(&mut self.0, t, (max_function_evals / 2).clamp(1, 10))
+    }
+}
+
+/// Calculate the Laplace inversion with a method the transform can be evaluated for.
+///
+/// Functions of complex arguments are inverted with the CME method, the same as [`laplace_inversion_mut`].
+/// Functions wrapped in [`RealOnly`] are only evaluated on the real axis, which is limited to smooth, non-oscillating inverses and about 8 digits.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::RealOnly;
+///
+/// let result = iltcme::laplace_inversion_of(RealOnly(|s: f64| 1.0 / (s + 1.0)), 1.0, 50);
+/// approx::assert_relative_eq!(result, (-1.0_f64).exp(), epsilon = 1e-6);
+/// # }
+/// ```
+pub fn laplace_inversion_of(
+    mut transform: impl LaplaceTransform,
+    t: f64,
+    max_function_evals: usize,
+) -> f64 {
+    transform.invert(t, max_function_evals)
+}
+
+/// Information about how a Laplace inversion was computed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diagnostics {
+    /// Maximum number of function evaluations the coefficients were looked up for.
+    pub order_used: usize,
+    /// Order of the concentrated matrix-exponential distribution that was chosen.
+    ///
+    /// This is the steepest distribution that fits within the maximum number of function evaluations.
+    pub n: usize,
+    /// Squared coefficient of variation of the chosen distribution, lower means a more accurate result.
+    pub cv2: f64,
+    /// Imaginary part of the weighted sum that gets discarded in the result.
+    pub imag_residual: f64,
+    /// Number of times the Laplace transform function got evaluated.
+    pub evals: usize,
+    /// Change of the result by the spectral filter, zero without one.
+    ///
+    /// It's largest close to features the order can't resolve, like jumps, see [`CmeInverter::with_spectral_filter`].
+    pub filter_correction: f64,
+}
+
+/// Agreement of an inversion in `f64` with the same inversion in double-double precision, see [`verify_precision`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Precision {
+    /// Result of the inversion in `f64`, the same as [`laplace_inversion`].
+    pub result: f64,
+    /// Result of the inversion in double-double precision, rounded to `f64`.
+    pub extended: f64,
+    /// Number of decimal digits of [`Precision::result`] that agree with [`Precision::extended`].
+    ///
+    /// Between zero and the almost 16 digits of `f64`, zero when either result isn't finite.
+    pub digits: f64,
 }
 
 #[cfg(test)]
@@ -125,4 +556,187 @@ mod tests {
     fn laplace_inversions() {
         invert_fns(30);
     }
+
+    #[test]
+    fn dyn_transforms() {
+        let transforms: [&dyn Fn(Complex<f64>) -> Complex<f64>; 2] =
+            [&|s| (1.0 + s).recip(), &|s| (1.0 + s.powi(2)).recip()];
+        for (transform, expected) in transforms
+            .into_iter()
+            .zip([(-1.5_f64).exp(), 1.5_f64.sin()])
+        {
+            let result = laplace_inversion_dyn(transform, 1.5, 100);
+            assert_eq!(result, laplace_inversion(transform, 1.5, 100));
+            assert!(approx::relative_eq!(result, expected, epsilon = 1e-4));
+        }
+    }
+
+    #[test]
+    fn unchecked() {
+        let staircase = |s: Complex<f64>| s.recip() / (s.exp() - 1.0);
+        for max_function_evals in [2, 30, 31, 200, coefficients::max_evaluations()] {
+            assert_eq!(
+                laplace_inversion_unchecked(staircase, 2.5, max_function_evals),
+                laplace_inversion(staircase, 2.5, max_function_evals)
+            );
+        }
+    }
+
+    /// Inverting a scaled exponential at its time constant should give the same result for any magnitude.
+    #[test]
+    fn time_scale_invariance() {
+        let expected = laplace_inversion(|s| (1.0 + s).recip(), 1.0, 200);
+        for exponent in (-100..=100).step_by(10) {
+            let tau = 10.0_f64.powi(exponent);
+            let result = laplace_inversion(|s| tau / (1.0 + tau * s), tau, 200);
+
+            assert!(
+                approx::relative_eq!(result, expected, max_relative = 1e-9),
+                "Inversion at time {tau} differs:\n\tResult  : {result}\n\tExpected: {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn generic_dual_numbers() {
+        use num_dual::{Dual64, DualNum};
+
+        // Same as the inversion with floats
+        let sine = |s: Complex<f64>| (1.0 + s.powi(2)).recip();
+        assert!(approx::relative_eq!(
+            laplace_inversion_generic(sine, 2.0, 50),
+            laplace_inversion(sine, 2.0, 50),
+            max_relative = 1e-12
+        ));
+
+        // Derivative of `sin(omega t)` with respect to the frequency is `t cos(omega t)`
+        let omega = Dual64::from(1.5).derivative();
+        for t in [0.5, 1.0, 2.0] {
+            let result = laplace_inversion_generic(
+                |s: Complex<Dual64>| Complex::from(omega) / (s * s + Complex::from(omega * omega)),
+                t,
+                100,
+            );
+
+            assert!(approx::relative_eq!(
+                result.re(),
+                (1.5 * t).sin(),
+                epsilon = 1e-3
+            ));
+            assert!(approx::relative_eq!(
+                result.eps,
+                t * (1.5 * t).cos(),
+                epsilon = 1e-3
+            ));
+        }
+    }
+
+    #[test]
+    fn sensitivity() {
+        use num_dual::Dual64;
+
+        // Damped sine with the damping as parameter
+        let transform = |s: Complex<f64>, damping: f64| ((s + damping).powi(2) + 1.0).recip();
+        let damping = Dual64::from(0.5).derivative();
+        for t in [0.5, 1.0, 2.0] {
+            let (result, derivative) = laplace_sensitivity(transform, t, 0.5, 1e-5, 50);
+            assert_eq!(result, laplace_inversion(|s| transform(s, 0.5), t, 50));
+
+            // Close to the exact derivative of the same inversion
+            let exact = laplace_inversion_generic(
+                |s: Complex<Dual64>| {
+                    let shifted = s + Complex::from(damping);
+                    Complex::from(Dual64::from(1.0))
+                        / (shifted * shifted + Complex::from(Dual64::from(1.0)))
+                },
+                t,
+                50,
+            );
+            assert!(approx::relative_eq!(
+                derivative,
+                exact.eps,
+                max_relative = 1e-6
+            ));
+            // The inverse is `e^(-damping t) sin(t)`
+            assert!(approx::relative_eq!(
+                derivative,
+                -t * (-0.5 * t).exp() * t.sin(),
+                epsilon = 1e-3
+            ));
+        }
+    }
+
+    #[test]
+    fn diagnostics() {
+        let mut evals = 0;
+        let (result, diagnostics) = laplace_inversion_with_diagnostics(
+            |s| {
+                evals += 1;
+                (1.0 + s).recip()
+            },
+            1.0,
+            30,
+        );
+
+        assert_eq!(result, laplace_inversion(|s| (1.0 + s).recip(), 1.0, 30));
+        assert_eq!(diagnostics.order_used, 30);
+        assert_eq!(diagnostics.evals, evals);
+        assert_eq!(diagnostics.evals, diagnostics.n + 1);
+        assert!(diagnostics.evals <= 30);
+        assert!(diagnostics.cv2 > 0.0);
+    }
+
+    #[test]
+    fn real_only_transforms() {
+        for t in [0.5_f64, 1.0, 3.0] {
+            let mut evals = 0;
+            let result = laplace_inversion_of(
+                RealOnly(|s: f64| {
+                    evals += 1;
+                    1.0 / (s + 1.0)
+                }),
+                t,
+                50,
+            );
+            assert!(approx::relative_eq!(result, (-t).exp(), epsilon = 1e-6));
+            assert_eq!(evals, 20);
+
+            // Complex functions keep using the CME method
+            assert_eq!(
+                laplace_inversion_of(|s: Complex<f64>| (1.0 + s).recip(), t, 50),
+                laplace_inversion(|s| (1.0 + s).recip(), t, 50)
+            );
+        }
+
+        let mut evals = 0;
+        laplace_inversion_of(
+            RealOnly(|s: f64| {
+                evals += 1;
+                s.recip()
+            }),
+            1.0,
+            5,
+        );
+        assert_eq!(evals, 4);
+    }
+
+    #[test]
+    fn precision() {
+        let exponential = |s: Complex<f64>| 1.0 / (s + 1.0);
+        for max in [10, 50, 500] {
+            let precision = verify_precision(exponential, 1.0, max);
+            assert_eq!(precision.result, laplace_inversion(exponential, 1.0, max));
+            assert!(precision.digits > 10.0, "{precision:?}");
+        }
+
+        // The weights of high orders are much larger than the result, so rounding errors cancel less
+        let low = verify_precision(exponential, 1.0, 10);
+        let high = verify_precision(exponential, 1.0, 500);
+        assert!(high.digits <= low.digits, "{low:?} {high:?}");
+
+        let zero = verify_precision(|_| Complex::new(0.0, 0.0), 1.0, 50);
+        assert_eq!(zero.digits, -(f64::EPSILON / 2.0).log10());
+        let not_finite = verify_precision(|_| Complex::new(f64::NAN, 0.0), 1.0, 50);
+        assert_eq!(not_finite.digits, 0.0);
+    }
 }
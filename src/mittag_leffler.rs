@@ -0,0 +1,96 @@
+//! Mittag-Leffler function through the inversion of its Laplace transform.
+//!
+//! The two-parameter Mittag-Leffler function follows from the Laplace transform pair
+//!
+//! $$
+//! \mathcal{L}\left[t^{\beta - 1} E_{\alpha,\beta}(z t^\alpha)\right](s) = \frac{s^{\alpha - \beta}}{s^\alpha - z}
+//! $$
+//!
+//! evaluated at $t = 1$.
+//! The poles of the transform on the principal sheet are subtracted and inverted exactly, so only the branch cut along the negative real axis is inverted numerically.
+
+use nalgebra::Complex;
+
+use crate::poles::Pole;
+
+/// Calculate the two-parameter Mittag-Leffler function $E_{\alpha,\beta}(z)$ for a real argument.
+///
+/// The accuracy is controlled with the maximum number of function evaluations of the CME method, which is at most 500.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// // The Mittag-Leffler function generalizes the exponential
+/// let result = iltcme::mittag_leffler::mittag_leffler(1.0, 1.0, 2.0, 50);
+/// approx::assert_relative_eq!(result, 2.0_f64.exp(), epsilon = 1e-10);
+///
+/// // Relaxation with a fractional order
+/// let result = iltcme::mittag_leffler::mittag_leffler(0.5, 1.0, -1.0, 100);
+/// approx::assert_relative_eq!(result, 0.4275835761558070, epsilon = 1e-4);
+/// # }
+/// ```
+pub fn mittag_leffler(alpha: f64, beta: f64, z: f64, max_function_evals: usize) -> f64 {
+    assert!(
+        alpha > 0.0,
+        "Mittag-Leffler alpha parameter must be positive"
+    );
+
+    crate::poles::laplace_inversion_with_poles(
+        |s| s.powf(alpha - beta) / (s.powf(alpha) - z),
+        &poles(alpha, beta, z),
+        1.0,
+        max_function_evals,
+    )
+}
+
+/// Poles of $\frac{s^{\alpha - \beta}}{s^\alpha - z}$ on the principal sheet with their residues.
+fn poles(alpha: f64, beta: f64, z: f64) -> Vec<Pole> {
+    if z == 0.0 {
+        return Vec::new();
+    }
+
+    let argument = if z > 0.0 { 0.0 } else { std::f64::consts::PI };
+    let modulus = z.abs().powf(alpha.recip());
+    // Only the branches with an angle within the principal sheet are poles, the boundary can only be reached for an integer alpha where there's no branch cut so only one side is included
+    let max_branch = (alpha / 2.0).ceil() as i32;
+    (-max_branch..=max_branch)
+        .map(|branch| argument + 2.0 * std::f64::consts::PI * f64::from(branch))
+        .filter(|angle| {
+            *angle > -alpha * std::f64::consts::PI && *angle <= alpha * std::f64::consts::PI
+        })
+        .map(|angle| {
+            let location = Complex::new(0.0, angle / alpha).exp() * modulus;
+
+            Pole::new(location, location.powf(1.0 - beta) / alpha)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::ComplexField;
+
+    use super::*;
+
+    #[test]
+    fn known_values() {
+        for x in [0.0, 0.5, 1.0, 2.0, 3.0] {
+            let cases = [
+                (1.0, 1.0, x, x.exp()),
+                (1.0, 1.0, -x, (-x).exp()),
+                (2.0, 1.0, -x * x, x.cos()),
+                (2.0, 1.0, x * x, x.cosh()),
+                (1.0, 2.0, x, if x == 0.0 { 1.0 } else { x.exp_m1() / x }),
+                (2.0, 2.0, -x * x, if x == 0.0 { 1.0 } else { x.sin() / x }),
+            ];
+            for (alpha, beta, z, expected) in cases {
+                let result = mittag_leffler(alpha, beta, z, 100);
+                assert!(
+                    approx::relative_eq!(result, expected, epsilon = 1e-6, max_relative = 1e-6),
+                    "E_{alpha},{beta}({z}) = {result} differs from {expected}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Inversions offloaded to a thread pool, awaited from an async runtime or by blocking.
+//!
+//! Inverting expensive transforms at many times is CPU work that would stall the executor of an async runtime.
+//! [`CmeInverter::spawn_batch`] runs it on the thread pool of the `parallel` feature and returns an [`InversionHandle`] to the results right away.
+//! The handle is a [`Future`], so it can be awaited in any runtime without a dependency on it.
+
+use std::{
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    task::{Context, Poll, Waker},
+    thread,
+};
+
+use nalgebra::Complex;
+
+use crate::{CmeInverter, Error};
+
+/// Results of a batch, or the panic of the transform.
+type Outcome = thread::Result<Result<Vec<f64>, Error>>;
+
+/// State shared between the handle and the task in the thread pool.
+#[derive(Default)]
+struct Shared {
+    /// Set once the task finished.
+    outcome: Option<Outcome>,
+    /// Waker of the last poll of the handle.
+    waker: Option<Waker>,
+}
+
+/// Handle to the results of a batch inversion running on the thread pool.
+///
+/// Await it in an async runtime, or block the current thread with [`InversionHandle::wait`].
+/// When the transform panics the panic is resumed when the results are retrieved.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), iltcme::Error> {
+/// use iltcme::CmeInverter;
+///
+/// let handle = CmeInverter::new(50)?.spawn_batch(|s| 1.0 / (s * s + 1.0), vec![0.5, 1.0]);
+/// // In an async function use `handle.await?`
+/// let result = handle.wait()?;
+/// approx::assert_relative_eq!(result[1], 1.0_f64.sin(), epsilon = 1e-3);
+/// # Ok(())
+/// # }
+/// ```
+pub struct InversionHandle {
+    /// State shared with the task, the condition variable is notified when it finished.
+    shared: Arc<(Mutex<Shared>, Condvar)>,
+}
+
+impl InversionHandle {
+    /// Run the task on the thread pool.
+    pub(crate) fn spawn(task: impl FnOnce() -> Result<Vec<f64>, Error> + Send + 'static) -> Self {
+        let shared = Arc::new((Mutex::new(Shared::default()), Condvar::new()));
+
+        let task_shared = shared.clone();
+        rayon::spawn(move || {
+            // A panic in the thread pool would abort the process
+            let outcome = panic::catch_unwind(AssertUnwindSafe(task));
+
+            let (state, finished) = &*task_shared;
+            let mut state = state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            state.outcome = Some(outcome);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+            finished.notify_all();
+        });
+
+        Self { shared }
+    }
+
+    /// Whether the batch finished, so the results can be retrieved without blocking.
+    pub fn is_finished(&self) -> bool {
+        self.lock().outcome.is_some()
+    }
+
+    /// Block the current thread until the batch finished and return its results.
+    pub fn wait(self) -> Result<Vec<f64>, Error> {
+        let (state, finished) = &*self.shared;
+        let mut state = finished
+            .wait_while(
+                state
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner()),
+                |state| state.outcome.is_none(),
+            )
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        resume(state.outcome.take().expect("Batch finished"))
+    }
+
+    /// Lock the shared state, the lock is never held while the transform runs.
+    fn lock(&self) -> MutexGuard<'_, Shared> {
+        self.shared
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Future for InversionHandle {
+    type Output = Result<Vec<f64>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.lock();
+        match state.outcome.take() {
+            Some(outcome) => Poll::Ready(resume(outcome)),
+            None => {
+                state.waker = Some(cx.waker().clone());
+
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Results of the batch, resuming the panic of the transform.
+fn resume(outcome: Outcome) -> Result<Vec<f64>, Error> {
+    outcome.unwrap_or_else(|payload| panic::resume_unwind(payload))
+}
+
+impl CmeInverter {
+    /// Calculate the Laplace inversion for a function at each of the times on the thread pool, returning a handle to the results right away.
+    ///
+    /// The times are inverted in parallel, the results are in the same order as the times.
+    /// Returns the error of the first time that failed.
+    pub fn spawn_batch(
+        &self,
+        laplace_func: impl Fn(Complex<f64>) -> Complex<f64> + Send + Sync + 'static,
+        times: Vec<f64>,
+    ) -> InversionHandle {
+        use rayon::prelude::*;
+
+        let inverter = *self;
+        InversionHandle::spawn(move || {
+            times
+                .par_iter()
+                .map(|t| inverter.invert(&laplace_func, *t))
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        task::Wake,
+        time::Duration,
+    };
+
+    use super::*;
+
+    /// Waker that unparks the thread polling the future.
+    struct Unpark(thread::Thread, AtomicBool);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.1.store(true, Ordering::SeqCst);
+            self.0.unpark();
+        }
+    }
+
+    /// Poll the future on the current thread until it's ready, like an async runtime.
+    fn block_on<F: Future>(future: F) -> (F::Output, bool) {
+        let unpark = Arc::new(Unpark(thread::current(), AtomicBool::new(false)));
+        let waker = Waker::from(unpark.clone());
+        let mut context = Context::from_waker(&waker);
+        let mut future = std::pin::pin!(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return (output, unpark.1.load(Ordering::SeqCst));
+            }
+            thread::park();
+        }
+    }
+
+    #[test]
+    fn offloaded_batches() {
+        let inverter = CmeInverter::new(100).unwrap();
+        let sine = |s: Complex<f64>| 1.0 / (s * s + 1.0);
+        let times = (1..=50).map(|i| f64::from(i) * 0.2).collect::<Vec<_>>();
+        let expected = times
+            .iter()
+            .map(|t| inverter.invert(sine, *t).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            inverter.spawn_batch(sine, times.clone()).wait(),
+            Ok(expected.clone())
+        );
+
+        // Slow transform, so the first poll is pending and the task wakes the future
+        let slow = move |s: Complex<f64>| {
+            thread::sleep(Duration::from_micros(50));
+            sine(s)
+        };
+        let handle = inverter.spawn_batch(slow, times.clone());
+        assert!(!handle.is_finished());
+        let (result, woken) = block_on(handle);
+        assert_eq!(result, Ok(expected));
+        assert!(woken);
+
+        assert_eq!(
+            inverter.spawn_batch(sine, vec![1.0, -1.0]).wait(),
+            Err(Error::NonPositiveTime(-1.0))
+        );
+
+        // Panics of the transform reach the caller instead of aborting
+        let handle = inverter.spawn_batch(|_| panic!("Transform failed"), vec![1.0]);
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| handle.wait())).is_err());
+    }
+}
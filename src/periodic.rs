@@ -0,0 +1,128 @@
+//! Inversion of Laplace transforms of periodic functions.
+//!
+//! A function with period $T$ has the transform
+//!
+//! $$
+//! F(s) = \frac{G(s)}{1 - e^{-sT}}
+//! $$
+//!
+//! where $G(s)$ is the transform of a single period.
+//! The poles of $F$ on the imaginary axis keep the CME method from resolving later periods, it gets worse with every period.
+//! Instead the single period is inverted at $t \bmod T$, with its jumps declared as delays like in [`crate::delay`] so they're applied exactly.
+
+use nalgebra::Complex;
+
+use crate::delay::DelayedTransform;
+
+/// Laplace transform of a periodic function, given by the transform of one period.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::{delay::DelayedTransform, periodic::PeriodicTransform};
+///
+/// // Square wave with period 2, one for the first half of each period
+/// let square = PeriodicTransform::new(
+///     2.0,
+///     DelayedTransform::new()
+///         .with_term(0.0, |s| 1.0 / s)
+///         .with_term(1.0, |s| -1.0 / s),
+/// );
+/// approx::assert_relative_eq!(square.invert(100.5, 50), 1.0, epsilon = 1e-8);
+/// approx::assert_relative_eq!(square.invert(101.5, 50), 0.0, epsilon = 1e-8);
+/// # }
+/// ```
+pub struct PeriodicTransform<'a> {
+    /// Period $T$.
+    period: f64,
+    /// Transform $G(s)$ of the first period.
+    one_period: DelayedTransform<'a>,
+}
+
+impl<'a> PeriodicTransform<'a> {
+    /// Construct from the period and the transform $G(s)$ of the function on $[0, T)$, which is zero afterwards.
+    ///
+    /// The delay factors of $G$, like the $e^{-sT}$ that ends the period, must be declared as delays.
+    /// Terms delayed by the full period or more never contribute to the inversion, they're only needed to evaluate $F$.
+    pub fn new(period: f64, one_period: DelayedTransform<'a>) -> Self {
+        assert!(period > 0.0, "Period must be positive");
+
+        Self { period, one_period }
+    }
+
+    /// Period $T$.
+    pub fn period(&self) -> f64 {
+        self.period
+    }
+
+    /// Evaluate the transform $F(s) = \frac{G(s)}{1 - e^{-sT}}$ of the periodic function.
+    pub fn evaluate(&self, s: Complex<f64>) -> Complex<f64> {
+        self.one_period.evaluate(s) / (1.0 - (-s * self.period).exp())
+    }
+
+    /// Calculate the Laplace inversion at time `t` by inverting the first period at `t mod T`.
+    ///
+    /// At multiples of the period this is the limit from the left, like at the end of the first period.
+    ///
+    /// Maximum number of evaluations per term is given by [`crate::coefficients::max_evaluations`].
+    pub fn invert(&self, t: f64, max_function_evals: usize) -> f64 {
+        assert!(t > 0.0, "Time must be positive");
+
+        // Phase in `(0, T]`, the terms ending the period are delayed by `T` so they don't contribute
+        let phase = t - ((t / self.period).ceil() - 1.0) * self.period;
+
+        self.one_period.invert(phase, max_function_evals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn periodic_functions() {
+        // Square wave jumps in the middle of the period
+        let square = PeriodicTransform::new(
+            2.0,
+            DelayedTransform::new()
+                .with_term(0.0, |s| 1.0 / s)
+                .with_term(1.0, |s| -1.0 / s),
+        );
+        // Sawtooth `t / T` drops back to zero at the end of the period
+        let period = 1.5;
+        let sawtooth = PeriodicTransform::new(
+            period,
+            DelayedTransform::new()
+                .with_term(0.0, move |s| 1.0 / (period * s * s))
+                .with_term(period, move |s| -1.0 / (period * s * s) - 1.0 / s),
+        );
+
+        for t in [0.3, 1.2, 7.9, 100.2, 1234.56] {
+            let expected = f64::from(u8::from(t % 2.0 < 1.0));
+            assert!(approx::relative_eq!(
+                square.invert(t, 50),
+                expected,
+                epsilon = 1e-8
+            ));
+
+            let expected = (t % period) / period;
+            assert!(approx::relative_eq!(
+                sawtooth.invert(t, 50),
+                expected,
+                epsilon = 1e-8
+            ));
+        }
+
+        // Inverting the periodic transform directly fails after many periods, even with far more evaluations
+        let naive = crate::laplace_inversion(|s| square.evaluate(s), 1234.56, 500);
+        assert!((naive - 1.0).abs() > 0.1);
+
+        // Left limit at the end of every period
+        assert!(approx::relative_eq!(
+            sawtooth.invert(3.0 * period, 50),
+            1.0,
+            epsilon = 1e-8
+        ));
+    }
+}
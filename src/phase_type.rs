@@ -0,0 +1,106 @@
+//! Density and distribution functions of phase-type distributions.
+//!
+//! A phase-type distribution with initial probability vector $\alpha$ and sub-generator matrix $T$ has the Laplace-Stieltjes transform
+//!
+//! $$
+//! f^*(s) = \alpha (sI - T)^{-1} t^0 + (1 - \alpha \mathbf{1}), \quad t^0 = -T \mathbf{1}
+//! $$
+//!
+//! which is evaluated with a small LU solve at each node and inverted.
+
+use nalgebra::{Complex, DMatrix, DVector};
+
+/// Evaluate the Laplace transform $\alpha (sI - T)^{-1} t^0$ of the density of a phase-type distribution.
+///
+/// The probability mass at zero $1 - \alpha \mathbf{1}$ is not part of the density.
+pub fn phase_type_transform(
+    alpha: &DVector<f64>,
+    t_matrix: &DMatrix<f64>,
+    s: Complex<f64>,
+) -> Complex<f64> {
+    assert!(t_matrix.is_square(), "Sub-generator matrix must be square");
+    assert_eq!(
+        alpha.len(),
+        t_matrix.nrows(),
+        "Initial probability vector must have an entry for each phase"
+    );
+
+    let exit_rates = -t_matrix * DVector::from_element(t_matrix.ncols(), 1.0);
+    let resolvent = DMatrix::<Complex<f64>>::identity(t_matrix.nrows(), t_matrix.ncols()) * s
+        - t_matrix.map(Complex::from);
+    let solution = resolvent
+        .lu()
+        .solve(&exit_rates.map(Complex::from))
+        .expect("Resolvent of a sub-generator matrix is singular");
+
+    alpha.map(Complex::from).dot(&solution)
+}
+
+/// Calculate the density of a phase-type distribution at time `t`.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use nalgebra::{DMatrix, DVector};
+///
+/// // Exponential distribution with rate 2
+/// let alpha = DVector::from_vec(vec![1.0]);
+/// let t_matrix = DMatrix::from_vec(1, 1, vec![-2.0]);
+/// let result = iltcme::phase_type::phase_type_pdf(&alpha, &t_matrix, 1.0, 50);
+/// approx::assert_relative_eq!(result, 2.0 * (-2.0_f64).exp(), epsilon = 0.001);
+/// # }
+/// ```
+pub fn phase_type_pdf(
+    alpha: &DVector<f64>,
+    t_matrix: &DMatrix<f64>,
+    t: f64,
+    max_function_evals: usize,
+) -> f64 {
+    crate::laplace_inversion(
+        |s| phase_type_transform(alpha, t_matrix, s),
+        t,
+        max_function_evals,
+    )
+}
+
+/// Calculate the cumulative distribution function of a phase-type distribution at time `t`.
+///
+/// Includes the probability mass at zero.
+pub fn phase_type_cdf(
+    alpha: &DVector<f64>,
+    t_matrix: &DMatrix<f64>,
+    t: f64,
+    max_function_evals: usize,
+) -> f64 {
+    let mass_at_zero = 1.0 - alpha.sum();
+
+    mass_at_zero
+        + crate::laplace_inversion(
+            |s| phase_type_transform(alpha, t_matrix, s) / s,
+            t,
+            max_function_evals,
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn erlang() {
+        // Erlang distribution with two phases and rate 3, starting in the first phase with probability 0.8
+        let alpha = DVector::from_vec(vec![0.8, 0.0]);
+        let t_matrix = DMatrix::from_row_slice(2, 2, &[-3.0, 3.0, 0.0, -3.0]);
+
+        for t in [0.1, 0.5, 1.0, 2.0] {
+            let pdf = phase_type_pdf(&alpha, &t_matrix, t, 100);
+            let cdf = phase_type_cdf(&alpha, &t_matrix, t, 100);
+            let expected_pdf = 0.8 * 9.0 * t * (-3.0 * t).exp();
+            let expected_cdf = 0.2 + 0.8 * (1.0 - (-3.0 * t).exp() * (1.0 + 3.0 * t));
+
+            assert!(approx::relative_eq!(pdf, expected_pdf, epsilon = 1e-4));
+            assert!(approx::relative_eq!(cdf, expected_cdf, epsilon = 1e-4));
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! Piecewise linear input signals, like ramps, pulses and loading schedules.
+//!
+//! A signal that's linear between breakpoints $t_i$ is a sum of delayed steps and ramps
+//!
+//! $$
+//! f(t) = \sum_i \left( J_i + K_i (t - t_i) \right) H(t - t_i)
+//! $$
+//!
+//! where $J_i$ is the jump and $K_i$ the change of the slope at the breakpoint, so its transform is
+//!
+//! $$
+//! F(s) = \sum_i e^{-s t_i} \left( \frac{J_i}{s} + \frac{K_i}{s^2} \right)
+//! $$
+//!
+//! The delays are declared in a [`TransformExpr`], so responses are inverted with the breakpoints shifted exactly instead of through the delay factors.
+
+use nalgebra::Complex;
+
+use crate::combinators::{delayed, TransformExpr};
+
+/// Signal that's linear between breakpoints, zero before the first one and constant after the last one.
+///
+/// Two breakpoints at the same time make a jump.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::piecewise::PiecewiseLinear;
+///
+/// // Load ramped up to one in the first second and removed after five seconds
+/// let load = PiecewiseLinear::new()
+///     .with_point(0.0, 0.0)
+///     .with_point(1.0, 1.0)
+///     .with_point(5.0, 1.0)
+///     .with_point(5.0, 0.0);
+/// assert_eq!(load.value(0.5), 0.5);
+///
+/// // Response of a first-order system with a time constant of one, when the load is removed
+/// let response = load.response(|s| 1.0 / (s + 1.0), &[5.0], 100);
+/// approx::assert_relative_eq!(response[0], 1.0 - (-4.0_f64).exp() + (-5.0_f64).exp(), epsilon = 1e-4);
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PiecewiseLinear {
+    /// Time and value of each breakpoint, in order of time.
+    points: Vec<(f64, f64)>,
+}
+
+impl PiecewiseLinear {
+    /// Construct without any breakpoints, which is zero at all times.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Step to `height` at time `start`.
+    pub fn step(start: f64, height: f64) -> Self {
+        Self::new().with_point(start, 0.0).with_point(start, height)
+    }
+
+    /// Ramp from zero at time `start` to `height` at time `end`, constant afterwards.
+    pub fn ramp(start: f64, end: f64, height: f64) -> Self {
+        Self::new().with_point(start, 0.0).with_point(end, height)
+    }
+
+    /// Rectangular pulse with `height` from time `start` until time `end`.
+    pub fn pulse(start: f64, end: f64, height: f64) -> Self {
+        Self::step(start, height)
+            .with_point(end, height)
+            .with_point(end, 0.0)
+    }
+
+    /// Add a breakpoint with `value` at `time`, which can't be before the previous breakpoint.
+    pub fn with_point(mut self, time: f64, value: f64) -> Self {
+        assert!(time >= 0.0, "Breakpoint can't be at a negative time");
+        assert!(
+            self.points.last().is_none_or(|(last, _)| time >= *last),
+            "Breakpoints must be in order of time"
+        );
+
+        self.points.push((time, value));
+
+        self
+    }
+
+    /// Time and value of each breakpoint.
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    /// Value at time `t`, the value after the jump at a breakpoint.
+    pub fn value(&self, t: f64) -> f64 {
+        self.changes()
+            .into_iter()
+            .filter(|(time, _, _)| t >= *time)
+            .map(|(time, jump, slope)| jump + slope * (t - time))
+            .sum()
+    }
+
+    /// Evaluate the transform including the delay factors in the Laplace domain.
+    pub fn evaluate(&self, s: Complex<f64>) -> Complex<f64> {
+        self.changes()
+            .into_iter()
+            .map(|(time, jump, slope)| (-s * time).exp() * (jump / s + slope / (s * s)))
+            .sum()
+    }
+
+    /// Transform as an expression with a delayed term for each breakpoint.
+    pub fn to_expr(&self) -> TransformExpr<'static> {
+        self.changes().into_iter().fold(
+            TransformExpr::new(|_| Complex::from(0.0)),
+            |expr, (time, jump, slope)| expr + delayed(move |s| jump / s + slope / (s * s), time),
+        )
+    }
+
+    /// Response at each of the times of a system with the transfer function `laplace_func` to the signal.
+    ///
+    /// Every breakpoint is shifted exactly, the delay-free responses use the CME method with at most `max_function_evals` evaluations per breakpoint.
+    pub fn response(
+        &self,
+        laplace_func: impl Fn(Complex<f64>) -> Complex<f64>,
+        times: &[f64],
+        max_function_evals: usize,
+    ) -> Vec<f64> {
+        let response = self.to_expr() * TransformExpr::new(laplace_func);
+
+        times
+            .iter()
+            .map(|t| response.invert(*t, max_function_evals))
+            .collect()
+    }
+
+    /// Time, jump and change of the slope at each breakpoint.
+    fn changes(&self) -> Vec<(f64, f64, f64)> {
+        let (mut value, mut slope, mut previous) = (0.0, 0.0, 0.0);
+
+        self.points
+            .iter()
+            .enumerate()
+            .map(|(i, &(time, point_value))| {
+                let jump = point_value - (value + slope * (time - previous));
+                // A breakpoint at the same time only jumps, the next one sets the slope
+                let next_slope = match self.points.get(i + 1) {
+                    Some(&(next, next_value)) if next > time => {
+                        (next_value - point_value) / (next - time)
+                    }
+                    Some(_) => slope,
+                    None => 0.0,
+                };
+                let change = (time, jump, next_slope - slope);
+
+                (value, slope, previous) = (point_value, next_slope, time);
+
+                change
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loading_schedule() {
+        // Trapezoid with a jump down, and a jump to a ramp
+        let signal = PiecewiseLinear::new()
+            .with_point(0.5, 0.0)
+            .with_point(1.5, 2.0)
+            .with_point(3.0, 2.0)
+            .with_point(3.0, 1.0)
+            .with_point(4.0, 0.0)
+            .with_point(4.0, -1.0)
+            .with_point(5.0, 0.0);
+        for (t, expected) in [
+            (0.2, 0.0),
+            (1.0, 1.0),
+            (2.0, 2.0),
+            (3.0, 1.0),
+            (3.5, 0.5),
+            (4.0, -1.0),
+            (4.5, -0.5),
+            (8.0, 0.0),
+        ] {
+            assert_eq!(signal.value(t), expected);
+            if ![3.0, 4.0].contains(&t) {
+                assert!(approx::relative_eq!(
+                    signal.to_expr().invert(t, 50),
+                    expected,
+                    epsilon = 1e-8
+                ));
+            }
+        }
+
+        // Transform of the ramp and the pulse
+        let s: Complex<f64> = Complex::new(0.7, 1.3);
+        let ramp = 2.0 * ((-s).exp() - (-3.0 * s).exp()) / (s * s);
+        assert!((PiecewiseLinear::ramp(1.0, 3.0, 4.0).evaluate(s) - ramp).norm() < 1e-14);
+        let pulse = 4.0 * ((-s).exp() - (-3.0 * s).exp()) / s;
+        assert!((PiecewiseLinear::pulse(1.0, 3.0, 4.0).evaluate(s) - pulse).norm() < 1e-14);
+        assert_eq!(PiecewiseLinear::step(1.0, 2.0).value(1.0), 2.0);
+        assert_eq!(PiecewiseLinear::new().value(1.0), 0.0);
+
+        // Response of an integrator is the integral of the signal
+        let pulse = PiecewiseLinear::pulse(1.0, 2.0, 3.0);
+        let response = pulse.response(|s| 1.0 / s, &[0.5, 1.5, 2.5], 50);
+        for (result, expected) in response.into_iter().zip([0.0, 1.5, 3.0]) {
+            assert!(approx::relative_eq!(result, expected, epsilon = 1e-8));
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Improve the accuracy of the inversion by subtracting known poles of the Laplace transform.
+//!
+//! Transforms that are dominated by a few poles, especially poles with a positive real part, are hard to approximate with the CME method.
+//! When the poles and their residues are known, the corresponding exponential terms can be inverted exactly and only the smooth remainder has to be inverted numerically:
+//!
+//! $$
+//! f(t) = \sum_k \frac{r_k t^{m_k - 1}}{(m_k - 1)!} e^{p_k t} + \mathcal{L}^{-1}\left[F(s) - \sum_k \frac{r_k}{(s - p_k)^{m_k}}\right](t)
+//! $$
+
+use nalgebra::Complex;
+
+/// Pole of a Laplace transform, contributing the term $\frac{r}{(s - p)^m}$ to it.
+///
+/// Complex poles must be supplied together with their complex conjugate so the inverse stays real.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pole {
+    /// Location $p$ of the pole.
+    pub location: Complex<f64>,
+    /// Residue $r$ of the pole, or the partial fraction coefficient for poles of higher order.
+    pub residue: Complex<f64>,
+    /// Order $m$ of the pole, must be at least one.
+    pub order: u32,
+}
+
+impl Pole {
+    /// Simple pole with a residue.
+    pub fn new(location: impl Into<Complex<f64>>, residue: impl Into<Complex<f64>>) -> Self {
+        Self::with_order(location, residue, 1)
+    }
+
+    /// Pole of a higher order.
+    pub fn with_order(
+        location: impl Into<Complex<f64>>,
+        residue: impl Into<Complex<f64>>,
+        order: u32,
+    ) -> Self {
+        assert!(order > 0, "Order of a pole must be at least one");
+
+        Self {
+            location: location.into(),
+            residue: residue.into(),
+            order,
+        }
+    }
+
+    /// Evaluate the term $\frac{r}{(s - p)^m}$ in the Laplace domain.
+    pub fn transform(&self, s: Complex<f64>) -> Complex<f64> {
+        self.residue / (s - self.location).powu(self.order)
+    }
+
+    /// Evaluate the exact inverse $\frac{r t^{m - 1}}{(m - 1)!} e^{p t}$ of the term.
+    pub fn inverse(&self, t: f64) -> Complex<f64> {
+        let factorial = (1..self.order).map(f64::from).product::<f64>();
+
+        self.residue * t.powi(self.order as i32 - 1) / factorial * (self.location * t).exp()
+    }
+}
+
+/// Calculate the Laplace inversion using the CME method after subtracting known poles.
+///
+/// The poles are inverted exactly, only the remainder of the transform is inverted numerically.
+///
+/// Maximum number of evaluations is given by [`crate::coefficients::max_evaluations`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::poles::{laplace_inversion_with_poles, Pole};
+///
+/// // The transform of `e^(2t) + sin(t)` has a pole at `s = 2` with residue 1
+/// let result = laplace_inversion_with_poles(
+///     |s| 1.0 / (s - 2.0) + 1.0 / (s.powi(2) + 1.0),
+///     &[Pole::new(2.0, 1.0)],
+///     3.0,
+///     50,
+/// );
+/// approx::assert_relative_eq!(result, 6.0_f64.exp() + 3.0_f64.sin(), epsilon = 0.001);
+/// # }
+/// ```
+pub fn laplace_inversion_with_poles(
+    mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+    poles: &[Pole],
+    t: f64,
+    max_function_evals: usize,
+) -> f64 {
+    let remainder = crate::laplace_inversion_mut(
+        |s| {
+            poles
+                .iter()
+                .fold(laplace_func(s), |value, pole| value - pole.transform(s))
+        },
+        t,
+        max_function_evals,
+    );
+
+    remainder
+        + poles
+            .iter()
+            .map(|pole| pole.inverse(t))
+            .sum::<Complex<f64>>()
+            .re
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::ComplexField;
+
+    use super::*;
+
+    #[test]
+    fn subtract_poles() {
+        // e^t + t e^-t + cos(2t) + sin(t)
+        let laplace_func = |s: Complex<f64>| {
+            (s - 1.0).recip()
+                + (s + 1.0).powi(2).recip()
+                + s / (s.powi(2) + 4.0)
+                + (s.powi(2) + 1.0).recip()
+        };
+        let poles = [
+            Pole::new(1.0, 1.0),
+            Pole::with_order(-1.0, 1.0, 2),
+            Pole::new(Complex::new(0.0, 2.0), 0.5),
+            Pole::new(Complex::new(0.0, -2.0), 0.5),
+        ];
+
+        for t in [0.5, 2.0, 5.0, 10.0] {
+            let expected = t.exp() + t * (-t).exp() + (2.0 * t).cos() + t.sin();
+            let result = laplace_inversion_with_poles(laplace_func, &poles, t, 50);
+            let without_poles = crate::laplace_inversion(laplace_func, t, 50);
+
+            assert!(
+                approx::relative_eq!(result, expected, max_relative = 1e-3),
+                "Result {result} differs from {expected} at time {t}"
+            );
+            assert!((result - expected).abs() < (without_poles - expected).abs());
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! Waiting-time distributions of queueing systems.
+
+use nalgebra::{Complex, DMatrix, DVector};
+
+use crate::BoxedLaplaceFunc;
+
+/// M/G/1 queue with Poisson arrivals and a general service-time distribution.
+///
+/// The Laplace-Stieltjes transform of the waiting time follows from the Pollaczek-Khinchine formula
+///
+/// $$
+/// W^*(s) = \frac{(1 - \rho) s}{s - \lambda (1 - B^*(s))}
+/// $$
+///
+/// with arrival rate $\lambda$, utilization $\rho$ and service-time transform $B^*$.
+/// A customer doesn't wait with probability $1 - \rho$, this mass at zero is not part of the density.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::queueing::Mg1Queue;
+/// use nalgebra::ComplexField;
+///
+/// // M/D/1 queue with a deterministic service time of 1 and a utilization of 0.5
+/// let queue = Mg1Queue::new(|s| (-s).exp(), 1.0, 0.5);
+/// let cdf = queue.waiting_time_cdf(&[0.0, 1.0, 2.0], 100);
+/// assert_eq!(cdf[0], 0.5);
+/// approx::assert_relative_eq!(cdf[1], 0.5 * 0.5_f64.exp(), epsilon = 0.01);
+/// # }
+/// ```
+pub struct Mg1Queue<'a> {
+    /// Laplace-Stieltjes transform of the service-time distribution.
+    service_transform: BoxedLaplaceFunc<'a>,
+    /// Mean service time.
+    mean_service_time: f64,
+    /// Fraction of time the server is busy.
+    utilization: f64,
+}
+
+impl<'a> Mg1Queue<'a> {
+    /// Construct from the transform of the service-time distribution, its mean and the utilization of the server.
+    ///
+    /// The utilization must be between zero and one for the queue to be stable.
+    pub fn new(
+        service_transform: impl Fn(Complex<f64>) -> Complex<f64> + 'a,
+        mean_service_time: f64,
+        utilization: f64,
+    ) -> Self {
+        assert!(
+            (0.0..1.0).contains(&utilization),
+            "Utilization must be between zero and one for a stable queue"
+        );
+        assert!(
+            mean_service_time > 0.0,
+            "Mean service time must be positive"
+        );
+
+        Self {
+            service_transform: Box::new(service_transform),
+            mean_service_time,
+            utilization,
+        }
+    }
+
+    /// Arrival rate $\lambda = \rho / E[S]$.
+    pub fn arrival_rate(&self) -> f64 {
+        self.utilization / self.mean_service_time
+    }
+
+    /// Evaluate the Laplace-Stieltjes transform $W^*(s)$ of the waiting time.
+    pub fn waiting_time_transform(&self, s: Complex<f64>) -> Complex<f64> {
+        s * (1.0 - self.utilization)
+            / (s - (1.0 - (self.service_transform)(s)) * self.arrival_rate())
+    }
+
+    /// Calculate the density of the waiting time at each of the times, excluding the mass at zero.
+    pub fn waiting_time_pdf(&self, times: &[f64], max_function_evals: usize) -> Vec<f64> {
+        times
+            .iter()
+            .map(|t| {
+                crate::laplace_inversion(
+                    |s| self.waiting_time_transform(s) - (1.0 - self.utilization),
+                    *t,
+                    max_function_evals,
+                )
+            })
+            .collect()
+    }
+
+    /// Calculate the probability that the waiting time is at most each of the times.
+    pub fn waiting_time_cdf(&self, times: &[f64], max_function_evals: usize) -> Vec<f64> {
+        let mass_at_zero = 1.0 - self.utilization;
+
+        times
+            .iter()
+            .map(|t| {
+                if *t <= 0.0 {
+                    // Only the customers that don't have to wait
+                    return if *t == 0.0 { mass_at_zero } else { 0.0 };
+                }
+
+                mass_at_zero
+                    + crate::laplace_inversion(
+                        |s| (self.waiting_time_transform(s) - mass_at_zero) / s,
+                        *t,
+                        max_function_evals,
+                    )
+            })
+            .collect()
+    }
+}
+
+/// Continuous-time birth-death chain on the states $0, 1, \ldots, n$.
+///
+/// The transient state probabilities $p(t) = p(0) e^{Qt}$ are found by inverting the resolvent of the generator $Q$ for all states at once.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::queueing::BirthDeathChain;
+/// use nalgebra::DVector;
+///
+/// // Empty M/M/1 queue with room for 10 customers
+/// let chain = BirthDeathChain::mm1(1.0, 2.0, 10);
+/// let mut initial = DVector::zeros(11);
+/// initial[0] = 1.0;
+/// let probabilities = chain.transient_probabilities(&initial, 1.5, 100);
+/// approx::assert_relative_eq!(probabilities.sum(), 1.0, epsilon = 1e-6);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BirthDeathChain {
+    /// Rate of going from state `i` to `i + 1`.
+    birth_rates: Vec<f64>,
+    /// Rate of going from state `i + 1` to `i`.
+    death_rates: Vec<f64>,
+}
+
+impl BirthDeathChain {
+    /// Construct from the rates of going up from each state and down to each state.
+    ///
+    /// Both rates must have a length equal to the highest state $n$.
+    pub fn new(birth_rates: &[f64], death_rates: &[f64]) -> Self {
+        assert_eq!(
+            birth_rates.len(),
+            death_rates.len(),
+            "Birth and death rates must have the same length"
+        );
+
+        Self {
+            birth_rates: birth_rates.to_vec(),
+            death_rates: death_rates.to_vec(),
+        }
+    }
+
+    /// M/M/1 queue with an arrival rate, service rate and a maximum number of customers in the system.
+    pub fn mm1(arrival_rate: f64, service_rate: f64, capacity: usize) -> Self {
+        Self::new(&vec![arrival_rate; capacity], &vec![service_rate; capacity])
+    }
+
+    /// Number of states in the chain.
+    pub fn states(&self) -> usize {
+        self.birth_rates.len() + 1
+    }
+
+    /// Tridiagonal generator matrix $Q$ of the chain.
+    pub fn generator(&self) -> DMatrix<f64> {
+        let mut generator = DMatrix::zeros(self.states(), self.states());
+        for (i, (birth, death)) in self.birth_rates.iter().zip(&self.death_rates).enumerate() {
+            generator[(i, i + 1)] = *birth;
+            generator[(i, i)] -= birth;
+            generator[(i + 1, i)] = *death;
+            generator[(i + 1, i + 1)] -= death;
+        }
+
+        generator
+    }
+
+    /// Calculate the probability of being in each state at time `t` starting from the `initial` probabilities.
+    ///
+    /// Maximum number of evaluations is given by [`crate::coefficients::max_evaluations`].
+    pub fn transient_probabilities(
+        &self,
+        initial: &DVector<f64>,
+        t: f64,
+        max_function_evals: usize,
+    ) -> DVector<f64> {
+        assert_eq!(
+            initial.len(),
+            self.states(),
+            "Initial probabilities must have an entry for each state"
+        );
+
+        // Row vector `p(0) e^(Qt)` is the column vector `e^(Q^T t) p(0)^T`
+        crate::state_space::matrix_exponential_action(
+            &self.generator().transpose(),
+            initial,
+            t,
+            max_function_evals,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mm1() {
+        // Exponential service with rate 2 and a utilization of 0.75
+        let (mu, rho) = (2.0, 0.75);
+        let queue = Mg1Queue::new(move |s| mu / (mu + s), 1.0 / mu, rho);
+        let times = [0.1, 0.5, 1.0, 3.0];
+
+        let pdf = queue.waiting_time_pdf(&times, 100);
+        let cdf = queue.waiting_time_cdf(&times, 100);
+        for (i, t) in times.into_iter().enumerate() {
+            let decay = (-mu * (1.0 - rho) * t).exp();
+            assert!(approx::relative_eq!(
+                pdf[i],
+                rho * mu * (1.0 - rho) * decay,
+                epsilon = 1e-4
+            ));
+            assert!(approx::relative_eq!(
+                cdf[i],
+                1.0 - rho * decay,
+                epsilon = 1e-4
+            ));
+        }
+    }
+
+    #[test]
+    fn two_state_chain() {
+        let (birth, death) = (1.5, 0.5);
+        let chain = BirthDeathChain::new(&[birth], &[death]);
+        let initial = DVector::from_vec(vec![1.0, 0.0]);
+
+        for t in [0.2, 1.0, 4.0] {
+            let probabilities = chain.transient_probabilities(&initial, t, 100);
+            let expected = birth / (birth + death) * (1.0 - (-(birth + death) * t).exp());
+
+            assert!(approx::relative_eq!(
+                probabilities[1],
+                expected,
+                epsilon = 1e-4
+            ));
+            assert!(approx::relative_eq!(
+                probabilities.sum(),
+                1.0,
+                epsilon = 1e-6
+            ));
+        }
+    }
+}
@@ -0,0 +1,592 @@
+//! Exact inversion of rational Laplace transforms.
+//!
+//! A rational transform $F(s) = \frac{N(s)}{D(s)}$ can be split into partial fractions using the roots of the denominator, each of which has a known exact inverse.
+//! When the roots can't be determined accurately, for example with clustered roots of a high order, the CME method is used instead.
+//!
+//! Transforms only known from samples can be approximated with a [`RationalFit`] first.
+
+use nalgebra::{Complex, ComplexField, DMatrix, DVector};
+
+use crate::poles::Pole;
+
+/// Default maximum number of function evaluations when falling back to the CME method.
+const DEFAULT_FALLBACK_EVALS: usize = 100;
+
+/// Roots closer than this relative distance are treated as a single root of a higher order.
+const ROOT_CLUSTER_TOLERANCE: f64 = 1e-3;
+
+/// Maximum relative error of the partial fractions before they're considered ill-conditioned.
+const PARTIAL_FRACTION_TOLERANCE: f64 = 1e-8;
+
+/// Rational Laplace transform $F(s) = \frac{N(s)}{D(s)}$ with an exact inverse.
+///
+/// Impulses at $t = 0$ caused by a numerator with a degree greater or equal to the denominator are not part of the inverse, it's only exact for $t > 0$.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::rational::RationalTransform;
+///
+/// // Transform of `t e^-t`
+/// let transform = RationalTransform::new(&[1.0], &[1.0, 2.0, 1.0]);
+/// approx::assert_relative_eq!(transform.inverse(2.0), 2.0 * (-2.0_f64).exp(), epsilon = 1e-12);
+///
+/// // It can also be evaluated as a Laplace transform function
+/// let result = iltcme::laplace_inversion(|s| transform.evaluate(s), 2.0, 50);
+/// approx::assert_relative_eq!(result, 2.0 * (-2.0_f64).exp(), epsilon = 0.001);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RationalTransform {
+    /// Coefficients of the numerator, highest power first.
+    numerator: Vec<f64>,
+    /// Coefficients of the denominator, highest power first.
+    denominator: Vec<f64>,
+    /// Partial fractions of the strictly proper part, `None` when they are ill-conditioned.
+    poles: Option<Vec<Pole>>,
+    /// Maximum number of function evaluations when falling back to the CME method.
+    fallback_evals: usize,
+}
+
+impl RationalTransform {
+    /// Construct from the polynomial coefficients of the numerator and denominator, highest power first.
+    ///
+    /// The coefficients follow the same convention as MATLAB and SciPy, `[1.0, 2.0, 1.0]` is $s^2 + 2 s + 1$.
+    pub fn new(numerator: &[f64], denominator: &[f64]) -> Self {
+        let numerator = strip_leading_zeros(numerator);
+        let denominator = strip_leading_zeros(denominator);
+        assert!(
+            !denominator.is_empty(),
+            "Denominator of a rational transform can't be zero"
+        );
+
+        let poles = partial_fractions(&numerator, &denominator);
+
+        Self {
+            numerator,
+            denominator,
+            poles,
+            fallback_evals: DEFAULT_FALLBACK_EVALS,
+        }
+    }
+
+    /// Set the maximum number of function evaluations used when falling back to the CME method.
+    pub fn with_fallback_evals(mut self, fallback_evals: usize) -> Self {
+        self.fallback_evals = fallback_evals;
+
+        self
+    }
+
+    /// Coefficients of the numerator, highest power first.
+    pub fn numerator(&self) -> &[f64] {
+        &self.numerator
+    }
+
+    /// Coefficients of the denominator, highest power first.
+    pub fn denominator(&self) -> &[f64] {
+        &self.denominator
+    }
+
+    /// Partial fractions of the transform, `None` when finding the roots of the denominator was ill-conditioned.
+    pub fn poles(&self) -> Option<&[Pole]> {
+        self.poles.as_deref()
+    }
+
+    /// Whether the inverse is computed exactly instead of with the CME method.
+    pub fn is_exact(&self) -> bool {
+        self.poles.is_some()
+    }
+
+    /// Evaluate the transform in the Laplace domain.
+    pub fn evaluate(&self, s: Complex<f64>) -> Complex<f64> {
+        polynomial(&self.numerator, s) / polynomial(&self.denominator, s)
+    }
+
+    /// Calculate the inverse of the transform at time `t`.
+    ///
+    /// Falls back to the CME method when the partial fractions are ill-conditioned.
+    pub fn inverse(&self, t: f64) -> f64 {
+        match &self.poles {
+            Some(poles) => {
+                poles
+                    .iter()
+                    .map(|pole| pole.inverse(t))
+                    .sum::<Complex<f64>>()
+                    .re
+            }
+            None => crate::laplace_inversion(|s| self.evaluate(s), t, self.fallback_evals),
+        }
+    }
+}
+
+/// Rational function in barycentric form fitted to samples of a transform with the AAA algorithm of Nakatsukasa, Sète and Trefethen.
+///
+/// $$
+/// r(s) = \frac{\sum_k \frac{w_k f_k}{s - z_k}}{\sum_k \frac{w_k}{s - z_k}}
+/// $$
+///
+/// Unlike interpolating between the samples this can be evaluated anywhere in the complex plane, which is needed since the CME nodes are never at the sampled points.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::rational::RationalFit;
+/// use nalgebra::Complex;
+///
+/// // Samples of the transform of `e^-t` on the real axis
+/// let samples = (1..=20)
+///     .map(|k| {
+///         let s = Complex::from(k as f64 / 4.0);
+///         (s, 1.0 / (s + 1.0))
+///     })
+///     .collect::<Vec<_>>();
+/// let fit = RationalFit::fit(&samples, 1e-12, 10);
+/// let result = iltcme::laplace_inversion(|s| fit.evaluate(s), 1.0, 50);
+/// approx::assert_relative_eq!(result, (-1.0_f64).exp(), epsilon = 0.001);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RationalFit {
+    /// Support points $z_k$.
+    support: Vec<Complex<f64>>,
+    /// Transform values $f_k$ at the support points.
+    values: Vec<Complex<f64>>,
+    /// Barycentric weights $w_k$.
+    weights: Vec<Complex<f64>>,
+    /// Largest absolute error at the samples.
+    max_error: f64,
+}
+
+impl RationalFit {
+    /// Fit samples of the transform until the largest error is below `tolerance` relative to the largest value, using at most `max_support_points` support points.
+    ///
+    /// For a real function the transform at the conjugate is the conjugate, so conjugate samples are added to keep the fit real.
+    /// The samples must be finite.
+    pub fn fit(
+        samples: &[(Complex<f64>, Complex<f64>)],
+        tolerance: f64,
+        max_support_points: usize,
+    ) -> Self {
+        let mut points = Vec::with_capacity(samples.len() * 2);
+        for (s, value) in samples {
+            points.push((*s, *value));
+            if s.im != 0.0 {
+                points.push((s.conj(), value.conj()));
+            }
+        }
+
+        let scale = points
+            .iter()
+            .map(|(_, value)| value.modulus())
+            .fold(0.0, f64::max);
+        let mean =
+            points.iter().map(|(_, value)| value).sum::<Complex<f64>>() / points.len() as f64;
+
+        let mut fit = Self {
+            support: Vec::new(),
+            values: Vec::new(),
+            weights: Vec::new(),
+            max_error: f64::INFINITY,
+        };
+        // Samples that aren't support points with their current approximation
+        let mut remaining = points
+            .into_iter()
+            .map(|(s, value)| (s, value, mean))
+            .collect::<Vec<_>>();
+
+        // Keep the least squares problem overdetermined
+        while fit.support.len() < max_support_points && fit.support.len() < remaining.len() {
+            // The sample with the largest error becomes a support point
+            let (worst, _) = remaining
+                .iter()
+                .enumerate()
+                .map(|(i, (_, value, approximation))| (i, (value - approximation).modulus()))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap();
+            let (s, value, _) = remaining.swap_remove(worst);
+            fit.support.push(s);
+            fit.values.push(value);
+
+            // Weights minimizing the linearized residual are the smallest right singular vector of the Loewner matrix
+            let cauchy = DMatrix::from_fn(remaining.len(), fit.support.len(), |i, k| {
+                (remaining[i].0 - fit.support[k]).recip()
+            });
+            let loewner = DMatrix::from_fn(remaining.len(), fit.support.len(), |i, k| {
+                (remaining[i].1 - fit.values[k]) * cauchy[(i, k)]
+            });
+            let svd = loewner.svd(false, true);
+            let v_t = svd.v_t.expect("Right singular vectors were computed");
+            let smallest = svd.singular_values.imin();
+            fit.weights = v_t.row(smallest).iter().map(|w| w.conj()).collect();
+
+            let weights = DVector::from_column_slice(&fit.weights);
+            let weighted_values = DVector::from_iterator(
+                fit.weights.len(),
+                fit.weights.iter().zip(&fit.values).map(|(w, f)| w * f),
+            );
+            let numerator = &cauchy * weighted_values;
+            let denominator = &cauchy * weights;
+
+            fit.max_error = 0.0;
+            for (i, (_, value, approximation)) in remaining.iter_mut().enumerate() {
+                *approximation = numerator[i] / denominator[i];
+                fit.max_error = fit.max_error.max((*value - *approximation).modulus());
+            }
+            if fit.max_error <= tolerance * scale {
+                break;
+            }
+        }
+
+        fit
+    }
+
+    /// Number of support points.
+    pub fn terms(&self) -> usize {
+        self.support.len()
+    }
+
+    /// Largest absolute error at the samples that aren't support points.
+    pub fn max_error(&self) -> f64 {
+        self.max_error
+    }
+
+    /// Evaluate the fit at `s`.
+    pub fn evaluate(&self, s: Complex<f64>) -> Complex<f64> {
+        let mut numerator = Complex::new(0.0, 0.0);
+        let mut denominator = Complex::new(0.0, 0.0);
+        for ((z, f), w) in self.support.iter().zip(&self.values).zip(&self.weights) {
+            if s == *z {
+                return *f;
+            }
+
+            let c = w / (s - z);
+            numerator += c * f;
+            denominator += c;
+        }
+
+        numerator / denominator
+    }
+
+    /// Simple poles of the fit with their residues, one less than the number of support points.
+    ///
+    /// The poles are the zeros of the denominator, the finite eigenvalues of the pencil $E - s B$ with
+    ///
+    /// $$
+    /// E = \begin{pmatrix} 0 & w^T \\ 1 & \operatorname{diag}(z) \end{pmatrix}, \quad B = \begin{pmatrix} 0 & 0 \\ 0 & I \end{pmatrix}
+    /// $$
+    ///
+    /// computed as the largest eigenvalues $\mu = 1 / (s - a)$ of $(E - a B)^{-1} B$ for a shift $a$ away from the real axis.
+    /// A constant term, the value of the fit at infinity, isn't part of the partial fractions.
+    pub fn poles(&self) -> Vec<Pole> {
+        let size = self.support.len() + 1;
+        if size <= 2 {
+            return Vec::new();
+        }
+
+        let radius = self.support.iter().map(|z| z.modulus()).fold(1.0, f64::max);
+        let shift = Complex::new(0.0, 0.5) * radius;
+        let mut shifted = DMatrix::zeros(size, size);
+        let mut b = DMatrix::zeros(size, size);
+        for (k, (z, w)) in self.support.iter().zip(&self.weights).enumerate() {
+            shifted[(0, k + 1)] = *w;
+            shifted[(k + 1, 0)] = Complex::new(1.0, 0.0);
+            shifted[(k + 1, k + 1)] = z - shift;
+            b[(k + 1, k + 1)] = Complex::new(1.0, 0.0);
+        }
+        let Some(inverse) = shifted.try_inverse() else {
+            return Vec::new();
+        };
+
+        // Two of the eigenvalues are zero, for the infinite eigenvalues of the pencil
+        let mut eigenvalues = (inverse * b)
+            .eigenvalues()
+            .map(|eigenvalues| eigenvalues.iter().copied().collect::<Vec<_>>())
+            .unwrap_or_default();
+        eigenvalues.sort_by(|a, b| b.modulus().total_cmp(&a.modulus()));
+
+        eigenvalues
+            .into_iter()
+            .take(size - 2)
+            .map(|mu| {
+                let location = shift + mu.recip();
+                // Residue of `N / D` at a simple zero of `D`
+                let (numerator, slope) = self
+                    .support
+                    .iter()
+                    .zip(&self.weights)
+                    .zip(&self.values)
+                    .fold(
+                        (Complex::new(0.0, 0.0), Complex::new(0.0, 0.0)),
+                        |(numerator, slope), ((z, w), f)| {
+                            let difference = location - z;
+                            (
+                                numerator + w * f / difference,
+                                slope - w / difference.powi(2),
+                            )
+                        },
+                    );
+
+                Pole::new(location, numerator / slope)
+            })
+            .collect()
+    }
+}
+
+/// Evaluate a polynomial with the highest power first using Horner's method.
+pub(crate) fn polynomial(coefficients: &[f64], s: Complex<f64>) -> Complex<f64> {
+    coefficients
+        .iter()
+        .fold(Complex::new(0.0, 0.0), |acc, coefficient| {
+            acc * s + coefficient
+        })
+}
+
+/// Remove the zero coefficients of the highest powers.
+fn strip_leading_zeros(coefficients: &[f64]) -> Vec<f64> {
+    coefficients
+        .iter()
+        .skip_while(|coefficient| **coefficient == 0.0)
+        .copied()
+        .collect()
+}
+
+/// Remainder of the division of two polynomials with the highest power first.
+fn polynomial_remainder(numerator: &[f64], denominator: &[f64]) -> Vec<f64> {
+    let mut remainder = numerator.to_vec();
+    while remainder.len() >= denominator.len() {
+        let factor = remainder[0] / denominator[0];
+        remainder
+            .iter_mut()
+            .zip(denominator)
+            .for_each(|(r, d)| *r -= factor * d);
+        remainder.remove(0);
+    }
+
+    remainder
+}
+
+/// Coefficients of the derivative of a polynomial with the highest power first.
+fn derivative(coefficients: &[f64]) -> Vec<f64> {
+    let degree = coefficients.len().saturating_sub(1);
+
+    coefficients[..degree]
+        .iter()
+        .enumerate()
+        .map(|(i, coefficient)| coefficient * (degree - i) as f64)
+        .collect()
+}
+
+/// Find the roots of a polynomial with the highest power first as the eigenvalues of its companion matrix.
+///
+/// Roots that are close to each other are grouped into a single root with a multiplicity.
+fn roots(coefficients: &[f64]) -> Vec<(Complex<f64>, u32)> {
+    let degree = coefficients.len() - 1;
+    if degree == 0 {
+        return Vec::new();
+    }
+
+    let mut companion = DMatrix::zeros(degree, degree);
+    for (column, coefficient) in coefficients[1..].iter().enumerate() {
+        companion[(0, column)] = -coefficient / coefficients[0];
+    }
+    for row in 1..degree {
+        companion[(row, row - 1)] = 1.0;
+    }
+
+    // Group roots that are close to each other, summing the locations so the average can be taken
+    let mut clusters: Vec<(Complex<f64>, u32)> = Vec::new();
+    for root in companion.complex_eigenvalues().iter() {
+        match clusters.iter_mut().find(|(location, multiplicity)| {
+            (*location / *multiplicity as f64 - root).modulus()
+                <= ROOT_CLUSTER_TOLERANCE * root.modulus().max(1.0)
+        }) {
+            Some((location, multiplicity)) => {
+                *location += root;
+                *multiplicity += 1;
+            }
+            None => clusters.push((*root, 1)),
+        }
+    }
+
+    clusters
+        .into_iter()
+        .map(|(location, multiplicity)| {
+            // A root with multiplicity `m` is a simple root of derivative `m - 1`, polish it with a few Newton iterations
+            let function = (1..multiplicity).fold(coefficients.to_vec(), |f, _| derivative(&f));
+            let slope = derivative(&function);
+            let mut root = location / multiplicity as f64;
+            for _ in 0..3 {
+                let step = polynomial(&function, root) / polynomial(&slope, root);
+                if !(step.re.is_finite() && step.im.is_finite()) {
+                    break;
+                }
+                root -= step;
+            }
+
+            (root, multiplicity)
+        })
+        .collect()
+}
+
+/// Taylor coefficients of a polynomial with the highest power first around a point.
+fn taylor_coefficients(coefficients: &[f64], point: Complex<f64>) -> Vec<Complex<f64>> {
+    // Repeated synthetic division
+    let mut remaining = coefficients
+        .iter()
+        .map(|coefficient| Complex::from(*coefficient))
+        .collect::<Vec<_>>();
+    let mut taylor = Vec::with_capacity(coefficients.len());
+    while !remaining.is_empty() {
+        for i in 1..remaining.len() {
+            let previous = remaining[i - 1];
+            remaining[i] += previous * point;
+        }
+        taylor.push(remaining.pop().unwrap());
+    }
+
+    taylor
+}
+
+/// Split the strictly proper part of a rational function into partial fractions.
+///
+/// Returns `None` when the partial fractions don't reproduce the rational function accurately.
+fn partial_fractions(numerator: &[f64], denominator: &[f64]) -> Option<Vec<Pole>> {
+    let remainder = polynomial_remainder(numerator, denominator);
+    let clusters = roots(denominator);
+
+    let mut poles = Vec::new();
+    for (i, (location, multiplicity)) in clusters.iter().enumerate() {
+        // Taylor coefficients of the denominator without this root around the root
+        let mut others = vec![Complex::from(denominator[0])];
+        for (j, (other, other_multiplicity)) in clusters.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            for _ in 0..*other_multiplicity {
+                // Multiply by `(location - other) + h`
+                let offset = location - other;
+                let mut product = vec![Complex::new(0.0, 0.0); others.len() + 1];
+                for (k, coefficient) in others.iter().enumerate() {
+                    product[k] += coefficient * offset;
+                    product[k + 1] += coefficient;
+                }
+                others = product;
+            }
+        }
+
+        // Divide the Taylor series of the numerator by the other factors
+        let numerator_taylor = taylor_coefficients(&remainder, *location);
+        let mut quotient: Vec<Complex<f64>> = Vec::with_capacity(*multiplicity as usize);
+        for k in 0..*multiplicity as usize {
+            let mut value = numerator_taylor
+                .get(k)
+                .copied()
+                .unwrap_or(Complex::new(0.0, 0.0));
+            for j in 1..=k.min(others.len() - 1) {
+                value -= others[j] * quotient[k - j];
+            }
+            quotient.push(value / others[0]);
+        }
+
+        poles.extend(
+            quotient
+                .into_iter()
+                .enumerate()
+                .map(|(k, residue)| Pole::with_order(*location, residue, multiplicity - k as u32)),
+        );
+    }
+
+    // Verify the partial fractions at a few points scaled to the size of the roots
+    let scale = clusters
+        .iter()
+        .map(|(location, _)| location.modulus())
+        .fold(1.0, f64::max);
+    let accurate = [
+        Complex::new(0.7, 1.3),
+        Complex::new(1.9, -0.4),
+        Complex::new(0.3, 3.1),
+    ]
+    .into_iter()
+    .map(|point| point * scale)
+    .all(|s| {
+        let expected = polynomial(&remainder, s) / polynomial(denominator, s);
+        let approximation = poles
+            .iter()
+            .map(|pole| pole.transform(s))
+            .sum::<Complex<f64>>();
+
+        (approximation - expected).modulus()
+            <= PARTIAL_FRACTION_TOLERANCE * expected.modulus().max(f64::MIN_POSITIVE)
+    });
+
+    accurate.then_some(poles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_inversions() {
+        type Case<'a> = (&'a [f64], &'a [f64], fn(f64) -> f64);
+        let cases: [Case; 5] = [
+            // Exponential
+            (&[1.0], &[1.0, 1.0], |t| (-t).exp()),
+            // Sine
+            (&[1.0], &[1.0, 0.0, 1.0], |t| t.sin()),
+            // Repeated root
+            (&[1.0], &[1.0, 3.0, 3.0, 1.0], |t| {
+                t.powi(2) / 2.0 * (-t).exp()
+            }),
+            // Step response of a damped oscillator
+            (&[4.0], &[1.0, 2.0, 4.0, 0.0], |t| {
+                let wd = 3.0_f64.sqrt();
+                1.0 - (-t).exp() * ((wd * t).cos() + (wd * t).sin() / wd)
+            }),
+            // Improper transform `1 + 1 / (s + 2)` has an impulse at zero
+            (&[1.0, 3.0], &[1.0, 2.0], |t| (-2.0 * t).exp()),
+        ];
+
+        for (numerator, denominator, expected) in cases {
+            let transform = RationalTransform::new(numerator, denominator);
+            assert!(transform.is_exact());
+
+            for t in [0.1, 1.0, 2.5, 10.0] {
+                let result = transform.inverse(t);
+                assert!(
+                    approx::relative_eq!(result, expected(t), epsilon = 1e-10, max_relative = 1e-8),
+                    "Inversion of {numerator:?} / {denominator:?} failed at {t}: {result} != {}",
+                    expected(t)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fallback() {
+        // Ill-conditioned root of a high multiplicity
+        let denominator = (0..12).fold(vec![1.0], |polynomial, _| {
+            let mut product = polynomial.clone();
+            product.push(0.0);
+            product
+                .iter_mut()
+                .skip(1)
+                .zip(&polynomial)
+                .for_each(|(p, q)| *p += q);
+            product
+        });
+        let transform = RationalTransform::new(&[1.0], &denominator);
+        assert!(!transform.is_exact());
+
+        // `t^11 e^-t / 11!`
+        let t = 12.0_f64;
+        let expected = t.powi(11) * (-t).exp() / (1..=11).map(f64::from).product::<f64>();
+        assert!(approx::relative_eq!(
+            transform.inverse(t),
+            expected,
+            epsilon = 1e-3
+        ));
+    }
+}
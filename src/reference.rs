@@ -0,0 +1,136 @@
+//! Slow but trusted evaluation of the CME formula in extended precision, to validate results against.
+//!
+//! The weights and nodes are computed directly from the published parameters of the distribution in double-double precision, and the weighted sum is accumulated in the same precision.
+//! This shares none of the precomputed tables of the fast path, only the transform is evaluated in `f64`.
+//!
+//! # Example
+//!
+//! ```rust
+//! # fn main() {
+//! use iltcme::{coefficients::CmeParams, reference};
+//!
+//! // Exponential distribution, the lowest order
+//! let params = [CmeParams {
+//!     n: 1,
+//!     a: vec![-2.4865],
+//!     b: vec![-0.8713],
+//!     c: 2.6348,
+//!     omega: 1.0359,
+//!     mu1: 2.2571,
+//!     cv2: 0.2009,
+//! }];
+//! let steepest = reference::steepest(&params, 10).unwrap();
+//! let result = reference::laplace_inversion(|s| 1.0 / (s + 1.0), 1.0, steepest);
+//! approx::assert_relative_eq!(result, (-1.0_f64).exp(), epsilon = 0.1);
+//! # }
+//! ```
+
+use nalgebra::Complex;
+
+use crate::{coefficients::CmeParams, double_double::DoubleDouble};
+
+/// Steepest distribution that uses at most `max_function_evals` evaluations, the one with the lowest squared coefficient of variation.
+///
+/// Returns [`None`] when no distribution is small enough.
+pub fn steepest(params: &[CmeParams], max_function_evals: usize) -> Option<&CmeParams> {
+    params
+        .iter()
+        .filter(|p| p.n < max_function_evals)
+        .min_by(|a, b| a.cv2.total_cmp(&b.cv2))
+}
+
+/// Calculate the Laplace inversion for a function at time `t` with a single distribution in double-double precision.
+///
+/// Evaluates the transform at the $n + 1$ nodes $\beta_k / t$ with
+///
+/// $$
+/// \eta_0 = c \mu_1, \quad \beta_0 = \mu_1, \quad \eta_k = \mu_1 (a_k + i b_k), \quad \beta_k = \mu_1 (1 + i k \omega)
+/// $$
+///
+/// Each distribution must have `n` cosine and sine coefficients.
+pub fn laplace_inversion(
+    mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+    t: f64,
+    params: &CmeParams,
+) -> f64 {
+    assert!(
+        params.a.len() == params.n && params.b.len() == params.n,
+        "Each distribution must have a cosine and sine coefficient for each order"
+    );
+
+    let t = DoubleDouble::from(t);
+    let mu1 = DoubleDouble::from(params.mu1);
+    // Real part of the weighted value at the node `mu1 (1 + i beta)`
+    let mut term = |eta_re: DoubleDouble, eta_im: DoubleDouble, beta: DoubleDouble| {
+        let s = Complex::new((mu1 / t).to_f64(), (mu1 * beta / t).to_f64());
+        let value = laplace_func(s);
+
+        eta_re * DoubleDouble::from(value.re) - eta_im * DoubleDouble::from(value.im)
+    };
+
+    let first = term(
+        DoubleDouble::product(params.c, params.mu1),
+        DoubleDouble::default(),
+        DoubleDouble::default(),
+    );
+    let sum = params
+        .a
+        .iter()
+        .zip(&params.b)
+        .enumerate()
+        .fold(first, |sum, (k, (a, b))| {
+            let beta = DoubleDouble::product((k + 1) as f64, params.omega);
+            sum + term(
+                DoubleDouble::product(*a, params.mu1),
+                DoubleDouble::product(*b, params.mu1),
+                beta,
+            )
+        });
+
+    (sum / t).to_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coefficients::CmeTable;
+
+    #[test]
+    fn single_distribution() {
+        let params = vec![
+            CmeParams {
+                n: 1,
+                a: vec![-2.4865],
+                b: vec![-0.8713],
+                c: 2.6348,
+                omega: 1.0359,
+                mu1: 2.2571,
+                cv2: 0.2009,
+            },
+            CmeParams {
+                n: 2,
+                a: vec![-1.0, 0.5],
+                b: vec![0.25, -0.5],
+                c: 1.5,
+                omega: 0.75,
+                mu1: 3.0,
+                cv2: 0.1,
+            },
+        ];
+        assert_eq!(steepest(&params, 2), Some(&params[0]));
+        assert_eq!(steepest(&params, 3), Some(&params[1]));
+        assert_eq!(steepest(&params, 1), None);
+
+        // Same formula as the fast path
+        let table = CmeTable::new(params.clone());
+        for t in [0.1, 1.0, 10.0] {
+            let reference = laplace_inversion(|s| 1.0 / (s * s + 1.0), t, &params[1]);
+            let result = table.order(3).unwrap().invert(|s| 1.0 / (s * s + 1.0), t);
+            assert!(approx::relative_eq!(
+                result,
+                reference,
+                max_relative = 1e-14
+            ));
+        }
+    }
+}
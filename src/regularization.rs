@@ -0,0 +1,380 @@
+//! Inversion of noisy samples of the transform on the real axis with Tikhonov regularization, in the style of Provencher's CONTIN.
+//!
+//! Measurements $F_i \approx F(s_i)$ at real points, such as dynamic light scattering or relaxometry data, can't be inverted with closed-form methods.
+//! The Laplace integral is discretized on a grid of times $t_j$ with trapezoidal weights $w_j$, which gives the linear system $K f = F$ with $K_{ij} = w_j e^{-s_i t_j}$.
+//! The system is severely ill-conditioned, so the solution minimizes
+//!
+//! $$
+//! \lVert K f - F \rVert^2 + \lambda^2 \lVert L f \rVert^2
+//! $$
+//!
+//! where $L$ is a [`Penalty`] operator, optionally with the constraint $f \geq 0$.
+//! The regularization parameter $\lambda$ balances the fit against the smoothness of the result, it should grow with the noise level.
+//!
+//! # Example
+//!
+//! ```rust
+//! # fn main() {
+//! use iltcme::regularization::RegularizedInversion;
+//!
+//! // Samples of the transform of `t e^(-t)`
+//! let s = (0..30).map(|i| 0.1 * 100.0_f64.powf(i as f64 / 29.0)).collect::<Vec<_>>();
+//! let samples = s.iter().map(|s| 1.0 / (s + 1.0).powi(2)).collect::<Vec<_>>();
+//!
+//! let times = (0..=100).map(|j| j as f64 * 0.1).collect();
+//! let result = RegularizedInversion::new(times).invert(&s, &samples);
+//! approx::assert_relative_eq!(result[10], (-1.0_f64).exp(), epsilon = 0.05);
+//! # }
+//! ```
+
+use nalgebra::{DMatrix, DVector};
+
+/// Operator $L$ whose norm is penalized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Penalty {
+    /// Identity, penalizing the size of the solution.
+    Identity,
+    /// Second differences, penalizing the curvature of the solution like CONTIN.
+    #[default]
+    Curvature,
+}
+
+impl Penalty {
+    /// Matrix of the operator for a grid of `len` points.
+    pub fn matrix(&self, len: usize) -> DMatrix<f64> {
+        match self {
+            Self::Identity => DMatrix::identity(len, len),
+            Self::Curvature => {
+                DMatrix::from_fn(len.saturating_sub(2), len, |i, j| match j.wrapping_sub(i) {
+                    0 | 2 => 1.0,
+                    1 => -2.0,
+                    _ => 0.0,
+                })
+            }
+        }
+    }
+}
+
+/// Rule choosing the regularization parameter $\lambda$ from the data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParameterChoice {
+    /// Use the given parameter.
+    Fixed(f64),
+    /// Minimize the generalized cross-validation score $m \lVert K f_\lambda - F \rVert^2 / \operatorname{tr}(I - A_\lambda)^2$, where $A_\lambda$ maps the data to the fit.
+    Gcv,
+    /// Take the corner of the L-curve, the point of largest curvature of $(\log \lVert K f_\lambda - F \rVert, \log \lVert L f_\lambda \rVert)$.
+    LCurve,
+}
+
+/// Regularized inversion of noisy transform samples onto a grid of times.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegularizedInversion {
+    /// Increasing times the inverse is recovered at.
+    times: Vec<f64>,
+    /// Regularization parameter.
+    lambda: f64,
+    /// Operator whose norm is penalized.
+    penalty: Penalty,
+    /// Whether the inverse is constrained to be nonnegative.
+    nonnegative: bool,
+}
+
+impl RegularizedInversion {
+    /// Create an inversion onto the increasing `times`.
+    ///
+    /// The default regularization parameter is $10^{-2}$, penalizing the [`Penalty::Curvature`] without constraining the sign.
+    pub fn new(times: Vec<f64>) -> Self {
+        assert!(times.len() >= 2, "Grid needs at least two times");
+        assert!(
+            times.windows(2).all(|pair| pair[0] < pair[1]),
+            "Grid times must be increasing"
+        );
+
+        Self {
+            times,
+            lambda: 1e-2,
+            penalty: Penalty::default(),
+            nonnegative: false,
+        }
+    }
+
+    /// Set the regularization parameter $\lambda$.
+    pub fn with_lambda(mut self, lambda: f64) -> Self {
+        self.lambda = lambda;
+
+        self
+    }
+
+    /// Set the operator whose norm is penalized.
+    pub fn with_penalty(mut self, penalty: Penalty) -> Self {
+        self.penalty = penalty;
+
+        self
+    }
+
+    /// Constrain the inverse to be nonnegative, such as for distributions of decay rates.
+    pub fn with_nonnegative(mut self, nonnegative: bool) -> Self {
+        self.nonnegative = nonnegative;
+
+        self
+    }
+
+    /// Increasing times the inverse is recovered at.
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Regularization parameter.
+    pub fn lambda(&self) -> f64 {
+        self.lambda
+    }
+
+    /// Operator whose norm is penalized.
+    pub fn penalty(&self) -> Penalty {
+        self.penalty
+    }
+
+    /// Whether the inverse is constrained to be nonnegative.
+    pub fn nonnegative(&self) -> bool {
+        self.nonnegative
+    }
+
+    /// Discretized Laplace transform $K_{ij} = w_j e^{-s_i t_j}$ with the trapezoidal weights of the grid.
+    pub fn kernel(&self, s: &[f64]) -> DMatrix<f64> {
+        let last = self.times.len() - 1;
+        let weight = |j: usize| {
+            let left = if j > 0 {
+                self.times[j] - self.times[j - 1]
+            } else {
+                0.0
+            };
+            let right = if j < last {
+                self.times[j + 1] - self.times[j]
+            } else {
+                0.0
+            };
+
+            (left + right) / 2.0
+        };
+
+        DMatrix::from_fn(s.len(), self.times.len(), |i, j| {
+            weight(j) * (-s[i] * self.times[j]).exp()
+        })
+    }
+
+    /// Recover the inverse at the grid times from the `samples` of the transform at the real points `s`.
+    pub fn invert(&self, s: &[f64], samples: &[f64]) -> Vec<f64> {
+        assert_eq!(s.len(), samples.len(), "Every point needs a sample");
+
+        solve(
+            &self.kernel(s),
+            &DVector::from_column_slice(samples),
+            &self.penalty.matrix(self.times.len()),
+            self.lambda,
+            self.nonnegative,
+        )
+        .data
+        .into()
+    }
+}
+
+/// Minimize $\lVert K f - F \rVert^2 + \lambda^2 \lVert L f \rVert^2$, optionally with $f \geq 0$, as the least squares problem of the stacked system.
+pub(crate) fn solve(
+    kernel: &DMatrix<f64>,
+    data: &DVector<f64>,
+    penalty: &DMatrix<f64>,
+    lambda: f64,
+    nonnegative: bool,
+) -> DVector<f64> {
+    let rows = kernel.nrows() + penalty.nrows();
+    let mut system = DMatrix::zeros(rows, kernel.ncols());
+    system.rows_mut(0, kernel.nrows()).copy_from(kernel);
+    system
+        .rows_mut(kernel.nrows(), penalty.nrows())
+        .copy_from(&(penalty * lambda));
+    let mut rhs = DVector::zeros(rows);
+    rhs.rows_mut(0, data.len()).copy_from(data);
+
+    if nonnegative {
+        nnls(&system, &rhs)
+    } else {
+        least_squares(&system, &rhs)
+    }
+}
+
+/// Choose the regularization parameter among candidates spanning eight decades around the ratio of the norms of the kernel and the penalty.
+///
+/// Both rules use the unconstrained solutions, the nonnegativity constraint is only applied to the final solution.
+pub(crate) fn select_lambda(
+    kernel: &DMatrix<f64>,
+    data: &DVector<f64>,
+    penalty: &DMatrix<f64>,
+    choice: ParameterChoice,
+) -> f64 {
+    let scale = kernel.norm() / penalty.norm();
+    let candidates = (0..=32)
+        .map(|k| scale * 10.0_f64.powf(-6.0 + k as f64 / 4.0))
+        .collect::<Vec<_>>();
+    let normal = kernel.tr_mul(kernel);
+    let penalty_normal = penalty.tr_mul(penalty);
+
+    match choice {
+        ParameterChoice::Fixed(lambda) => lambda,
+        ParameterChoice::Gcv => {
+            let score = |lambda: f64| {
+                let Some(inverse) = (&normal + &penalty_normal * lambda.powi(2)).try_inverse()
+                else {
+                    return f64::INFINITY;
+                };
+                let solution = &inverse * kernel.tr_mul(data);
+                let residual = (kernel * solution - data).norm_squared();
+                // Trace of the influence matrix `K M^-1 K^T`
+                let influence = (kernel * &inverse).component_mul(kernel).sum();
+
+                data.len() as f64 * residual / (data.len() as f64 - influence).powi(2)
+            };
+
+            candidates
+                .into_iter()
+                .min_by(|a, b| score(*a).total_cmp(&score(*b)))
+                .expect("There are candidates")
+        }
+        ParameterChoice::LCurve => {
+            let points = candidates
+                .iter()
+                .map(|lambda| {
+                    let solution = solve(kernel, data, penalty, *lambda, false);
+
+                    (
+                        (kernel * &solution - data).norm().ln(),
+                        (penalty * &solution).norm().ln(),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            // Curvature with central differences in the logarithm of the parameter
+            (1..points.len() - 1)
+                .map(|k| {
+                    let (previous, current, next) = (points[k - 1], points[k], points[k + 1]);
+                    let (dx, dy) = ((next.0 - previous.0) / 2.0, (next.1 - previous.1) / 2.0);
+                    let (ddx, ddy) = (
+                        next.0 - 2.0 * current.0 + previous.0,
+                        next.1 - 2.0 * current.1 + previous.1,
+                    );
+
+                    (k, (dx * ddy - ddx * dy) / (dx * dx + dy * dy).powf(1.5))
+                })
+                .filter(|(_, curvature)| curvature.is_finite())
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map_or(scale, |(k, _)| candidates[k])
+        }
+    }
+}
+
+/// Solve the nonnegative least squares problem $\min_{x \geq 0} \lVert A x - b \rVert$ with the active set method of Lawson and Hanson.
+pub fn nnls(a: &DMatrix<f64>, b: &DVector<f64>) -> DVector<f64> {
+    let columns = a.ncols();
+    let tolerance = 10.0 * f64::EPSILON * a.norm() * b.norm().max(1.0);
+    let mut x = DVector::zeros(columns);
+    let mut passive = vec![false; columns];
+
+    for _ in 0..3 * columns {
+        // Gradient of the residual, positive components can decrease it
+        let gradient = a.tr_mul(&(b - a * &x));
+        let Some(entering) = (0..columns)
+            .filter(|&j| !passive[j] && gradient[j] > tolerance)
+            .max_by(|&i, &j| gradient[i].total_cmp(&gradient[j]))
+        else {
+            break;
+        };
+        passive[entering] = true;
+
+        loop {
+            let indices = (0..columns).filter(|&j| passive[j]).collect::<Vec<_>>();
+            let reduced =
+                DMatrix::from_columns(&indices.iter().map(|&j| a.column(j)).collect::<Vec<_>>());
+            let z = least_squares(&reduced, b);
+
+            if z.iter().all(|&z| z > 0.0) {
+                for (&j, z) in indices.iter().zip(z.iter()) {
+                    x[j] = *z;
+                }
+                break;
+            }
+
+            // Move towards the unconstrained solution until the first component reaches zero
+            let step = indices
+                .iter()
+                .zip(z.iter())
+                .filter(|(_, &z)| z <= 0.0)
+                .map(|(&j, &z)| x[j] / (x[j] - z))
+                .fold(f64::INFINITY, f64::min);
+            for (&j, z) in indices.iter().zip(z.iter()) {
+                x[j] += step * (z - x[j]);
+                if x[j] <= tolerance {
+                    x[j] = 0.0;
+                    passive[j] = false;
+                }
+            }
+        }
+    }
+
+    x
+}
+
+/// Least squares solution with the singular value decomposition, which tolerates rank deficient systems.
+fn least_squares(a: &DMatrix<f64>, b: &DVector<f64>) -> DVector<f64> {
+    a.clone()
+        .svd(true, true)
+        .solve(b, f64::EPSILON)
+        .expect("Both singular vectors were computed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regularized_inversions() {
+        // Deterministic noise with a relative size of `1e-4`
+        let s = (0..40)
+            .map(|i| 0.1 * 100.0_f64.powf(i as f64 / 39.0))
+            .collect::<Vec<_>>();
+        let samples = s
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (1.0 + 1e-4 * (i as f64 * 12.9898).sin()) / (s + 1.0).powi(2))
+            .collect::<Vec<_>>();
+        let times = (0..=50).map(|j| j as f64 * 0.2).collect::<Vec<_>>();
+        let exact = |t: f64| t * (-t).exp();
+
+        let max_error = |inversion: &RegularizedInversion| {
+            inversion
+                .invert(&s, &samples)
+                .iter()
+                .zip(inversion.times())
+                .map(|(f, t)| (f - exact(*t)).abs())
+                .fold(0.0, f64::max)
+        };
+        let inversion = RegularizedInversion::new(times);
+        assert!(max_error(&inversion) < 0.05);
+        let nonnegative = inversion.clone().with_nonnegative(true);
+        assert!(max_error(&nonnegative) < 0.03);
+        assert!(nonnegative.invert(&s, &samples).iter().all(|f| *f >= 0.0));
+        assert!(max_error(&inversion.clone().with_penalty(Penalty::Identity)) < 0.05);
+        // Without regularization the noise is amplified
+        assert!(max_error(&inversion.with_lambda(0.0)) > 1.0);
+
+        // Nonnegative least squares against a known active set
+        let a = DMatrix::from_row_slice(3, 2, &[1.0, 0.0, 0.0, 1.0, 1.0, 1.0]);
+        let b = DVector::from_vec(vec![1.0, -1.0, 0.0]);
+        assert!(approx::relative_eq!(
+            nnls(&a, &b),
+            DVector::from_vec(vec![0.5, 0.0]),
+            epsilon = 1e-12
+        ));
+    }
+}
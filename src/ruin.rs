@@ -0,0 +1,286 @@
+//! Ruin probabilities and Gerber-Shiu functions of the Cramér-Lundberg risk model.
+//!
+//! The surplus of an insurer starting with capital $u$ is $U(t) = u + ct - \sum_{k=1}^{N(t)} X_k$, with premium rate $c$, a Poisson process $N$ of claims with rate $\lambda$ and independent claim sizes $X_k$ with density $f$.
+//! Ruin happens at the first time $T$ the surplus becomes negative.
+//! The functions of the initial capital satisfy renewal equations, so their transforms in $u$ follow from the claim-size transform $\hat{f}(s)$.
+//! In particular the ruin probability has the Pollaczek-Khinchine transform
+//!
+//! $$
+//! \hat{\psi}(s) = \frac{1}{s} - \frac{c - \lambda \mu}{cs - \lambda (1 - \hat{f}(s))}
+//! $$
+//!
+//! with the mean claim size $\mu$, which is the tail of the waiting time of an M/G/1 queue.
+
+use nalgebra::Complex;
+
+use crate::BoxedLaplaceFunc;
+
+/// Number of bisection steps for the root of Lundberg's equation.
+const ROOT_STEPS: usize = 200;
+
+/// Cramér-Lundberg model with Poisson claims and a general claim-size distribution.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::ruin::CramerLundberg;
+///
+/// // Exponential claims with mean 1 and a safety loading of 25%
+/// let model = CramerLundberg::new(|s| 1.0 / (s + 1.0), 1.0, 1.0, 1.25);
+/// let ruin = model.ruin_probability(&[5.0], 100);
+/// approx::assert_relative_eq!(ruin[0], 0.8 * (-0.2 * 5.0_f64).exp(), epsilon = 1e-4);
+/// # }
+/// ```
+pub struct CramerLundberg<'a> {
+    /// Laplace transform $\hat{f}(s)$ of the claim-size density.
+    claim_transform: BoxedLaplaceFunc<'a>,
+    /// Mean claim size $\mu$.
+    mean_claim: f64,
+    /// Poisson rate $\lambda$ of the claims.
+    claim_rate: f64,
+    /// Premium rate $c$.
+    premium_rate: f64,
+}
+
+impl<'a> CramerLundberg<'a> {
+    /// Construct from the transform of the claim-size density, its mean, the rate of the claims and the premium rate.
+    ///
+    /// The premiums must exceed the expected claims, $c > \lambda \mu$, otherwise ruin is certain.
+    pub fn new(
+        claim_transform: impl Fn(Complex<f64>) -> Complex<f64> + 'a,
+        mean_claim: f64,
+        claim_rate: f64,
+        premium_rate: f64,
+    ) -> Self {
+        assert!(mean_claim > 0.0, "Mean claim size must be positive");
+        assert!(claim_rate > 0.0, "Claim rate must be positive");
+        assert!(
+            premium_rate > claim_rate * mean_claim,
+            "Premium rate must exceed the expected claims per unit of time"
+        );
+
+        Self {
+            claim_transform: Box::new(claim_transform),
+            mean_claim,
+            claim_rate,
+            premium_rate,
+        }
+    }
+
+    /// Mean claim size $\mu$.
+    pub fn mean_claim(&self) -> f64 {
+        self.mean_claim
+    }
+
+    /// Poisson rate $\lambda$ of the claims.
+    pub fn claim_rate(&self) -> f64 {
+        self.claim_rate
+    }
+
+    /// Premium rate $c$.
+    pub fn premium_rate(&self) -> f64 {
+        self.premium_rate
+    }
+
+    /// Safety loading $\theta = \frac{c}{\lambda \mu} - 1$.
+    pub fn safety_loading(&self) -> f64 {
+        self.premium_rate / (self.claim_rate * self.mean_claim) - 1.0
+    }
+
+    /// Ruin probability $\psi(0) = \lambda \mu / c$ without initial capital, for any claim-size distribution.
+    pub fn ruin_probability_at_zero(&self) -> f64 {
+        self.claim_rate * self.mean_claim / self.premium_rate
+    }
+
+    /// Evaluate the Pollaczek-Khinchine transform $\hat{\psi}(s)$ of the ruin probability.
+    pub fn ruin_probability_transform(&self, s: Complex<f64>) -> Complex<f64> {
+        let tail = 1.0 - (self.claim_transform)(s);
+
+        self.claim_rate * (self.mean_claim * s - tail)
+            / (s * (self.premium_rate * s - self.claim_rate * tail))
+    }
+
+    /// Nonnegative root $\rho_\delta$ of Lundberg's equation $c \rho - (\lambda + \delta) + \lambda \hat{f}(\rho) = 0$ for the force of interest $\delta$.
+    pub fn lundberg_root(&self, discount: f64) -> f64 {
+        assert!(discount >= 0.0, "Force of interest can't be negative");
+        if discount == 0.0 {
+            return 0.0;
+        }
+
+        // Negative at zero and positive where the premiums alone balance the equation
+        let equation = |rho: f64| {
+            self.premium_rate * rho - (self.claim_rate + discount)
+                + self.claim_rate * (self.claim_transform)(Complex::from(rho)).re
+        };
+        let (mut low, mut high) = (0.0, (self.claim_rate + discount) / self.premium_rate);
+        for _ in 0..ROOT_STEPS {
+            let middle = (low + high) / 2.0;
+            if equation(middle) < 0.0 {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+
+        (low + high) / 2.0
+    }
+
+    /// Evaluate the transform of the Gerber-Shiu function $m(u) = E[e^{-\delta T - a |U(T)|}; T < \infty]$, with force of interest `discount` and penalty rate `deficit_rate` on the deficit at ruin.
+    ///
+    /// It solves the defective renewal equation of Gerber and Shiu,
+    ///
+    /// $$
+    /// \hat{m}(s) = \frac{\lambda (\hat{\omega}(\rho) - \hat{\omega}(s))}{c (s - \rho) - \lambda (\hat{f}(\rho) - \hat{f}(s))}, \quad \hat{\omega}(s) = \frac{\hat{f}(a) - \hat{f}(s)}{s - a}
+    /// $$
+    ///
+    /// with the root $\rho$ of Lundberg's equation.
+    /// Without discount and penalty this is the ruin probability, with a zero penalty rate it's the transform of the time of ruin.
+    pub fn gerber_shiu_transform(
+        &self,
+        discount: f64,
+        deficit_rate: f64,
+        s: Complex<f64>,
+    ) -> Complex<f64> {
+        assert!(deficit_rate >= 0.0, "Penalty rate can't be negative");
+
+        self.gerber_shiu_at_root(self.lundberg_root(discount), deficit_rate, s)
+    }
+
+    /// Calculate the probability of ruin for each of the initial capitals.
+    pub fn ruin_probability(&self, capitals: &[f64], max_function_evals: usize) -> Vec<f64> {
+        self.invert(
+            |s| self.ruin_probability_transform(s),
+            self.ruin_probability_at_zero(),
+            capitals,
+            max_function_evals,
+        )
+    }
+
+    /// Calculate the Gerber-Shiu function for each of the initial capitals, see [`CramerLundberg::gerber_shiu_transform`].
+    pub fn gerber_shiu(
+        &self,
+        capitals: &[f64],
+        discount: f64,
+        deficit_rate: f64,
+        max_function_evals: usize,
+    ) -> Vec<f64> {
+        assert!(deficit_rate >= 0.0, "Penalty rate can't be negative");
+
+        // Root is the same for all evaluations, and `m(0)` follows from the transform for large `s`
+        let rho = self.lundberg_root(discount);
+        let at_zero =
+            self.claim_rate / self.premium_rate * self.penalty(deficit_rate, rho.into()).re;
+
+        self.invert(
+            |s| self.gerber_shiu_at_root(rho, deficit_rate, s),
+            at_zero,
+            capitals,
+            max_function_evals,
+        )
+    }
+
+    /// Gerber-Shiu transform for the root `rho` of Lundberg's equation.
+    fn gerber_shiu_at_root(&self, rho: f64, deficit_rate: f64, s: Complex<f64>) -> Complex<f64> {
+        let rho = Complex::from(rho);
+
+        self.claim_rate * (self.penalty(deficit_rate, rho) - self.penalty(deficit_rate, s))
+            / (self.premium_rate * (s - rho)
+                - self.claim_rate * ((self.claim_transform)(rho) - (self.claim_transform)(s)))
+    }
+
+    /// Transform $\hat{\omega}(s)$ of the expected penalty at ruin from a claim exceeding the surplus, the limit at $s = a$ is the derivative.
+    fn penalty(&self, deficit_rate: f64, s: Complex<f64>) -> Complex<f64> {
+        let rate = Complex::from(deficit_rate);
+        if s == rate {
+            return self.derivative(rate);
+        }
+
+        ((self.claim_transform)(rate) - (self.claim_transform)(s)) / (s - rate)
+    }
+
+    /// Invert a function of the capital, using its known value `at_zero` where the time-domain inversion doesn't apply.
+    fn invert(
+        &self,
+        transform: impl Fn(Complex<f64>) -> Complex<f64>,
+        at_zero: f64,
+        capitals: &[f64],
+        max_function_evals: usize,
+    ) -> Vec<f64> {
+        capitals
+            .iter()
+            .map(|u| {
+                if *u == 0.0 {
+                    return at_zero;
+                }
+
+                crate::laplace_inversion(&transform, *u, max_function_evals)
+            })
+            .collect()
+    }
+
+    /// Central difference of the claim transform, for the penalty at its own rate.
+    fn derivative(&self, x: Complex<f64>) -> Complex<f64> {
+        let step = 1e-6 * (1.0 + x.norm());
+
+        -((self.claim_transform)(x + step) - (self.claim_transform)(x - step)) / (2.0 * step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ruin_probabilities() {
+        // Exponential claims have closed forms for all functions
+        let (beta, claim_rate, premium_rate) = (2.0, 1.0, 0.8);
+        let model =
+            CramerLundberg::new(|s| beta / (s + beta), 1.0 / beta, claim_rate, premium_rate);
+        assert!(approx::relative_eq!(model.safety_loading(), 0.6));
+        let capitals = [0.0, 0.5, 2.0, 5.0];
+        let adjustment = beta - claim_rate / premium_rate;
+        for (u, ruin) in capitals.iter().zip(model.ruin_probability(&capitals, 100)) {
+            let exact = claim_rate / (premium_rate * beta) * (-adjustment * u).exp();
+            assert!(approx::relative_eq!(ruin, exact, epsilon = 1e-4));
+        }
+
+        // Roots `rho` and `-R` of `c x^2 + (c beta - lambda - delta) x - delta beta`
+        let discount = 0.1;
+        let linear = premium_rate * beta - claim_rate - discount;
+        let root = (linear * linear + 4.0 * premium_rate * discount * beta).sqrt();
+        let (rho, negative) = (
+            (root - linear) / (2.0 * premium_rate),
+            (-root - linear) / (2.0 * premium_rate),
+        );
+        assert!(approx::relative_eq!(
+            model.lundberg_root(discount),
+            rho,
+            epsilon = 1e-12
+        ));
+        // Deficit is exponential and independent of the time of ruin
+        for deficit_rate in [0.0, 1.0, beta] {
+            let functions = model.gerber_shiu(&capitals[1..], discount, deficit_rate, 100);
+            for (u, function) in capitals[1..].iter().zip(functions) {
+                let exact =
+                    claim_rate / (premium_rate * (rho + beta)) * (negative * u).exp() * beta
+                        / (beta + deficit_rate);
+                assert!(approx::relative_eq!(function, exact, epsilon = 1e-4));
+            }
+        }
+
+        // Gamma claims, the Gerber-Shiu function without discount or penalty is the ruin probability
+        let gamma = CramerLundberg::new(|s| (beta / (s + beta)).powi(2), 2.0 / beta, 1.0, 1.5);
+        let ruin = gamma.ruin_probability(&[1e-6, 1.0], 100);
+        assert!(approx::relative_eq!(
+            ruin[0],
+            gamma.ruin_probability_at_zero(),
+            epsilon = 1e-4
+        ));
+        assert!(approx::relative_eq!(
+            gamma.gerber_shiu(&[1.0], 0.0, 0.0, 100)[0],
+            ruin[1],
+            epsilon = 1e-9
+        ));
+    }
+}
@@ -0,0 +1,310 @@
+//! Special functions needed for closed-form reference solutions.
+
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2, PI};
+
+use nalgebra::Complex;
+
+/// Complementary error function $\operatorname{erfc}(x) = 1 - \operatorname{erf}(x)$.
+///
+/// Uses the series of $e^{x^2} \operatorname{erf}(x)$ for small arguments and a continued fraction for large arguments, both accurate to roughly machine precision.
+pub fn erfc(x: f64) -> f64 {
+    if x.is_nan() {
+        return f64::NAN;
+    }
+    if x < 0.0 {
+        return 2.0 - erfc(-x);
+    }
+
+    if x < 2.5 {
+        1.0 - erf_series(x)
+    } else {
+        erfc_continued_fraction(x)
+    }
+}
+
+/// Error function $\operatorname{erf}(x)$.
+pub fn erf(x: f64) -> f64 {
+    if x.abs() < 2.5 {
+        erf_series(x)
+    } else {
+        1.0 - erfc(x)
+    }
+}
+
+/// Cumulative distribution function of the standard normal distribution.
+pub fn norm_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x * FRAC_1_SQRT_2)
+}
+
+/// Gamma function $\Gamma(x)$ for real arguments.
+///
+/// Uses the Lanczos approximation with $g = 7$ for $x \geq \frac{1}{2}$ and the reflection formula $\Gamma(x) \Gamma(1 - x) = \frac{\pi}{\sin \pi x}$ below, accurate to about 15 digits.
+pub fn gamma(x: f64) -> f64 {
+    if x < 0.5 {
+        return PI / ((PI * x).sin() * gamma(1.0 - x));
+    }
+
+    let x = x - 1.0;
+    let sum = LANCZOS
+        .iter()
+        .enumerate()
+        .skip(1)
+        .fold(LANCZOS[0], |sum, (k, coefficient)| {
+            sum + coefficient / (x + k as f64)
+        });
+    let base = x + LANCZOS_G + 0.5;
+
+    (2.0 * PI).sqrt() * base.powf(x + 0.5) * (-base).exp() * sum
+}
+
+/// Parameter $g$ of the Lanczos approximation.
+const LANCZOS_G: f64 = 7.0;
+
+/// Coefficients of the Lanczos approximation for $g = 7$ with nine terms.
+#[allow(clippy::excessive_precision)]
+const LANCZOS: [f64; 9] = [
+    0.999_999_999_999_809_93,
+    676.520_368_121_885_1,
+    -1_259.139_216_722_402_8,
+    771.323_428_777_653_13,
+    -176.615_029_162_140_59,
+    12.507_343_278_686_905,
+    -0.138_571_095_265_720_12,
+    9.984_369_578_019_571_6e-6,
+    1.505_632_735_149_311_6e-7,
+];
+
+/// Exponentially scaled modified Bessel function of the first kind $e^{-z} I_n(z)$ of order zero or one, for $\operatorname{Re} z \geq 0$.
+///
+/// Uses the trapezoidal rule on $I_n(z) = \frac{1}{\pi} \int_0^\pi e^{z \cos \theta} \cos n \theta d\theta$, which converges exponentially for the periodic integrand, and the asymptotic expansion for $|z| > 50$.
+pub fn bessel_ie(n: u32, z: Complex<f64>) -> Complex<f64> {
+    assert!(n <= 1, "Only orders zero and one are supported");
+
+    if z.norm() > BESSEL_ASYMPTOTIC {
+        return bessel_asymptotic(n, z, -1.0) / (2.0 * PI * z).sqrt();
+    }
+
+    // Resolve the peak at zero with a step of `1 / sqrt(|z|)`
+    let points = (2.0 * PI * z.norm().sqrt()).ceil().max(32.0) as usize;
+    let step = PI / points as f64;
+    let sum = (0..=points)
+        .map(|k| {
+            let theta = k as f64 * step;
+            let weight = if k == 0 || k == points { 0.5 } else { 1.0 };
+            weight * (z * (theta.cos() - 1.0)).exp() * (n as f64 * theta).cos()
+        })
+        .sum::<Complex<f64>>();
+
+    sum * step / PI
+}
+
+/// Exponentially scaled modified Bessel function of the second kind $e^z K_n(z)$ of order zero or one, for $\operatorname{Re} z > 0$.
+///
+/// Uses the power series for $|z| \leq 2$, the trapezoidal rule on $K_n(z) = \int_0^\infty e^{-z \cosh t} \cosh n t dt$ up to $|z| = 50$ and the asymptotic expansion beyond.
+pub fn bessel_ke(n: u32, z: Complex<f64>) -> Complex<f64> {
+    assert!(n <= 1, "Only orders zero and one are supported");
+
+    if z.norm() > BESSEL_ASYMPTOTIC {
+        return bessel_asymptotic(n, z, 1.0) * (PI / (2.0 * z)).sqrt();
+    }
+    if z.norm() <= 2.0 {
+        return bessel_k_series(n, z) * z.exp();
+    }
+
+    // The integrand decays in a strip of half width `pi / 2 - |arg z|`, the step has to resolve both the strip and the peak at zero
+    let strip = FRAC_PI_2 - z.arg().abs();
+    let step = (0.5 / z.norm().sqrt()).min(0.1).min(strip / 12.0);
+    let mut sum = Complex::new(0.5, 0.0);
+    for k in 1.. {
+        let t = k as f64 * step;
+        let term = (-z * (t.cosh() - 1.0)).exp() * (n as f64 * t).cosh();
+        sum += term;
+        if term.norm() < f64::EPSILON * sum.norm() {
+            break;
+        }
+    }
+
+    sum * step
+}
+
+/// Argument above which the asymptotic expansions of the modified Bessel functions are used.
+const BESSEL_ASYMPTOTIC: f64 = 50.0;
+
+/// Asymptotic series $\sum_k \sigma^k a_k(n) / z^k$ with $a_k(n) = \prod_{j = 1}^k (4 n^2 - (2j - 1)^2) / (k! 8^k)$.
+fn bessel_asymptotic(n: u32, z: Complex<f64>, sign: f64) -> Complex<f64> {
+    let mu = 4.0 * f64::from(n * n);
+    let mut term = Complex::new(1.0, 0.0);
+    let mut sum = term;
+    for k in 1..30 {
+        let odd = f64::from(2 * k - 1);
+        term *= sign * (mu - odd * odd) / (f64::from(k) * 8.0 * z);
+        sum += term;
+        if term.norm() < f64::EPSILON * sum.norm() {
+            break;
+        }
+    }
+
+    sum
+}
+
+/// Power series of $K_0$ and $K_1$, from Abramowitz and Stegun 9.6.13 and 9.6.11.
+fn bessel_k_series(n: u32, z: Complex<f64>) -> Complex<f64> {
+    let quarter = z * z / 4.0;
+    let log = (z / 2.0).ln() + EULER_GAMMA;
+    // `term` is `(z^2 / 4)^k / (k! (k + n)!)` and `harmonic` the harmonic number `H_k`
+    let mut term = Complex::new(1.0, 0.0);
+    let mut harmonic = 0.0;
+    let (mut bessel_i, mut sum) = (term, Complex::new(0.0, 0.0));
+    if n == 0 {
+        for k in 1..40 {
+            term *= quarter / f64::from(k * k);
+            harmonic += 1.0 / f64::from(k);
+            bessel_i += term;
+            sum += term * harmonic;
+        }
+
+        sum - log * bessel_i
+    } else {
+        // `psi(k + 1) + psi(k + 2) = 2 H_k + 1 / (k + 1) - 2 gamma`, the `gamma` is part of `log`
+        sum = term;
+        for k in 1..40 {
+            term *= quarter / f64::from(k * (k + 1));
+            harmonic += 1.0 / f64::from(k);
+            bessel_i += term;
+            sum += term * (2.0 * harmonic + 1.0 / f64::from(k + 1));
+        }
+
+        1.0 / z + log * z / 2.0 * bessel_i - z / 4.0 * sum
+    }
+}
+
+/// Euler-Mascheroni constant $\gamma$.
+const EULER_GAMMA: f64 = 0.577_215_664_901_532_9;
+
+/// Series $\operatorname{erf}(x) = \frac{2}{\sqrt{\pi}} e^{-x^2} \sum_n \frac{2^n x^{2n + 1}}{1 \cdot 3 \cdots (2n + 1)}$ without cancellation.
+fn erf_series(x: f64) -> f64 {
+    let mut term = x;
+    let mut sum = x;
+    let mut n = 0.0;
+    while term.abs() > sum.abs() * f64::EPSILON {
+        n += 1.0;
+        term *= 2.0 * x * x / (2.0 * n + 1.0);
+        sum += term;
+    }
+
+    2.0 / PI.sqrt() * (-x * x).exp() * sum
+}
+
+/// Continued fraction $\operatorname{erfc}(x) = \frac{e^{-x^2}}{\sqrt{\pi}} \cfrac{1}{x + \cfrac{1/2}{x + \cfrac{1}{x + \cdots}}}$ evaluated from the tail.
+fn erfc_continued_fraction(x: f64) -> f64 {
+    let fraction = (1..=60)
+        .rev()
+        .fold(0.0, |tail, k| f64::from(k) / 2.0 / (x + tail));
+
+    (-x * x).exp() / PI.sqrt() / (x + fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_function() {
+        // Reference values from Abramowitz and Stegun table 7.1
+        for (x, expected) in [
+            (0.0, 1.0),
+            (0.5, 0.479_500_122_186_953_5),
+            (1.0, 0.157_299_207_050_285_13),
+            (2.0, 0.004_677_734_981_047_266),
+            (3.0, 2.209_049_699_858_544e-5),
+            (5.0, 1.537_459_794_428_034_8e-12),
+        ] {
+            assert!(approx::relative_eq!(
+                erfc(x),
+                expected,
+                max_relative = 1e-12
+            ));
+            assert!(approx::relative_eq!(
+                erfc(-x),
+                2.0 - expected,
+                max_relative = 1e-12
+            ));
+        }
+
+        assert!(approx::relative_eq!(norm_cdf(1.96), 0.975, epsilon = 1e-4));
+        assert_eq!(erf(0.0), 0.0);
+    }
+
+    #[test]
+    fn gamma_function() {
+        for (x, expected) in [
+            (1.0, 1.0),
+            (0.5, PI.sqrt()),
+            (5.0, 24.0),
+            (0.1, 9.513_507_698_668_732),
+            (-0.5, -2.0 * PI.sqrt()),
+            (20.5, 5.406_242_982_335_075e17),
+        ] {
+            assert!(approx::relative_eq!(
+                gamma(x),
+                expected,
+                max_relative = 1e-13
+            ));
+        }
+    }
+
+    #[test]
+    fn modified_bessel_functions() {
+        // Reference values from Abramowitz and Stegun tables 9.8 and 9.11
+        for (x, i0, i1, k0, k1) in [
+            (
+                0.5,
+                1.063_483_370_741_324,
+                0.257_894_305_390_896_4,
+                0.924_419_071_227_665_9,
+                1.656_441_120_003_301,
+            ),
+            (
+                1.0,
+                1.266_065_877_752_008_4,
+                0.565_159_103_992_485_1,
+                0.421_024_438_240_708_3,
+                0.601_907_230_197_234_6,
+            ),
+            (
+                10.0,
+                2_815.716_628_466_254,
+                2_670.988_303_701_255,
+                1.778_006_231_616_917e-5,
+                1.864_877_345_382_558_4e-5,
+            ),
+        ] {
+            let z = Complex::from(x);
+            let expected = [i0, i1, k0, k1];
+            let results = [
+                bessel_ie(0, z) * x.exp(),
+                bessel_ie(1, z) * x.exp(),
+                bessel_ke(0, z) * (-x).exp(),
+                bessel_ke(1, z) * (-x).exp(),
+            ];
+            for (result, expected) in results.iter().zip(expected) {
+                assert!(approx::relative_eq!(
+                    result.re,
+                    expected,
+                    max_relative = 1e-12
+                ));
+                assert!(result.im.abs() < 1e-12 * expected);
+            }
+        }
+
+        // Wronskian `I_0 K_1 + I_1 K_0 = 1 / z` in every regime
+        for modulus in [0.1, 1.5, 2.5, 10.0, 49.0, 51.0, 1e3] {
+            for angle in [0.0_f64, 0.5, 1.2, 1.4] {
+                let z = Complex::from_polar(modulus, angle);
+                let wronskian =
+                    bessel_ie(0, z) * bessel_ke(1, z) + bessel_ie(1, z) * bessel_ke(0, z);
+                assert!((wronskian * z - 1.0).norm() < 1e-12);
+            }
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! Time responses of linear state-space systems $\dot{x} = A x + B u$ by inverting the resolvent.
+//!
+//! The Laplace transform of the solution of $\dot{x} = A x$ with $x(0) = b$ is $(sI - A)^{-1} b$, so inverting it gives the action of the matrix exponential $e^{At} b$.
+//! Every node only requires solving a linear system, which makes this usable as an exponential integrator for large systems.
+//! For sparse systems [`resolvent_response`] only needs a shifted solve, for example a sparse LU factorization of [`nalgebra-sparse`](https://docs.rs/nalgebra-sparse) or [`sprs`](https://docs.rs/sprs) or a Krylov method.
+
+use nalgebra::{Complex, DMatrix, DVector};
+
+use crate::coefficients;
+
+/// Calculate the inverse of the resolvent $(sI - A)^{-1} b$ using a function that solves the linear system.
+///
+/// `solve` receives $s = \beta / t$ for each node and must return $x$ solving $(sI - A) x = b$, this allows sparse or matrix-free solvers.
+/// The result is $e^{At} b$.
+///
+/// Maximum number of evaluations is given by [`crate::coefficients::max_evaluations`].
+pub fn resolvent_inversion(
+    solve: impl FnMut(Complex<f64>) -> DVector<Complex<f64>>,
+    t: f64,
+    max_function_evals: usize,
+) -> DVector<f64> {
+    crate::laplace_inversion_vector(solve, t, max_function_evals)
+}
+
+/// Calculate $u(t) = e^{At} b$ for every time in `times` using a function that solves the shifted linear system.
+///
+/// `solve(z, b)` must return $(zI - A)^{-1} b$, so $A$ is only needed by the solver and can be sparse or matrix-free.
+/// The real parts of the weighted solutions are accumulated into a single vector per time, without keeping the solutions of the nodes.
+///
+/// Maximum number of evaluations is given by [`crate::coefficients::max_evaluations`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use nalgebra::DVector;
+///
+/// // Diagonal system solved without a matrix
+/// let rates = DVector::from_vec(vec![1.0, 2.0]);
+/// let b = DVector::from_vec(vec![1.0, 1.0]);
+/// let result = iltcme::state_space::resolvent_response(
+///     |z, b| b.zip_map(&rates, |b, rate| b / (z + rate)),
+///     &b,
+///     &[1.0, 2.0],
+///     50,
+/// );
+/// approx::assert_relative_eq!(result[1][1], (-4.0_f64).exp(), epsilon = 0.001);
+/// # }
+/// ```
+pub fn resolvent_response(
+    mut solve: impl FnMut(Complex<f64>, &DVector<Complex<f64>>) -> DVector<Complex<f64>>,
+    b: &DVector<f64>,
+    times: &[f64],
+    max_function_evals: usize,
+) -> Vec<DVector<f64>> {
+    let b = b.map(Complex::from);
+    let order = coefficients::order(max_function_evals);
+
+    times
+        .iter()
+        .map(|t| {
+            accumulate(
+                order.nodes().map(|(eta, beta)| (eta, solve(beta / t, &b))),
+                b.len(),
+                *t,
+            )
+        })
+        .collect()
+}
+
+/// Calculate $u(t) = e^{At} b$ for every time in `times`, solving the shifted linear systems of the nodes of each time on multiple threads.
+///
+/// The solutions are accumulated in node order once all solves finished, so the result is bit-identical to [`resolvent_response`].
+#[cfg(feature = "parallel")]
+pub fn par_resolvent_response(
+    solve: impl Fn(Complex<f64>, &DVector<Complex<f64>>) -> DVector<Complex<f64>> + Sync,
+    b: &DVector<f64>,
+    times: &[f64],
+    max_function_evals: usize,
+) -> Vec<DVector<f64>> {
+    use rayon::prelude::*;
+
+    let b = b.map(Complex::from);
+    let nodes = coefficients::order(max_function_evals)
+        .nodes()
+        .collect::<Vec<_>>();
+
+    times
+        .iter()
+        .map(|t| {
+            let solutions = nodes
+                .par_iter()
+                .map(|(eta, beta)| (*eta, solve(beta / t, &b)))
+                .collect::<Vec<_>>();
+
+            accumulate(solutions, b.len(), *t)
+        })
+        .collect()
+}
+
+/// Sum the real parts of the weighted solutions in node order and divide by the time.
+fn accumulate(
+    solutions: impl IntoIterator<Item = (Complex<f64>, DVector<Complex<f64>>)>,
+    len: usize,
+    t: f64,
+) -> DVector<f64> {
+    let mut sum = DVector::zeros(len);
+    for (eta, solution) in solutions {
+        sum.zip_apply(&solution, |sum, x| *sum += (eta * x).re);
+    }
+
+    sum / t
+}
+
+/// Calculate the action of the matrix exponential $e^{At} b$ with dense LU solves of the resolvent.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use nalgebra::{DMatrix, DVector};
+///
+/// // Harmonic oscillator rotating the initial state
+/// let a = DMatrix::from_row_slice(2, 2, &[0.0, 1.0, -1.0, 0.0]);
+/// let b = DVector::from_vec(vec![1.0, 0.0]);
+/// let result = iltcme::state_space::matrix_exponential_action(&a, &b, 1.0, 50);
+/// approx::assert_relative_eq!(result[0], 1.0_f64.cos(), epsilon = 0.001);
+/// approx::assert_relative_eq!(result[1], -1.0_f64.sin(), epsilon = 0.001);
+/// # }
+/// ```
+pub fn matrix_exponential_action(
+    a: &DMatrix<f64>,
+    b: &DVector<f64>,
+    t: f64,
+    max_function_evals: usize,
+) -> DVector<f64> {
+    StateSpace::new(a.clone(), DMatrix::zeros(a.nrows(), 0)).response(
+        b,
+        |_| DVector::zeros(0),
+        t,
+        max_function_evals,
+    )
+}
+
+/// Linear state-space system $\dot{x} = A x + B u$.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateSpace {
+    /// State matrix.
+    a: DMatrix<f64>,
+    /// Input matrix.
+    b: DMatrix<f64>,
+}
+
+impl StateSpace {
+    /// Construct from the square state matrix $A$ and the input matrix $B$.
+    pub fn new(a: DMatrix<f64>, b: DMatrix<f64>) -> Self {
+        assert!(a.is_square(), "State matrix must be square");
+        assert_eq!(
+            a.nrows(),
+            b.nrows(),
+            "Input matrix must have a row for each state"
+        );
+
+        Self { a, b }
+    }
+
+    /// Calculate the state at time `t` starting from `initial_state`.
+    ///
+    /// `input_transform` is the Laplace transform of the input $u$, the state is inverted from $(sI - A)^{-1} (x_0 + B U(s))$.
+    pub fn response(
+        &self,
+        initial_state: &DVector<f64>,
+        mut input_transform: impl FnMut(Complex<f64>) -> DVector<Complex<f64>>,
+        t: f64,
+        max_function_evals: usize,
+    ) -> DVector<f64> {
+        let a = self.a.map(Complex::from);
+        let b = self.b.map(Complex::from);
+        let initial_state = initial_state.map(Complex::from);
+        let identity = DMatrix::<Complex<f64>>::identity(a.nrows(), a.ncols());
+
+        resolvent_inversion(
+            |s| {
+                let rhs = if b.ncols() == 0 {
+                    initial_state.clone()
+                } else {
+                    &initial_state + &b * input_transform(s)
+                };
+
+                (&identity * s - &a)
+                    .lu()
+                    .solve(&rhs)
+                    .expect("Resolvent is singular at a node of the CME method")
+            },
+            t,
+            max_function_evals,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forced_response() {
+        // Decaying states driven by a unit step on the second state
+        let system = StateSpace::new(
+            DMatrix::from_row_slice(2, 2, &[-1.0, 0.0, 0.0, -2.0]),
+            DMatrix::from_row_slice(2, 1, &[0.0, 1.0]),
+        );
+        let initial_state = DVector::from_vec(vec![1.0, 0.0]);
+
+        for t in [0.5, 1.0, 3.0] {
+            let state =
+                system.response(&initial_state, |s| DVector::from_vec(vec![1.0 / s]), t, 100);
+
+            assert!(approx::relative_eq!(state[0], (-t).exp(), epsilon = 1e-4));
+            assert!(approx::relative_eq!(
+                state[1],
+                (1.0 - (-2.0 * t).exp()) / 2.0,
+                epsilon = 1e-4
+            ));
+        }
+    }
+
+    #[test]
+    fn sparse_resolvent() {
+        // Heat equation on a line with zero boundaries, solving the tridiagonal system without a matrix
+        let n = 20;
+        let h = 1.0 / (n + 1) as f64;
+        let solve = |z: Complex<f64>, b: &DVector<Complex<f64>>| {
+            // Thomas algorithm for `z - A` with `2 / h^2 + z` on the diagonal and `-1 / h^2` beside it
+            let (diagonal, off) = (z + 2.0 / h.powi(2), Complex::from(-1.0 / h.powi(2)));
+            let mut c = vec![Complex::from(0.0); n];
+            let mut x = b.clone();
+            c[0] = off / diagonal;
+            x[0] /= diagonal;
+            for i in 1..n {
+                let pivot = diagonal - off * c[i - 1];
+                c[i] = off / pivot;
+                x[i] = (x[i] - off * x[i - 1]) / pivot;
+            }
+            for i in (0..n - 1).rev() {
+                x[i] = x[i] - c[i] * x[i + 1];
+            }
+
+            x
+        };
+
+        let a = DMatrix::from_fn(n, n, |i, j| match i.abs_diff(j) {
+            0 => -2.0 / h.powi(2),
+            1 => 1.0 / h.powi(2),
+            _ => 0.0,
+        });
+        let b = DVector::from_fn(n, |i, _| ((i + 1) as f64 * h * std::f64::consts::PI).sin());
+        let times = [0.01, 0.05, 0.1];
+        let responses = resolvent_response(solve, &b, &times, 100);
+        for (t, response) in times.iter().zip(&responses) {
+            assert!(approx::relative_eq!(
+                response,
+                &matrix_exponential_action(&a, &b, *t, 100),
+                epsilon = 1e-10
+            ));
+        }
+
+        #[cfg(feature = "parallel")]
+        assert_eq!(par_resolvent_response(solve, &b, &times, 100), responses);
+    }
+}
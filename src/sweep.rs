@@ -0,0 +1,458 @@
+//! Inversions at many times that report their progress and can be cancelled from another thread.
+//!
+//! A sweep over a fine grid or many windows can take seconds for expensive transforms.
+//! A [`Sweep`] reports its [`Progress`] after each time it inverts, to drive a progress bar.
+//! It checks a [`CancellationToken`] before each time it inverts, when it's cancelled the results computed so far are returned instead of an error.
+//!
+//! # Example
+//!
+//! ```rust
+//! # fn main() -> Result<(), iltcme::Error> {
+//! use iltcme::{
+//!     sweep::{CancellationToken, Sweep},
+//!     CmeInverter,
+//! };
+//!
+//! let token = CancellationToken::new();
+//! let times = (1..=1000).map(|i| f64::from(i) * 0.01).collect::<Vec<_>>();
+//!
+//! // Cancel halfway, for example from a button in another thread
+//! let mut sweep = Sweep::new(CmeInverter::new(100)?)
+//!     .with_cancellation(token.clone())
+//!     .with_progress(|progress| {
+//!         if progress.fraction >= 0.5 {
+//!             token.cancel();
+//!         }
+//!     });
+//! let result = sweep.invert_times(|s| 1.0 / (s * s + 1.0), &times)?;
+//! assert_eq!(result.len(), 500);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use nalgebra::Complex;
+
+use crate::{CmeInverter, Error};
+
+/// Number of times of the initial grid of [`Sweep::invert_uniform`].
+const UNIFORM_TIMES: u32 = 17;
+
+/// Number of times the intervals of [`Sweep::invert_uniform`] can be halved.
+const MAX_REFINEMENTS: usize = 12;
+
+/// How far a sweep got.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    /// Number of results computed so far.
+    pub completed: usize,
+    /// Estimate of the fraction of the sweep that's done, between zero and one.
+    ///
+    /// The refinement of a grid isn't known in advance, the fraction of the interval that's covered is used for it.
+    pub fraction: f64,
+}
+
+/// Shared flag to cancel sweeps, all clones cancel the same sweeps.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Construct a token that's not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop the sweeps using this token before they invert the next time.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the token is cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Inversions at many times with the configuration of a [`CmeInverter`] that report their progress and can be cancelled.
+///
+/// When cancelled the results of the times inverted so far are returned, they're a prefix of the complete results.
+pub struct Sweep<'a> {
+    /// Configuration of the inversion at each time.
+    inverter: CmeInverter,
+    /// Token to cancel the sweep.
+    cancellation: Option<CancellationToken>,
+    /// Called after each result.
+    progress: Option<Box<dyn FnMut(Progress) + 'a>>,
+}
+
+impl<'a> Sweep<'a> {
+    /// Construct a sweep that inverts each time with the inverter.
+    pub fn new(inverter: CmeInverter) -> Self {
+        Self {
+            inverter,
+            cancellation: None,
+            progress: None,
+        }
+    }
+
+    /// Call `progress` after each result that's computed.
+    ///
+    /// The times inverted to choose the order aren't reported.
+    pub fn with_progress(mut self, progress: impl FnMut(Progress) + 'a) -> Self {
+        self.progress = Some(Box::new(progress));
+
+        self
+    }
+
+    /// Stop the sweep before inverting the next time once the token is cancelled.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+
+        self
+    }
+
+    /// Configuration of the inversion at each time.
+    pub fn inverter(&self) -> &CmeInverter {
+        &self.inverter
+    }
+
+    /// Whether the token of the sweep is cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Calculate the Laplace inversion for a function at each of the times in order.
+    ///
+    /// Returns fewer results than times when cancelled.
+    pub fn invert_times(
+        &mut self,
+        mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+        times: &[f64],
+    ) -> Result<Vec<f64>, Error> {
+        let mut results = Vec::with_capacity(times.len());
+        for &t in times {
+            if self.is_cancelled() {
+                break;
+            }
+            results.push(self.inverter.invert(&mut laplace_func, t)?);
+            self.report(results.len(), results.len() as f64 / times.len() as f64);
+        }
+
+        Ok(results)
+    }
+
+    /// Calculate the Laplace inversion for a function in windows of times with the lowest order that's accurate for each window, see [`CmeInverter::invert_windowed`].
+    ///
+    /// When cancelled the results of the times inverted so far are returned, with the orders of the windows they're in.
+    pub fn invert_windowed(
+        &mut self,
+        mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+        windows: &[&[f64]],
+        tolerance: f64,
+    ) -> Result<(Vec<f64>, Vec<usize>), Error> {
+        let total = windows.iter().map(|times| times.len()).sum::<usize>();
+        let mut results = Vec::with_capacity(total);
+        let mut orders = Vec::with_capacity(windows.len());
+        for times in windows {
+            let probes = match times {
+                [] => Vec::new(),
+                [first, .., last] => vec![*first, times[times.len() / 2], *last],
+                [t] => vec![*t],
+            };
+            let order = self.probed_order(&mut laplace_func, &probes, tolerance)?;
+            let inverter = self.inverter.with_max_function_evals(order);
+            let inverted = results.len();
+            for t in *times {
+                if self.is_cancelled() {
+                    // Order of the window that's partially inverted
+                    if results.len() > inverted {
+                        orders.push(order);
+                    }
+                    return Ok((results, orders));
+                }
+                results.push(inverter.invert(&mut laplace_func, *t)?);
+                self.report(results.len(), results.len() as f64 / total as f64);
+            }
+            orders.push(order);
+        }
+
+        Ok((results, orders))
+    }
+
+    /// Calculate the Laplace inversion for a function on a refined grid over `[start, end]`, see [`CmeInverter::invert_uniform`].
+    ///
+    /// When cancelled the grid up to the last time inverted is returned, linear interpolation is accurate within it.
+    pub fn invert_uniform(
+        &mut self,
+        mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+        [start, end]: [f64; 2],
+        tolerance: f64,
+    ) -> Result<(Vec<f64>, Vec<f64>), Error> {
+        let intervals = f64::from(UNIFORM_TIMES - 1);
+        let initial = (0..UNIFORM_TIMES)
+            .map(|i| start + (end - start) * f64::from(i) / intervals)
+            .collect::<Vec<_>>();
+        let order = self.probed_order(&mut laplace_func, &initial, tolerance)?;
+        let inverter = self.inverter.with_max_function_evals(order);
+
+        let mut grid = Grid {
+            range: [start, end],
+            times: Vec::new(),
+            results: Vec::new(),
+        };
+        if self.is_cancelled() {
+            return Ok((grid.times, grid.results));
+        }
+        let result = inverter.invert(&mut laplace_func, start)?;
+        self.push(&mut grid, (start, result));
+        for &t in &initial[1..] {
+            if self.is_cancelled() {
+                break;
+            }
+            let result = inverter.invert(&mut laplace_func, t)?;
+            let previous = (
+                grid.times[grid.times.len() - 1],
+                grid.results[grid.results.len() - 1],
+            );
+            self.refine(
+                &inverter,
+                &mut laplace_func,
+                [previous, (t, result)],
+                tolerance,
+                MAX_REFINEMENTS,
+                &mut grid,
+            )?;
+        }
+
+        Ok((grid.times, grid.results))
+    }
+
+    /// Append the results in the interval after `start` up to and including `end`, halving the interval while the middle isn't linear within `tolerance`.
+    ///
+    /// Stops appending once cancelled, so the results stay a prefix of the grid.
+    fn refine(
+        &mut self,
+        inverter: &CmeInverter,
+        laplace_func: &mut impl FnMut(Complex<f64>) -> Complex<f64>,
+        [start, end]: [(f64, f64); 2],
+        tolerance: f64,
+        depth: usize,
+        grid: &mut Grid,
+    ) -> Result<(), Error> {
+        if self.is_cancelled() {
+            return Ok(());
+        }
+
+        if depth > 0 {
+            let t = (start.0 + end.0) / 2.0;
+            let middle = (t, inverter.invert(&mut *laplace_func, t)?);
+            if (middle.1 - (start.1 + end.1) / 2.0).abs() > tolerance {
+                self.refine(
+                    inverter,
+                    laplace_func,
+                    [start, middle],
+                    tolerance,
+                    depth - 1,
+                    grid,
+                )?;
+                return self.refine(
+                    inverter,
+                    laplace_func,
+                    [middle, end],
+                    tolerance,
+                    depth - 1,
+                    grid,
+                );
+            }
+        }
+
+        self.push(grid, end);
+
+        Ok(())
+    }
+
+    /// Append a time and its result to the grid and report the fraction of the interval that's covered.
+    fn push(&mut self, grid: &mut Grid, (t, result): (f64, f64)) {
+        grid.times.push(t);
+        grid.results.push(result);
+
+        let [start, end] = grid.range;
+        let fraction = if end > start {
+            (t - start) / (end - start)
+        } else {
+            1.0
+        };
+        self.report(grid.times.len(), fraction);
+    }
+
+    /// Call the progress callback.
+    fn report(&mut self, completed: usize, fraction: f64) {
+        if let Some(progress) = &mut self.progress {
+            progress(Progress {
+                completed,
+                fraction,
+            });
+        }
+    }
+
+    /// Lowest order where the results at all probe times agree with the next order, the current order when cancelled.
+    fn probed_order(
+        &self,
+        mut laplace_func: impl FnMut(Complex<f64>) -> Complex<f64>,
+        probes: &[f64],
+        tolerance: f64,
+    ) -> Result<usize, Error> {
+        let max_function_evals = self.inverter.max_function_evals();
+        let mut order = max_function_evals.min(10);
+        while order < max_function_evals {
+            let next = (2 * order).min(max_function_evals);
+            let mut agree = true;
+            for &t in probes {
+                if self.is_cancelled() {
+                    return Ok(order);
+                }
+                let result = self
+                    .inverter
+                    .with_max_function_evals(order)
+                    .invert(&mut laplace_func, t)?;
+                let next_result = self
+                    .inverter
+                    .with_max_function_evals(next)
+                    .invert(&mut laplace_func, t)?;
+                if (result - next_result).abs() > tolerance {
+                    agree = false;
+                    break;
+                }
+            }
+
+            if agree {
+                return Ok(order);
+            }
+            order = next;
+        }
+
+        Ok(max_function_evals)
+    }
+}
+
+/// Times and results of a refined grid over an interval.
+struct Grid {
+    /// Start and end of the interval.
+    range: [f64; 2],
+    /// Sorted times of the grid.
+    times: Vec<f64>,
+    /// Result at each time.
+    results: Vec<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancellation() {
+        let sine = |s: Complex<f64>| 1.0 / (s * s + 1.0);
+        let inverter = CmeInverter::new(200).unwrap();
+        let times = [0.5, 1.0, 2.0, 4.0];
+        let windows: [&[f64]; 2] = [&[0.1, 0.2], &[10.0, 15.0, 20.0]];
+
+        // Without cancelling the results are complete
+        let mut sweep = Sweep::new(inverter).with_cancellation(CancellationToken::new());
+        assert_eq!(
+            sweep.invert_times(sine, &times).unwrap(),
+            times.map(|t| inverter.invert(sine, t).unwrap())
+        );
+        assert_eq!(
+            sweep.invert_uniform(sine, [0.1, 10.0], 1e-3),
+            inverter.invert_uniform(sine, [0.1, 10.0], 1e-3)
+        );
+
+        // Cancelled partway through the second time
+        let cancelled = |limit: usize| {
+            let token = CancellationToken::new();
+            let sweep = Sweep::new(inverter).with_cancellation(token.clone());
+            let mut evaluations = 0;
+            let transform = move |s| {
+                evaluations += 1;
+                if evaluations == limit {
+                    token.cancel();
+                }
+                sine(s)
+            };
+
+            (sweep, transform)
+        };
+        let evaluations = crate::coefficients::order(200).evaluations();
+        let (mut sweep, transform) = cancelled(evaluations + 1);
+        let result = sweep.invert_times(transform, &times).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[1], inverter.invert(sine, 1.0).unwrap());
+
+        // Prefix of the grid
+        let mut evaluations = 0;
+        let (full_grid, _) = inverter
+            .invert_uniform(
+                |s| {
+                    evaluations += 1;
+                    sine(s)
+                },
+                [0.1, 10.0],
+                1e-3,
+            )
+            .unwrap();
+        let (mut sweep, transform) = cancelled(evaluations / 2);
+        let (grid, result) = sweep.invert_uniform(transform, [0.1, 10.0], 1e-3).unwrap();
+        assert!(!grid.is_empty() && grid.len() < full_grid.len());
+        assert_eq!(grid.len(), result.len());
+        assert_eq!(grid, full_grid[..grid.len()]);
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let mut sweep = sweep.with_cancellation(token);
+        assert_eq!(
+            sweep.invert_windowed(sine, &windows, 1e-4).unwrap(),
+            (Vec::new(), Vec::new())
+        );
+        assert!(sweep.is_cancelled());
+    }
+
+    #[test]
+    fn progress() {
+        let sine = |s: Complex<f64>| 1.0 / (s * s + 1.0);
+        let inverter = CmeInverter::new(100).unwrap();
+
+        let mut reports = Vec::new();
+        let windows: [&[f64]; 2] = [&[0.1, 0.2], &[10.0, 15.0, 20.0]];
+        Sweep::new(inverter)
+            .with_progress(|progress| reports.push(progress))
+            .invert_windowed(sine, &windows, 1e-4)
+            .unwrap();
+        assert_eq!(reports.len(), 5);
+        assert_eq!(
+            reports[1],
+            Progress {
+                completed: 2,
+                fraction: 0.4
+            }
+        );
+
+        // A report for every time of the grid, covering the interval in order
+        let mut reports = Vec::new();
+        let (times, _) = Sweep::new(inverter)
+            .with_progress(|progress| reports.push(progress))
+            .invert_uniform(sine, [1.0, 5.0], 1e-3)
+            .unwrap();
+        assert_eq!(reports.len(), times.len());
+        assert!(reports
+            .windows(2)
+            .all(|r| r[1].completed == r[0].completed + 1 && r[1].fraction > r[0].fraction));
+        assert_eq!(reports[0].fraction, 0.0);
+        assert_eq!(reports[reports.len() - 1].fraction, 1.0);
+    }
+}
@@ -0,0 +1,165 @@
+//! Catalogue of Laplace transform pairs with exact inverses.
+//!
+//! The pairs are ready-made transforms for building models, and test cases with known answers to check the accuracy of an inversion setup before trusting it with an unknown transform.
+//!
+//! # Example
+//!
+//! ```rust
+//! # fn main() {
+//! use iltcme::transforms::KnownTransform;
+//!
+//! // Check the order is sufficient for the slowly converging heat kernel
+//! let pair = KnownTransform::HeatKernel { distance: 1.0 };
+//! let error = pair.inversion_error(&[0.1, 1.0, 10.0], 100);
+//! assert!(error < 1e-4);
+//! # }
+//! ```
+
+use std::f64::consts::PI;
+
+use nalgebra::Complex;
+
+use crate::special;
+
+/// Laplace transform with a closed-form inverse.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KnownTransform {
+    /// Exponential $e^{-\lambda t}$ with the transform $\frac{1}{s + \lambda}$.
+    Exponential {
+        /// Rate $\lambda$.
+        rate: f64,
+    },
+    /// Sine $\sin \omega t$ with the transform $\frac{\omega}{s^2 + \omega^2}$.
+    Sine {
+        /// Angular frequency $\omega$.
+        frequency: f64,
+    },
+    /// Cosine $\cos \omega t$ with the transform $\frac{s}{s^2 + \omega^2}$.
+    Cosine {
+        /// Angular frequency $\omega$.
+        frequency: f64,
+    },
+    /// Power $t^\alpha$ with the transform $\frac{\Gamma(\alpha + 1)}{s^{\alpha + 1}}$, for $\alpha > -1$.
+    Power {
+        /// Exponent $\alpha$.
+        exponent: f64,
+    },
+    /// Gamma density $\frac{\lambda^k t^{k - 1} e^{-\lambda t}}{\Gamma(k)}$ with the transform $\left( \frac{\lambda}{s + \lambda} \right)^k$.
+    GammaDensity {
+        /// Shape $k$.
+        shape: f64,
+        /// Rate $\lambda$.
+        rate: f64,
+    },
+    /// Temperature $\operatorname{erfc}(\frac{x}{2 \sqrt{t}})$ at distance $x$ in a half-space after a unit step at the surface, with the transform $\frac{e^{-x \sqrt{s}}}{s}$.
+    Erfc {
+        /// Distance $x$ from the surface.
+        distance: f64,
+    },
+    /// Heat kernel $\frac{x}{2 \sqrt{\pi t^3}} e^{-\frac{x^2}{4 t}}$, the response to a unit impulse at the surface, with the transform $e^{-x \sqrt{s}}$.
+    HeatKernel {
+        /// Distance $x$ from the surface.
+        distance: f64,
+    },
+    /// Exponentially scaled modified Bessel function $e^{-a t} I_0(a t)$ with the transform $\frac{1}{\sqrt{s (s + 2 a)}}$.
+    ScaledBessel {
+        /// Scale $a$.
+        scale: f64,
+    },
+}
+
+impl KnownTransform {
+    /// Evaluate the transform at `s`.
+    pub fn evaluate(&self, s: Complex<f64>) -> Complex<f64> {
+        match *self {
+            Self::Exponential { rate } => 1.0 / (s + rate),
+            Self::Sine { frequency } => frequency / (s * s + frequency * frequency),
+            Self::Cosine { frequency } => s / (s * s + frequency * frequency),
+            Self::Power { exponent } => special::gamma(exponent + 1.0) / s.powf(exponent + 1.0),
+            Self::GammaDensity { shape, rate } => (rate / (s + rate)).powf(shape),
+            Self::Erfc { distance } => (-distance * s.sqrt()).exp() / s,
+            Self::HeatKernel { distance } => (-distance * s.sqrt()).exp(),
+            Self::ScaledBessel { scale } => 1.0 / (s * (s + 2.0 * scale)).sqrt(),
+        }
+    }
+
+    /// Transform as a Laplace transform function.
+    pub fn transform(self) -> impl Fn(Complex<f64>) -> Complex<f64> {
+        move |s| self.evaluate(s)
+    }
+
+    /// Exact inverse at time `t`.
+    pub fn inverse(&self, t: f64) -> f64 {
+        match *self {
+            Self::Exponential { rate } => (-rate * t).exp(),
+            Self::Sine { frequency } => (frequency * t).sin(),
+            Self::Cosine { frequency } => (frequency * t).cos(),
+            Self::Power { exponent } => t.powf(exponent),
+            Self::GammaDensity { shape, rate } => {
+                rate.powf(shape) * t.powf(shape - 1.0) * (-rate * t).exp() / special::gamma(shape)
+            }
+            Self::Erfc { distance } => special::erfc(distance / (2.0 * t.sqrt())),
+            Self::HeatKernel { distance } => {
+                distance / (2.0 * (PI * t.powi(3)).sqrt())
+                    * (-distance * distance / (4.0 * t)).exp()
+            }
+            Self::ScaledBessel { scale } => special::bessel_ie(0, Complex::from(scale * t)).re,
+        }
+    }
+
+    /// Largest absolute difference between the CME inversion and the exact inverse at the times.
+    pub fn inversion_error(&self, times: &[f64], max_function_evals: usize) -> f64 {
+        times
+            .iter()
+            .map(|t| {
+                (crate::laplace_inversion(self.transform(), *t, max_function_evals)
+                    - self.inverse(*t))
+                .abs()
+            })
+            .fold(0.0, f64::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalogue() {
+        let times = [0.5, 1.0, 2.0, 5.0];
+        for pair in [
+            KnownTransform::Exponential { rate: 0.5 },
+            KnownTransform::Sine { frequency: 2.0 },
+            KnownTransform::Cosine { frequency: 0.5 },
+            KnownTransform::Power { exponent: 0.5 },
+            KnownTransform::Power { exponent: 2.0 },
+            KnownTransform::GammaDensity {
+                shape: 2.5,
+                rate: 1.5,
+            },
+            KnownTransform::Erfc { distance: 1.0 },
+            KnownTransform::HeatKernel { distance: 1.0 },
+            KnownTransform::ScaledBessel { scale: 1.0 },
+        ] {
+            let error = pair.inversion_error(&times, 200);
+            assert!(error < 1e-3, "Inversion of {pair:?} is off by {error}");
+        }
+
+        // Integer powers and shapes reduce to the elementary pairs
+        let s = Complex::new(0.8, 1.7);
+        let exponential = KnownTransform::Exponential { rate: 2.0 };
+        let gamma = KnownTransform::GammaDensity {
+            shape: 1.0,
+            rate: 2.0,
+        };
+        assert!((gamma.evaluate(s) - 2.0 * exponential.evaluate(s)).norm() < 1e-14);
+        assert!(approx::relative_eq!(
+            gamma.inverse(0.7),
+            2.0 * exponential.inverse(0.7),
+            epsilon = 1e-14
+        ));
+        let square = KnownTransform::Power { exponent: 2.0 };
+        assert!((square.evaluate(s) - 2.0 / s.powi(3)).norm() < 1e-12);
+    }
+}
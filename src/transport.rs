@@ -0,0 +1,313 @@
+//! Breakthrough curves of solutes transported through a porous column by advection and dispersion.
+//!
+//! The resident concentration $c$ of a solute with retardation factor $R$ and first-order decay rate $\lambda$ follows
+//!
+//! $$
+//! R \frac{\partial c}{\partial t} = D \frac{\partial^2 c}{\partial x^2} - v \frac{\partial c}{\partial x} - \lambda R c
+//! $$
+//!
+//! for the pore water velocity $v$ and the dispersion coefficient $D$.
+//! In the Laplace domain the solutions are combinations of $e^{r_\pm x}$ with $r_\pm = \frac{v \pm \sqrt{v^2 + 4 D R (s + \lambda)}}{2D}$, the closed forms in the time domain like Ogata-Banks only exist for the simplest boundary conditions.
+//! Concentrations are relative to the inlet concentration $c_0$ of the solution entering a clean column.
+
+use nalgebra::Complex;
+
+/// Condition at the inlet of the column.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Inlet {
+    /// Concentration at the inlet is prescribed, a first-type condition.
+    #[default]
+    Concentration,
+    /// Solute flux at the inlet is prescribed, a third-type condition $v c - D \frac{\partial c}{\partial x} = v c_0$ that conserves mass.
+    Flux,
+}
+
+/// One-dimensional advection and dispersion of a solute through a column.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::transport::AdvectionDispersion;
+///
+/// // Half of the inlet concentration arrives at about the mean travel time
+/// let column = AdvectionDispersion::new(0.5, 0.01);
+/// let breakthrough = column.breakthrough(2.0, &[4.0], 100);
+/// approx::assert_relative_eq!(breakthrough[0], 0.5, epsilon = 0.05);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdvectionDispersion {
+    /// Pore water velocity $v$.
+    velocity: f64,
+    /// Dispersion coefficient $D$.
+    dispersion: f64,
+    /// Retardation factor $R$ from sorption.
+    retardation: f64,
+    /// First-order decay rate $\lambda$.
+    decay: f64,
+    /// Condition at the inlet.
+    inlet: Inlet,
+    /// Length of the column with a zero concentration gradient at the outlet, infinite for a semi-infinite column.
+    column_length: f64,
+    /// Duration of the injection, infinite for a continuous injection.
+    pulse_duration: f64,
+}
+
+impl AdvectionDispersion {
+    /// Continuous injection at the inlet of a semi-infinite column with a prescribed concentration.
+    pub fn new(velocity: f64, dispersion: f64) -> Self {
+        assert!(velocity > 0.0, "Velocity must be positive");
+        assert!(dispersion > 0.0, "Dispersion coefficient must be positive");
+
+        Self {
+            velocity,
+            dispersion,
+            retardation: 1.0,
+            decay: 0.0,
+            inlet: Inlet::default(),
+            column_length: f64::INFINITY,
+            pulse_duration: f64::INFINITY,
+        }
+    }
+
+    /// Set the retardation factor $R \geq 1$ from sorption.
+    pub fn with_retardation(mut self, retardation: f64) -> Self {
+        assert!(retardation >= 1.0, "Retardation factor can't be below one");
+        self.retardation = retardation;
+
+        self
+    }
+
+    /// Set the first-order decay rate $\lambda$, applied to both the dissolved and sorbed solute.
+    pub fn with_decay(mut self, decay: f64) -> Self {
+        assert!(decay >= 0.0, "Decay rate can't be negative");
+        self.decay = decay;
+
+        self
+    }
+
+    /// Set the condition at the inlet.
+    pub fn with_inlet(mut self, inlet: Inlet) -> Self {
+        self.inlet = inlet;
+
+        self
+    }
+
+    /// Set the length of a finite column, with a zero concentration gradient at the outlet.
+    pub fn with_column_length(mut self, column_length: f64) -> Self {
+        assert!(column_length > 0.0, "Column length must be positive");
+        self.column_length = column_length;
+
+        self
+    }
+
+    /// Set the duration of the injection, after which clean water enters the column.
+    pub fn with_pulse_duration(mut self, pulse_duration: f64) -> Self {
+        assert!(pulse_duration > 0.0, "Pulse duration must be positive");
+        self.pulse_duration = pulse_duration;
+
+        self
+    }
+
+    /// Pore water velocity $v$.
+    pub fn velocity(&self) -> f64 {
+        self.velocity
+    }
+
+    /// Dispersion coefficient $D$.
+    pub fn dispersion(&self) -> f64 {
+        self.dispersion
+    }
+
+    /// Retardation factor $R$.
+    pub fn retardation(&self) -> f64 {
+        self.retardation
+    }
+
+    /// First-order decay rate $\lambda$.
+    pub fn decay(&self) -> f64 {
+        self.decay
+    }
+
+    /// Condition at the inlet.
+    pub fn inlet(&self) -> Inlet {
+        self.inlet
+    }
+
+    /// Length of the column, infinite for a semi-infinite column.
+    pub fn column_length(&self) -> f64 {
+        self.column_length
+    }
+
+    /// Duration of the injection, infinite for a continuous injection.
+    pub fn pulse_duration(&self) -> f64 {
+        self.pulse_duration
+    }
+
+    /// Peclet number $v x / D$ at distance `x`, dispersion dominates below about one.
+    pub fn peclet_number(&self, x: f64) -> f64 {
+        self.velocity * x / self.dispersion
+    }
+
+    /// Evaluate the transform of the relative concentration at distance `x` from the inlet.
+    pub fn concentration_transform(&self, x: f64, s: Complex<f64>) -> Complex<f64> {
+        assert!(
+            (0.0..=self.column_length).contains(&x),
+            "Distance must be inside the column"
+        );
+
+        let (v, d) = (self.velocity, self.dispersion);
+        let root = (v * v + 4.0 * d * self.retardation * (s + self.decay)).sqrt();
+        let (decaying, growing) = ((v - root) / (2.0 * d), (v + root) / (2.0 * d));
+
+        // Reflection of the decaying solution at the outlet, `e^(r+ (x - L))` is scaled by `e^(r- L)`
+        let ratio = decaying / growing;
+        let (reflected, inlet_reflected) = if self.column_length.is_finite() {
+            let length = self.column_length;
+            (
+                ratio * (decaying * length + growing * (x - length)).exp(),
+                ratio * ((decaying - growing) * length).exp(),
+            )
+        } else {
+            (Complex::from(0.0), Complex::from(0.0))
+        };
+        let profile = (decaying * x).exp() - reflected;
+
+        // Profile at the inlet for each of the conditions
+        let inlet = match self.inlet {
+            Inlet::Concentration => 1.0 - inlet_reflected,
+            Inlet::Flux => {
+                1.0 - inlet_reflected
+                    - d / v * decaying * (1.0 - inlet_reflected * growing / decaying)
+            }
+        };
+        let injection = if self.pulse_duration.is_finite() {
+            (1.0 - (-s * self.pulse_duration).exp()) / s
+        } else {
+            1.0 / s
+        };
+
+        injection * profile / inlet
+    }
+
+    /// Calculate the relative concentration at distance `x` from the inlet at each of the times.
+    pub fn breakthrough(&self, x: f64, times: &[f64], max_function_evals: usize) -> Vec<f64> {
+        times
+            .iter()
+            .map(|t| {
+                crate::laplace_inversion(
+                    |s| self.concentration_transform(x, s),
+                    *t,
+                    max_function_evals,
+                )
+            })
+            .collect()
+    }
+
+    /// Calculate the relative concentration at each of the distances from the inlet at time `t`.
+    pub fn profile(&self, distances: &[f64], t: f64, max_function_evals: usize) -> Vec<f64> {
+        distances
+            .iter()
+            .map(|x| {
+                crate::laplace_inversion(
+                    |s| self.concentration_transform(*x, s),
+                    t,
+                    max_function_evals,
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::special::erfc;
+
+    #[test]
+    fn breakthrough_curves() {
+        let (velocity, dispersion, retardation) = (1.0, 0.1, 2.0);
+        let x = 5.0_f64;
+        let times = [6.0, 8.0, 10.0, 12.0, 16.0];
+        let arguments = |t: f64| {
+            let spread = 2.0 * (dispersion * retardation * t).sqrt();
+            (
+                (retardation * x - velocity * t) / spread,
+                (retardation * x + velocity * t) / spread,
+            )
+        };
+        let peclet = velocity * x / dispersion;
+
+        // Ogata-Banks solution for a prescribed concentration
+        let ogata_banks = |t: f64| {
+            let (front, back) = arguments(t);
+            0.5 * (erfc(front) + peclet.exp() * erfc(back))
+        };
+        // Lindstrom solution for a prescribed flux
+        let lindstrom = |t: f64| {
+            let (front, back) = arguments(t);
+            0.5 * erfc(front)
+                + (velocity * velocity * t / (PI * dispersion * retardation)).sqrt()
+                    * (-front * front).exp()
+                - 0.5
+                    * (1.0 + peclet + velocity * velocity * t / (dispersion * retardation))
+                    * peclet.exp()
+                    * erfc(back)
+        };
+
+        let column = AdvectionDispersion::new(velocity, dispersion).with_retardation(retardation);
+        assert_eq!(column.peclet_number(x), peclet);
+        let flux = column.with_inlet(Inlet::Flux);
+        for (t, (concentration, flux_concentration)) in times.iter().zip(
+            column
+                .breakthrough(x, &times, 100)
+                .into_iter()
+                .zip(flux.breakthrough(x, &times, 100)),
+        ) {
+            assert!(approx::relative_eq!(
+                concentration,
+                ogata_banks(*t),
+                epsilon = 1e-3
+            ));
+            assert!(approx::relative_eq!(
+                flux_concentration,
+                lindstrom(*t),
+                epsilon = 1e-3
+            ));
+
+            // Pulse is the difference of two continuous injections
+            let pulse = column.with_pulse_duration(3.0).breakthrough(x, &[*t], 100)[0];
+            assert!(approx::relative_eq!(
+                pulse,
+                ogata_banks(*t) - ogata_banks(t - 3.0),
+                epsilon = 1e-3
+            ));
+        }
+
+        // Outlet of a long column barely matters upstream, and a short column fills up
+        let long = flux.with_column_length(50.0);
+        assert!(approx::relative_eq!(
+            long.breakthrough(x, &[10.0], 100)[0],
+            lindstrom(10.0),
+            epsilon = 1e-4
+        ));
+        let short = flux.with_column_length(x);
+        let filled = short.profile(&[0.0, 2.0, x], 40.0, 100);
+        assert!(filled.iter().all(|c| (c - 1.0).abs() < 1e-4));
+
+        // Decay limits the concentration to the steady profile
+        let decaying = column.with_decay(0.05);
+        let rate = (velocity - (velocity.powi(2) + 4.0 * dispersion * retardation * 0.05).sqrt())
+            / (2.0 * dispersion);
+        assert!(approx::relative_eq!(
+            decaying.breakthrough(x, &[100.0], 100)[0],
+            (rate * x).exp(),
+            epsilon = 1e-4
+        ));
+    }
+}
@@ -0,0 +1,176 @@
+//! Inversions with times and transform arguments as quantities with units from [uom](https://docs.rs/uom).
+//!
+//! A transform written for times in minutes gives wrong results when it's inverted at times in seconds, without any error.
+//! [`CmeInverter::invert_quantity`] takes the time as a [`Time`] and passes the argument of the transform as a [`ComplexFrequency`], so the transform has to state the unit it's written in.
+//! The transform of an inverse is its integral over time, so it returns a [`ComplexTime`] in the same unit.
+//!
+//! # Example
+//!
+//! ```rust
+//! # fn main() -> Result<(), iltcme::Error> {
+//! use iltcme::CmeInverter;
+//! use uom::si::{f64::Time, frequency::cycle_per_minute, time::{minute, second}};
+//!
+//! // Decay with a time constant of a minute, written in minutes
+//! let transform = |s: iltcme::units::ComplexFrequency| {
+//!     iltcme::units::ComplexTime::new::<minute>(1.0 / (s.get::<cycle_per_minute>() + 1.0))
+//! };
+//! let result = CmeInverter::new(50)?.invert_quantity(transform, Time::new::<second>(90.0))?;
+//! approx::assert_relative_eq!(result, (-1.5_f64).exp(), epsilon = 1e-3);
+//! # Ok(())
+//! # }
+//! ```
+
+use nalgebra::Complex;
+use uom::{
+    si::{
+        f64::{Frequency, Time},
+        frequency::{self, hertz},
+        time::{self, second},
+    },
+    Conversion,
+};
+
+use crate::{CmeInverter, Error};
+
+/// Complex Laplace transform argument, a frequency.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComplexFrequency {
+    /// Value in hertz.
+    hertz: Complex<f64>,
+}
+
+impl ComplexFrequency {
+    /// Frequency with the value in the unit `N`.
+    pub fn new<N>(value: Complex<f64>) -> Self
+    where
+        N: frequency::Unit + Conversion<f64, T = f64>,
+    {
+        Self {
+            hertz: Complex::new(
+                Frequency::new::<N>(value.re).get::<hertz>(),
+                Frequency::new::<N>(value.im).get::<hertz>(),
+            ),
+        }
+    }
+
+    /// Value in the unit `N`.
+    pub fn get<N>(&self) -> Complex<f64>
+    where
+        N: frequency::Unit + Conversion<f64, T = f64>,
+    {
+        Complex::new(self.re().get::<N>(), self.im().get::<N>())
+    }
+
+    /// Real part.
+    pub fn re(&self) -> Frequency {
+        Frequency::new::<hertz>(self.hertz.re)
+    }
+
+    /// Imaginary part.
+    pub fn im(&self) -> Frequency {
+        Frequency::new::<hertz>(self.hertz.im)
+    }
+}
+
+/// Complex value of a transform, a time for an inverse without units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComplexTime {
+    /// Value in seconds.
+    seconds: Complex<f64>,
+}
+
+impl ComplexTime {
+    /// Time with the value in the unit `N`.
+    pub fn new<N>(value: Complex<f64>) -> Self
+    where
+        N: time::Unit + Conversion<f64, T = f64>,
+    {
+        Self {
+            seconds: Complex::new(
+                Time::new::<N>(value.re).get::<second>(),
+                Time::new::<N>(value.im).get::<second>(),
+            ),
+        }
+    }
+
+    /// Value in the unit `N`.
+    pub fn get<N>(&self) -> Complex<f64>
+    where
+        N: time::Unit + Conversion<f64, T = f64>,
+    {
+        Complex::new(self.re().get::<N>(), self.im().get::<N>())
+    }
+
+    /// Real part.
+    pub fn re(&self) -> Time {
+        Time::new::<second>(self.seconds.re)
+    }
+
+    /// Imaginary part.
+    pub fn im(&self) -> Time {
+        Time::new::<second>(self.seconds.im)
+    }
+}
+
+impl CmeInverter {
+    /// Calculate the Laplace inversion for a function of a complex frequency at the time `t`.
+    ///
+    /// The inverse has the unit of the transform divided by the unit of time, so it's without units when the transform returns a [`ComplexTime`].
+    pub fn invert_quantity(
+        &self,
+        mut laplace_func: impl FnMut(ComplexFrequency) -> ComplexTime,
+        t: Time,
+    ) -> Result<f64, Error> {
+        self.invert(
+            |s| laplace_func(ComplexFrequency { hertz: s }).seconds,
+            t.get::<second>(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uom::si::{
+        frequency::{cycle_per_hour, cycle_per_minute},
+        time::{hour, millisecond, minute},
+    };
+
+    use super::*;
+
+    #[test]
+    fn units() {
+        let inverter = CmeInverter::new(100).unwrap();
+        // Decay with a time constant of a minute
+        let exponential = |s: Complex<f64>| 1.0 / (s + 1.0);
+        let in_minutes = |s: ComplexFrequency| {
+            ComplexTime::new::<minute>(exponential(s.get::<cycle_per_minute>()))
+        };
+
+        // Same result whatever the units of the time, up to the rounding of the scaled nodes
+        let expected = inverter.invert(exponential, 1.5).unwrap();
+        for t in [
+            Time::new::<minute>(1.5),
+            Time::new::<second>(90.0),
+            Time::new::<millisecond>(90_000.0),
+            Time::new::<hour>(0.025),
+        ] {
+            let result = inverter.invert_quantity(in_minutes, t).unwrap();
+            assert!(approx::relative_eq!(result, expected, max_relative = 1e-9));
+        }
+
+        // Or of the transform
+        let in_hours = |s: ComplexFrequency| {
+            ComplexTime::new::<hour>(1.0 / (s.get::<cycle_per_hour>() + 60.0))
+        };
+        let result = inverter
+            .invert_quantity(in_hours, Time::new::<second>(90.0))
+            .unwrap();
+        assert!(approx::relative_eq!(result, expected, max_relative = 1e-9));
+
+        assert_eq!(
+            inverter.invert_quantity(in_minutes, Time::new::<second>(0.0)),
+            Err(Error::NonPositiveTime(0.0))
+        );
+    }
+}
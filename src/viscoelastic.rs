@@ -0,0 +1,143 @@
+//! Relaxation modulus, creep compliance and complex modulus of linear viscoelastic materials.
+//!
+//! With the correspondence principle a viscoelastic problem becomes an elastic one in the Laplace domain, where the modulus is replaced by the operational modulus $E^*(s) = s \bar{E}(s)$, the Carson transform of the relaxation modulus $E(t)$.
+//! The transforms of the relaxation modulus and the creep compliance $J(t)$ are related by
+//!
+//! $$
+//! \bar{J}(s) = \frac{1}{s^2 \bar{E}(s)} = \frac{1}{s E^*(s)}
+//! $$
+//!
+//! and the complex modulus measured in dynamic tests is $E^*(i \omega)$, with the storage modulus as real part and the loss modulus as imaginary part.
+//! So any of the three describes the material and the others follow from an inversion.
+
+use nalgebra::Complex;
+
+use crate::BoxedLaplaceFunc;
+
+/// Linear viscoelastic material described by its operational modulus $E^*(s) = s \bar{E}(s)$.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::viscoelastic::Viscoelastic;
+///
+/// // Maxwell material with a modulus of 2 and a relaxation time of 0.5
+/// let material = Viscoelastic::from_relaxation_transform(|s| 2.0 / (s + 2.0));
+/// let compliance = material.creep_compliance(&[1.0], 100);
+/// approx::assert_relative_eq!(compliance[0], 0.5 + 1.0, epsilon = 0.001);
+/// # }
+/// ```
+pub struct Viscoelastic<'a> {
+    /// Operational modulus $E^*(s)$.
+    modulus: BoxedLaplaceFunc<'a>,
+}
+
+impl<'a> Viscoelastic<'a> {
+    /// Construct from the operational modulus $E^*(s) = s \bar{E}(s)$, which is also the complex modulus at $s = i \omega$.
+    pub fn from_modulus(modulus: impl Fn(Complex<f64>) -> Complex<f64> + 'a) -> Self {
+        Self {
+            modulus: Box::new(modulus),
+        }
+    }
+
+    /// Construct from the Laplace transform $\bar{E}(s)$ of the relaxation modulus.
+    pub fn from_relaxation_transform(
+        relaxation_transform: impl Fn(Complex<f64>) -> Complex<f64> + 'a,
+    ) -> Self {
+        Self::from_modulus(move |s| s * relaxation_transform(s))
+    }
+
+    /// Construct from the Laplace transform $\bar{J}(s)$ of the creep compliance.
+    pub fn from_compliance_transform(
+        compliance_transform: impl Fn(Complex<f64>) -> Complex<f64> + 'a,
+    ) -> Self {
+        Self::from_modulus(move |s| 1.0 / (s * compliance_transform(s)))
+    }
+
+    /// Evaluate the operational modulus $E^*(s)$.
+    pub fn modulus(&self, s: Complex<f64>) -> Complex<f64> {
+        (self.modulus)(s)
+    }
+
+    /// Complex modulus $E^*(i \omega)$ at angular frequency `omega`, the storage modulus is the real part and the loss modulus the imaginary part.
+    pub fn complex_modulus(&self, omega: f64) -> Complex<f64> {
+        self.modulus(Complex::new(0.0, omega))
+    }
+
+    /// Evaluate the Laplace transform $\bar{E}(s) = E^*(s) / s$ of the relaxation modulus.
+    pub fn relaxation_transform(&self, s: Complex<f64>) -> Complex<f64> {
+        self.modulus(s) / s
+    }
+
+    /// Evaluate the Laplace transform $\bar{J}(s) = 1 / (s^2 \bar{E}(s))$ of the creep compliance.
+    pub fn compliance_transform(&self, s: Complex<f64>) -> Complex<f64> {
+        1.0 / (s * self.modulus(s))
+    }
+
+    /// Calculate the stress response $E(t)$ to a unit step in strain at each of the times.
+    pub fn relaxation_modulus(&self, times: &[f64], max_function_evals: usize) -> Vec<f64> {
+        times
+            .iter()
+            .map(|t| {
+                crate::laplace_inversion(|s| self.relaxation_transform(s), *t, max_function_evals)
+            })
+            .collect()
+    }
+
+    /// Calculate the strain response $J(t)$ to a unit step in stress at each of the times.
+    pub fn creep_compliance(&self, times: &[f64], max_function_evals: usize) -> Vec<f64> {
+        times
+            .iter()
+            .map(|t| {
+                crate::laplace_inversion(|s| self.compliance_transform(s), *t, max_function_evals)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn correspondence() {
+        // Standard linear solid with an equilibrium modulus of 1, a relaxing modulus of 3 and a relaxation time of 0.5
+        let (equilibrium, relaxing, tau) = (1.0, 3.0, 0.5);
+        let relaxation = |t: f64| equilibrium + relaxing * (-t / tau).exp();
+        // Creep is slower, with the retardation time `tau E(0) / E(inf)`
+        let glassy = equilibrium + relaxing;
+        let creep = |t: f64| {
+            1.0 / equilibrium
+                - (1.0 / equilibrium - 1.0 / glassy) * (-t * equilibrium / (tau * glassy)).exp()
+        };
+        let times = [0.1, 0.5, 1.0, 3.0];
+
+        let from_relaxation = Viscoelastic::from_relaxation_transform(|s| {
+            equilibrium / s + relaxing / (s + 1.0 / tau)
+        });
+        let from_compliance =
+            Viscoelastic::from_compliance_transform(|s| from_relaxation.compliance_transform(s));
+        for material in [&from_relaxation, &from_compliance] {
+            let moduli = material.relaxation_modulus(&times, 100);
+            let compliances = material.creep_compliance(&times, 100);
+            for ((t, modulus), compliance) in times.iter().zip(moduli).zip(compliances) {
+                assert!(approx::relative_eq!(
+                    modulus,
+                    relaxation(*t),
+                    epsilon = 1e-3
+                ));
+                assert!(approx::relative_eq!(compliance, creep(*t), epsilon = 1e-3));
+            }
+        }
+
+        // Storage and loss modulus
+        let omega = 2.0_f64;
+        let product = omega * tau;
+        let expected = Complex::new(
+            equilibrium + relaxing * product.powi(2) / (1.0 + product.powi(2)),
+            relaxing * product / (1.0 + product.powi(2)),
+        );
+        assert!((from_relaxation.complex_modulus(omega) - expected).norm() < 1e-12);
+    }
+}
@@ -0,0 +1,265 @@
+//! Drawdown around a well pumping at a constant rate, the well functions of hydrogeology.
+//!
+//! Radial flow in a confined aquifer with transmissivity $T$ and storativity $S$ has the transform of the drawdown
+//!
+//! $$
+//! \bar{s}(r, p) = \frac{Q}{2 \pi T p} K_0(q r), \quad q = \sqrt{\frac{p S}{T} + \frac{1}{B^2}}
+//! $$
+//!
+//! for a line source, the Theis solution, where leakage through an aquitard with leakage factor $B$ gives the Hantush-Jacob solution.
+//! A well with a finite radius $r_w$ and casing radius $r_c$ first delivers water from its storage, the drawdown in the well also includes the skin effect.
+//! The transforms avoid the tabulated well functions $W(u)$ and $W(u, r / B)$, and their extensions for wellbore storage.
+
+use std::f64::consts::PI;
+
+use nalgebra::Complex;
+
+use crate::special::bessel_ke;
+
+/// Well pumping at a constant rate from a confined or leaky aquifer.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+/// use iltcme::wells::{invert_drawdown, Well};
+///
+/// // Pumping 0.01 m³/s, the drawdown at 10 m approaches the steady state of a leaky aquifer
+/// let well = Well::new(0.01, 1e-3, 1e-4).with_leakage_factor(100.0);
+/// let drawdown = invert_drawdown(&well, 10.0, &[1e6], 100);
+/// let steady = 0.01 / (2.0 * std::f64::consts::PI * 1e-3) * 2.427_069_024_702_017;
+/// approx::assert_relative_eq!(drawdown[0], steady, epsilon = 1e-3);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Well {
+    /// Constant discharge $Q$.
+    discharge: f64,
+    /// Transmissivity $T$ of the aquifer.
+    transmissivity: f64,
+    /// Storativity $S$ of the aquifer.
+    storativity: f64,
+    /// Leakage factor $B$, infinite for a confined aquifer.
+    leakage_factor: f64,
+    /// Radius $r_w$ of the well screen, zero for a line source.
+    well_radius: f64,
+    /// Radius $r_c$ of the casing where the water level changes, zero without wellbore storage.
+    casing_radius: f64,
+    /// Skin factor of the damaged or stimulated zone around the well.
+    skin: f64,
+}
+
+impl Well {
+    /// Line source pumping `discharge` from a confined aquifer, the Theis solution.
+    pub fn new(discharge: f64, transmissivity: f64, storativity: f64) -> Self {
+        assert!(transmissivity > 0.0, "Transmissivity must be positive");
+        assert!(storativity > 0.0, "Storativity must be positive");
+
+        Self {
+            discharge,
+            transmissivity,
+            storativity,
+            leakage_factor: f64::INFINITY,
+            well_radius: 0.0,
+            casing_radius: 0.0,
+            skin: 0.0,
+        }
+    }
+
+    /// Set the leakage factor $B = \sqrt{T b' / K'}$ of the aquitard with thickness $b'$ and conductivity $K'$, the Hantush-Jacob solution.
+    pub fn with_leakage_factor(mut self, leakage_factor: f64) -> Self {
+        assert!(leakage_factor > 0.0, "Leakage factor must be positive");
+        self.leakage_factor = leakage_factor;
+
+        self
+    }
+
+    /// Set the radius $r_w$ of the well screen and the radius $r_c$ of the casing, whose storage delivers water before the aquifer does.
+    pub fn with_wellbore_storage(mut self, well_radius: f64, casing_radius: f64) -> Self {
+        assert!(well_radius > 0.0, "Well radius must be positive");
+        assert!(casing_radius >= 0.0, "Casing radius can't be negative");
+        self.well_radius = well_radius;
+        self.casing_radius = casing_radius;
+
+        self
+    }
+
+    /// Set the skin factor, the additional drawdown in the well is $\frac{Q \sigma}{2 \pi T}$ once the flow comes from the aquifer.
+    ///
+    /// Only applies to a well with a radius, see [`Well::with_wellbore_storage`].
+    pub fn with_skin(mut self, skin: f64) -> Self {
+        self.skin = skin;
+
+        self
+    }
+
+    /// Constant discharge $Q$.
+    pub fn discharge(&self) -> f64 {
+        self.discharge
+    }
+
+    /// Transmissivity $T$ of the aquifer.
+    pub fn transmissivity(&self) -> f64 {
+        self.transmissivity
+    }
+
+    /// Storativity $S$ of the aquifer.
+    pub fn storativity(&self) -> f64 {
+        self.storativity
+    }
+
+    /// Leakage factor $B$, infinite for a confined aquifer.
+    pub fn leakage_factor(&self) -> f64 {
+        self.leakage_factor
+    }
+
+    /// Radius $r_w$ of the well screen, zero for a line source.
+    pub fn well_radius(&self) -> f64 {
+        self.well_radius
+    }
+
+    /// Radius $r_c$ of the casing, zero without wellbore storage.
+    pub fn casing_radius(&self) -> f64 {
+        self.casing_radius
+    }
+
+    /// Skin factor of the zone around the well.
+    pub fn skin(&self) -> f64 {
+        self.skin
+    }
+
+    /// Evaluate the transform of the drawdown at distance `radius` from the center of the well.
+    ///
+    /// Inside the well, for a radius up to the well radius, this is the drawdown of the water level in the well including the skin effect.
+    pub fn drawdown_transform(&self, radius: f64, p: Complex<f64>) -> Complex<f64> {
+        let q = (p * self.storativity / self.transmissivity + self.leakage_factor.powi(-2)).sqrt();
+        if self.well_radius == 0.0 {
+            return self.discharge / (2.0 * PI * self.transmissivity * p)
+                * (-q * radius).exp()
+                * bessel_ke(0, q * radius);
+        }
+
+        // Drawdown `A K_0(q r)` in the aquifer, scaled by `e^(q r_w)`
+        let x = q * self.well_radius;
+        let (k0, k1) = (bessel_ke(0, x), bessel_ke(1, x));
+        let well = k0 + self.skin * x * k1;
+        let amplitude = self.discharge
+            / p
+            / (2.0 * PI * self.transmissivity * x * k1
+                + PI * self.casing_radius.powi(2) * p * well);
+
+        if radius <= self.well_radius {
+            amplitude * well
+        } else {
+            amplitude * (-q * (radius - self.well_radius)).exp() * bessel_ke(0, q * radius)
+        }
+    }
+
+    /// Steady state drawdown at distance `radius` of a well in a leaky aquifer, $\frac{Q}{2 \pi T} K_0(r / B)$.
+    ///
+    /// Infinite for a confined aquifer, where the drawdown keeps growing.
+    pub fn steady_drawdown(&self, radius: f64) -> f64 {
+        let x = Complex::from(radius / self.leakage_factor);
+        if x.re == 0.0 {
+            return f64::INFINITY;
+        }
+
+        self.discharge / (2.0 * PI * self.transmissivity) * ((-x).exp() * bessel_ke(0, x)).re
+    }
+}
+
+/// Calculate the drawdown of the well at distance `radius` at each of the times.
+pub fn invert_drawdown(
+    well: &Well,
+    radius: f64,
+    times: &[f64],
+    max_function_evals: usize,
+) -> Vec<f64> {
+    times
+        .iter()
+        .map(|t| {
+            crate::laplace_inversion(
+                |p| well.drawdown_transform(radius, p),
+                *t,
+                max_function_evals,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Theis well function, the exponential integral `E_1(u)` from its series.
+    fn theis(u: f64) -> f64 {
+        let mut term = 1.0;
+        let mut sum = -0.577_215_664_901_532_9 - u.ln();
+        for k in 1..100 {
+            term *= -u / k as f64;
+            sum -= term / k as f64;
+        }
+
+        sum
+    }
+
+    #[test]
+    fn well_functions() {
+        let (discharge, transmissivity, storativity) = (0.02, 5e-3, 2e-4);
+        let scale = discharge / (4.0 * PI * transmissivity);
+        let theis_drawdown =
+            |r: f64, t: f64| scale * theis(r * r * storativity / (4.0 * transmissivity * t));
+
+        // Theis solution
+        let well = Well::new(discharge, transmissivity, storativity);
+        let times = [10.0, 100.0, 1e4];
+        for radius in [1.0, 10.0, 30.0] {
+            for (t, drawdown) in times
+                .iter()
+                .zip(invert_drawdown(&well, radius, &times, 100))
+            {
+                assert!(approx::relative_eq!(
+                    drawdown,
+                    theis_drawdown(radius, *t),
+                    max_relative = 1e-3
+                ));
+            }
+        }
+
+        // Leakage doesn't matter at first, but limits the drawdown
+        let leaky = well.with_leakage_factor(200.0);
+        let drawdown = invert_drawdown(&leaky, 10.0, &[1.0, 1e7], 100);
+        assert!(approx::relative_eq!(
+            drawdown[0],
+            theis_drawdown(10.0, 1.0),
+            max_relative = 1e-3
+        ));
+        assert!(approx::relative_eq!(
+            drawdown[1],
+            leaky.steady_drawdown(10.0),
+            max_relative = 1e-3
+        ));
+        assert_eq!(well.steady_drawdown(10.0), f64::INFINITY);
+
+        // Wellbore storage delivers the water at first, later the skin adds a constant drawdown in the well
+        let stored = well.with_wellbore_storage(0.1, 0.1).with_skin(2.0);
+        let drawdown = invert_drawdown(&stored, 0.1, &[1e-3, 1e6], 100);
+        assert!(approx::relative_eq!(
+            drawdown[0],
+            discharge * 1e-3 / (PI * 0.01),
+            max_relative = 0.01
+        ));
+        assert!(approx::relative_eq!(
+            drawdown[1],
+            theis_drawdown(0.1, 1e6) + 2.0 * scale * 2.0,
+            max_relative = 1e-3
+        ));
+        // The aquifer outside the well doesn't see the skin
+        assert!(approx::relative_eq!(
+            invert_drawdown(&stored, 10.0, &[1e6], 100)[0],
+            theis_drawdown(10.0, 1e6),
+            max_relative = 1e-3
+        ));
+    }
+}
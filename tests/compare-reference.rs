@@ -0,0 +1,119 @@
+use std::sync::OnceLock;
+
+use iltcme::{coefficients::CmeParams, reference};
+use nalgebra::{Complex, ComplexField};
+
+/// How much worse the result is allowed to be compared to the reference result.
+const ALLOWED_ERROR: f64 = 1e-8;
+
+/// Values to test.
+const TEST_VALUES: [f64; 13] = [
+    1.0, 1e-3, 1e-5, 1e-8, 1e-15, 1e+3, 1e+5, 1e+8, 1e+15, 3.0, -1.0, -1e-8, -1e+8,
+];
+
+#[test]
+fn exponential() {
+    for max_fn_evals in 2..500 {
+        compare_values(
+            &TEST_VALUES,
+            |x| (-x).exp(),
+            |s| (s + 1.0).recip(),
+            max_fn_evals,
+        );
+    }
+}
+
+#[test]
+fn sine() {
+    for max_fn_evals in 2..500 {
+        compare_values(
+            &TEST_VALUES,
+            |x| x.sin(),
+            |s| (1.0 + s.powi(2)).recip(),
+            max_fn_evals,
+        );
+    }
+}
+
+#[test]
+fn squarewave() {
+    for max_fn_evals in 2..500 {
+        compare_values(
+            &[1.0, 2.0, 2.5, 3.0, 4.1, 5.01],
+            |x| x.floor() % 2.0,
+            |s| s.recip() * (1.0 + s.exp()).recip(),
+            max_fn_evals,
+        );
+    }
+}
+
+#[test]
+fn staircase() {
+    for max_fn_evals in 2..500 {
+        compare_values(
+            &[1.0, 2.0, 2.5, 3.0, 4.1, 5.01],
+            |x| x.floor(),
+            |s| s.recip() * (s.exp() - 1.0).recip(),
+            max_fn_evals,
+        );
+    }
+}
+
+fn compare_values(
+    x_values: &[f64],
+    expected_fn: impl Fn(f64) -> f64,
+    func: impl Fn(Complex<f64>) -> Complex<f64>,
+    max_fn_evals: usize,
+) {
+    let params = reference::steepest(params(), max_fn_evals).unwrap();
+
+    for x in x_values {
+        // Extended precision evaluation of the published distribution
+        let reference = reference::laplace_inversion(&func, *x, params);
+        let result = iltcme::laplace_inversion(&func, *x, max_fn_evals);
+        let real = expected_fn(*x);
+
+        // Ensure that the result is closer or equal to the reference solution
+        let reference_dist = (real - reference).abs();
+        let result_dist = (real - result).abs();
+        assert!(result_dist <= reference_dist + ALLOWED_ERROR, "Result is worse than reference result:\n\tReference Delta: {reference_dist}\n\tResult Delta   : {result_dist}\n\tReference      : {reference}\n\tResult         : {result}\n\tExpected       : {real}\n\tFn evals       : {max_fn_evals}\n\tX              : {x}");
+    }
+}
+
+/// Distributions of the published `iltcme.json`, parsed once.
+///
+/// Without the embedded coefficients they're installed as the table of the inversions as well.
+fn params() -> &'static [CmeParams] {
+    static PARAMS: OnceLock<Vec<CmeParams>> = OnceLock::new();
+
+    PARAMS.get_or_init(|| {
+        let json: Vec<serde_json::Value> =
+            serde_json::from_str(include_str!("../iltcme.json")).unwrap();
+        let floats = |value: &serde_json::Value| -> Vec<f64> {
+            value
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|x| x.as_f64().unwrap())
+                .collect()
+        };
+
+        let params = json
+            .iter()
+            .map(|p| CmeParams {
+                n: p["n"].as_u64().unwrap() as usize,
+                a: floats(&p["a"]),
+                b: floats(&p["b"]),
+                c: p["c"].as_f64().unwrap(),
+                omega: p["omega"].as_f64().unwrap(),
+                mu1: p["mu1"].as_f64().unwrap(),
+                cv2: p["cv2"].as_f64().unwrap(),
+            })
+            .collect::<Vec<_>>();
+
+        #[cfg(not(feature = "embedded"))]
+        iltcme::coefficients::install(iltcme::coefficients::CmeTable::new(params.clone())).unwrap();
+
+        params
+    })
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "macros")]
+
+use iltcme::laplace;
+use nalgebra::{Complex, ComplexField};
+
+/// Transform written with the macro.
+type Transform = Box<dyn Fn(Complex<f64>) -> Complex<f64>>;
+
+#[test]
+fn expressions() {
+    let s = Complex::new(0.7, 1.3);
+    let (zeta, omega) = (0.1, 2.0);
+    let shift = Complex::new(0.5, -0.25);
+
+    let cases: [(Transform, Complex<f64>); 8] = [
+        (Box::new(laplace!(1 / (s ^ 2 + 1))), 1.0 / (s * s + 1.0)),
+        // Powers bind stronger than signs and are right associative
+        (Box::new(laplace!(-s ^ 2 + 2 ^ 3 ^ 2)), -(s * s) + 512.0),
+        (Box::new(laplace!(s ^ -2 * 1e-3)), 1e-3 / (s * s)),
+        (Box::new(laplace!(s ^ 0.5)), s.sqrt()),
+        // Imaginary literals
+        (
+            Box::new(laplace!(1 / (s - 2i) + 0.5i * i)),
+            1.0 / (s - Complex::new(0.0, 2.0)) - 0.5,
+        ),
+        (
+            Box::new(laplace!(
+                omega ^ 2 / (s ^ 2 + 2 * zeta * omega * s + omega ^ 2)
+            )),
+            omega * omega / (s * s + 2.0 * zeta * omega * s + omega * omega),
+        ),
+        (
+            Box::new(laplace!(exp(-s * pi) / (s + shift))),
+            (-s * std::f64::consts::PI).exp() / (s + shift),
+        ),
+        (
+            Box::new(laplace!(ln(s) * sqrt(s) + tanh(e / s) - atan(s))),
+            s.ln() * s.sqrt() + (std::f64::consts::E / s).tanh() - s.atan(),
+        ),
+    ];
+    for (index, (transform, expected)) in cases.iter().enumerate() {
+        let value = transform(s);
+        assert!(
+            (value - expected).norm() < 1e-12,
+            "Case {index} gives {value} instead of {expected}"
+        );
+    }
+
+    let result = iltcme::laplace_inversion(laplace!(1 / (s ^ 2 + 1)), 1.0, 100);
+    assert!(approx::relative_eq!(result, 1.0_f64.sin(), epsilon = 1e-4));
+}